@@ -0,0 +1,72 @@
+//! Refreshes an offline snapshot from the API, for a scheduled job keeping the dataset read
+//! with `load_snapshot` or `map_snapshot` current.
+//!
+//! ```text
+//! HOLIDAYAPI_KEY=<key> cargo run --example update_snapshot -- holidays.json us,gb 2024-2025
+//! ```
+//!
+//! The years are a single year or an inclusive range. Exits with status `1` when the update
+//! fails, leaving the snapshot untouched.
+
+use std::{ops::RangeInclusive, process::ExitCode};
+
+use holidayapi_rust::prelude::*;
+
+const USAGE: &str =
+    "usage: update_snapshot <path> <countries, comma separated> <year or first-last>";
+
+fn years(arg: &str) -> Option<RangeInclusive<i32>> {
+    match arg.split_once('-') {
+        Some((first, last)) => Some(first.trim().parse().ok()?..=last.trim().parse().ok()?),
+        None => {
+            let year = arg.trim().parse().ok()?;
+            Some(year..=year)
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [path, countries, years_arg] = args.as_slice() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+    let Some(years) = years(years_arg) else {
+        eprintln!("invalid years {:?}\n{}", years_arg, USAGE);
+        return ExitCode::FAILURE;
+    };
+    let Ok(key) = std::env::var("HOLIDAYAPI_KEY") else {
+        eprintln!("HOLIDAYAPI_KEY is not set");
+        return ExitCode::FAILURE;
+    };
+    let api = match HolidayAPI::new(&key) {
+        Ok(api) => api,
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let countries: Vec<&str> = countries.split(',').map(str::trim).collect();
+    match api.update_snapshot(path, &countries, years).await {
+        Ok(update) if update.is_changed() => {
+            for (country, year) in &update.added {
+                println!("added {} {}", country, year);
+            }
+            for (country, year, _) in &update.changed {
+                println!("changed {} {}", country, year);
+            }
+            println!("wrote revision {}", update.snapshot.revision);
+            ExitCode::SUCCESS
+        }
+        Ok(update) => {
+            println!("unchanged at revision {}", update.snapshot.revision);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
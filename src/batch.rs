@@ -0,0 +1,386 @@
+use futures_util::{
+    future,
+    stream::{self, BoxStream, StreamExt},
+};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    calendar::DateRange,
+    country::CountryCode,
+    date,
+    requests::Request,
+    responses::{
+        CountriesResponse, HolidaysResponse, LanguagesResponse, WorkdayResponse, WorkdaysResponse,
+    },
+    scheduler::Scheduler,
+    transport::BoxFuture,
+    HolidayAPI, HolidayAPIError,
+};
+
+/// Request prepared to be sent by [`HolidayAPI::get_many`]: any [`Request`], or an
+/// [`AnyRequest`] to mix endpoints in one batch.
+pub trait Prepared: Send + 'static {
+    type Output: Send + 'static;
+
+    /// Sends the request and parses its response, see [`Request::get_full`].
+    fn send(self) -> BoxFuture<'static, Result<Self::Output, HolidayAPIError>>;
+
+    /// Queues the request on `scheduler`, see [`Scheduler::enqueue`].
+    fn schedule(
+        self,
+        scheduler: &Scheduler,
+    ) -> BoxFuture<'static, Result<Self::Output, HolidayAPIError>>;
+}
+
+impl<T> Prepared for Request<T>
+where
+    T: Clone + DeserializeOwned + Send + Sync + 'static,
+{
+    type Output = T;
+
+    fn send(self) -> BoxFuture<'static, Result<T, HolidayAPIError>> {
+        Box::pin(self.get_full())
+    }
+
+    fn schedule(self, scheduler: &Scheduler) -> BoxFuture<'static, Result<T, HolidayAPIError>> {
+        Box::pin(scheduler.enqueue(self))
+    }
+}
+
+/// Request of any endpoint, to send requests of different endpoints with
+/// [`HolidayAPI::get_many`].
+#[derive(Debug, Clone)]
+pub enum AnyRequest {
+    Countries(Request<CountriesResponse>),
+    Holidays(Request<HolidaysResponse>),
+    Languages(Request<LanguagesResponse>),
+    Workday(Request<WorkdayResponse>),
+    Workdays(Request<WorkdaysResponse>),
+}
+
+/// Parsed response of an [`AnyRequest`], of the same variant as the request.
+#[derive(Debug, Clone)]
+pub enum AnyResponse {
+    Countries(CountriesResponse),
+    Holidays(HolidaysResponse),
+    Languages(LanguagesResponse),
+    Workday(WorkdayResponse),
+    Workdays(WorkdaysResponse),
+}
+
+macro_rules! impl_any {
+    ($($variant:ident($response:ty) => $into:ident),* $(,)?) => {
+        $(
+            impl From<Request<$response>> for AnyRequest {
+                fn from(request: Request<$response>) -> Self {
+                    AnyRequest::$variant(request)
+                }
+            }
+        )*
+
+        impl AnyResponse {
+            $(
+                #[doc = concat!("The response, if it is a `", stringify!($response), "`.")]
+                pub fn $into(self) -> Option<$response> {
+                    match self {
+                        AnyResponse::$variant(response) => Some(response),
+                        _ => None,
+                    }
+                }
+            )*
+        }
+
+        impl Prepared for AnyRequest {
+            type Output = AnyResponse;
+
+            fn send(self) -> BoxFuture<'static, Result<AnyResponse, HolidayAPIError>> {
+                match self {
+                    $(
+                        AnyRequest::$variant(request) => Box::pin(async move {
+                            request.get_full().await.map(AnyResponse::$variant)
+                        }),
+                    )*
+                }
+            }
+
+            fn schedule(
+                self,
+                scheduler: &Scheduler,
+            ) -> BoxFuture<'static, Result<AnyResponse, HolidayAPIError>> {
+                match self {
+                    $(
+                        AnyRequest::$variant(request) => {
+                            let response = scheduler.enqueue(request);
+                            Box::pin(async move { response.await.map(AnyResponse::$variant) })
+                        }
+                    )*
+                }
+            }
+        }
+    };
+}
+
+impl_any!(
+    Countries(CountriesResponse) => into_countries,
+    Holidays(HolidaysResponse) => into_holidays,
+    Languages(LanguagesResponse) => into_languages,
+    Workday(WorkdayResponse) => into_workday,
+    Workdays(WorkdaysResponse) => into_workdays,
+);
+
+impl HolidayAPI {
+    /// Sends prepared requests concurrently and returns their results in the same order.
+    ///
+    /// At most [`HolidayAPI::PREFETCH_CONCURRENCY`] requests are in flight by default, see
+    /// [`HolidayAPI::stream_concurrency`], or fewer when limited with
+    /// [`HolidayAPI::max_concurrency`], and requests go through the client's
+    /// cache, budget and retry policy. Requests of different endpoints can be mixed by
+    /// converting them into [`AnyRequest`].
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let years = api.get_many((2020..=2022).map(|year| api.holidays("us", year))).await;
+    ///
+    ///     let mixed = api
+    ///         .get_many([
+    ///             AnyRequest::from(api.countries()),
+    ///             api.holidays("us", 2021).into(),
+    ///         ])
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn get_many<R: Prepared>(
+        &self,
+        requests: impl IntoIterator<Item = R>,
+    ) -> Vec<Result<R::Output, HolidayAPIError>> {
+        stream::iter(requests)
+            .map(Prepared::send)
+            .buffered(self.streamed(Self::PREFETCH_CONCURRENCY))
+            .collect()
+            .await
+    }
+
+    /// Sends prepared requests through `scheduler`, respecting its [`RateLimit`], and returns
+    /// their results in the same order, see [`HolidayAPI::get_many`].
+    ///
+    /// Every request is queued right away, and dispatched by the scheduler, which also pauses
+    /// them when the API rate limits the client.
+    ///
+    /// [`RateLimit`]: crate::scheduler::RateLimit
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let scheduler = Scheduler::new(RateLimit::per_second(5));
+    ///     let years = api
+    ///         .get_many_scheduled(&scheduler, (2020..=2022).map(|year| api.holidays("us", year)))
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn get_many_scheduled<R: Prepared>(
+        &self,
+        scheduler: &Scheduler,
+        requests: impl IntoIterator<Item = R>,
+    ) -> Vec<Result<R::Output, HolidayAPIError>> {
+        let queued: Vec<_> = requests
+            .into_iter()
+            .map(|request| request.schedule(scheduler))
+            .collect();
+        future::join_all(queued).await
+    }
+
+    /// Counts the working days of many date ranges of a country, yielding each range with its
+    /// result as soon as it completes, in any order.
+    ///
+    /// Requests are sent like [`HolidayAPI::get_many`], as the stream is polled.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```no_run
+    /// use chrono::NaiveDate;
+    /// use futures_util::StreamExt;
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let months = (1..=12).map(|month| {
+    ///         let start = NaiveDate::from_ymd_opt(2021, month, 1).unwrap();
+    ///         let end = start.checked_add_months(chrono::Months::new(1)).unwrap().pred_opt();
+    ///         DateRange { start, end: end.unwrap() }
+    ///     });
+    ///     let mut workdays = api.workdays_stream("us", months);
+    ///     while let Some((range, result)) = workdays.next().await {
+    ///         if let Ok(response) = result {
+    ///             println!("{}: {} working days", range.start, response.workdays);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn workdays_stream(
+        &self,
+        country: impl Into<CountryCode>,
+        ranges: impl IntoIterator<Item = DateRange>,
+    ) -> BoxStream<'static, (DateRange, Result<WorkdaysResponse, HolidayAPIError>)> {
+        let country = country.into();
+        let requests: Vec<(DateRange, Request<WorkdaysResponse>)> = ranges
+            .into_iter()
+            .map(|range| {
+                let start = range.start.format(date::FORMAT).to_string();
+                let end = range.end.format(date::FORMAT).to_string();
+                (range, self.workdays(&country, &start, &end))
+            })
+            .collect();
+        stream::iter(requests)
+            .map(|(range, request)| async move { (range, request.get_full().await) })
+            .buffer_unordered(self.streamed(Self::PREFETCH_CONCURRENCY))
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::Fixture, mock::MockTransport, test_support::EXPIRED_KEY};
+
+    #[tokio::test]
+    async fn test_get_many() {
+        let transport = MockTransport::new(Fixture::named("holidays").unwrap().mock_response())
+            .on_call(1, Fixture::named("countries").unwrap().mock_response())
+            .on_call(3, Fixture::named("error_400").unwrap().mock_response());
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+            .max_concurrency(1);
+
+        let results = api
+            .get_many([
+                AnyRequest::from(api.countries()),
+                api.holidays("us", 2021).into(),
+                api.holidays("jp", 2021).into(),
+            ])
+            .await;
+        assert_eq!(results.len(), 3);
+        let mut results = results.into_iter();
+        let countries = results.next().unwrap().unwrap();
+        assert!(countries.clone().into_holidays().is_none());
+        assert!(!countries.into_countries().unwrap().countries.is_empty());
+        assert!(results.next().unwrap().unwrap().into_holidays().is_some());
+        assert!(results.next().unwrap().is_err());
+
+        let years = api
+            .get_many((2020..=2021).map(|year| api.holidays("us", year)))
+            .await;
+        assert!(years.iter().all(Result::is_ok));
+        assert_eq!(transport.calls(), 5);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn test_get_many_scheduled() {
+        use crate::scheduler::RateLimit;
+
+        let transport = MockTransport::new(Fixture::named("holidays").unwrap().mock_response())
+            .on_call(1, Fixture::named("countries").unwrap().mock_response());
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone());
+        let scheduler = Scheduler::new(RateLimit::per_second(2));
+
+        let start = tokio::time::Instant::now();
+        let results = api
+            .get_many_scheduled(
+                &scheduler,
+                [
+                    AnyRequest::from(api.countries()),
+                    api.holidays("us", 2021).into(),
+                    api.holidays("jp", 2021).into(),
+                ],
+            )
+            .await;
+        assert!(start.elapsed() >= std::time::Duration::from_secs(1));
+        assert_eq!(transport.calls(), 3);
+        let mut results = results.into_iter();
+        assert!(results.next().unwrap().unwrap().into_countries().is_some());
+        assert!(results.all(|result| result.unwrap().into_holidays().is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_workdays_stream() {
+        let transport = MockTransport::new(Fixture::named("workdays").unwrap().mock_response())
+            .on_call(2, Fixture::named("error_500").unwrap().mock_response());
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone());
+        let day = |month, day| chrono::NaiveDate::from_ymd_opt(2021, month, day).unwrap();
+        let ranges = [
+            DateRange {
+                start: day(1, 1),
+                end: day(1, 31),
+            },
+            DateRange {
+                start: day(2, 1),
+                end: day(2, 28),
+            },
+        ];
+
+        let mut results: Vec<(DateRange, bool)> = api
+            .workdays_stream("us", ranges)
+            .map(|(range, result)| (range, result.is_ok()))
+            .collect()
+            .await;
+        results.sort();
+        assert_eq!(results, [(ranges[0], true), (ranges[1], false)]);
+        let mut ends: Vec<String> = transport
+            .requests()
+            .iter()
+            .filter_map(|url| {
+                url.query_pairs()
+                    .find(|(key, _)| key == "end")
+                    .map(|(_, value)| value.into_owned())
+            })
+            .collect();
+        ends.sort();
+        assert_eq!(ends, ["2021-01-31", "2021-02-28"]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_concurrency() {
+        let transport = MockTransport::new(
+            Fixture::named("holidays")
+                .unwrap()
+                .mock_response()
+                .latency(std::time::Duration::from_millis(20)),
+        );
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone());
+
+        let mut months = api.holidays_by_month("us", 2021);
+        assert!(months.next().await.unwrap().is_ok());
+        assert_eq!(transport.calls(), 1);
+
+        let mut months = api
+            .clone()
+            .stream_concurrency(3)
+            .holidays_by_month("us", 2021);
+        assert!(months.next().await.unwrap().is_ok());
+        assert_eq!(transport.calls(), 4);
+        assert_eq!(months.count().await, 11);
+        assert_eq!(transport.calls(), 13);
+    }
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    calendar::{Weekend, YearCalendar},
+    responses::{CountriesResponse, Holiday, HolidaysResponse, LanguagesResponse},
+};
+
+/// Serialized form of a [`YearCalendar`], whose index is rebuilt when loading.
+#[derive(Serialize, Deserialize)]
+struct StoredCalendar {
+    weekend: Weekend,
+    holidays: Vec<Holiday>,
+}
+
+impl YearCalendar {
+    /// Serializes the calendar to a compact [postcard](https://docs.rs/postcard) blob, which
+    /// can be written to a file at build time and embedded with `include_bytes!`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let response = api.holidays("us", 2021).get_full().await.unwrap();
+    ///     let bytes = response.year_calendar(Weekend::default()).to_bytes().unwrap();
+    ///     std::fs::write("us-2021.bin", bytes).unwrap();
+    ///
+    ///     // Later, without calling the API:
+    ///     // let calendar = YearCalendar::from_bytes(include_bytes!("us-2021.bin")).unwrap();
+    /// }
+    /// ```
+    pub fn to_bytes(&self) -> Result<Vec<u8>, postcard::Error> {
+        postcard::to_allocvec(&StoredCalendar {
+            weekend: self.weekend(),
+            holidays: self.holidays().to_vec(),
+        })
+    }
+
+    /// Loads a calendar serialized with [`YearCalendar::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, postcard::Error> {
+        let stored: StoredCalendar = postcard::from_bytes(bytes)?;
+        Ok(Self::from_holidays(&stored.holidays, stored.weekend))
+    }
+}
+
+macro_rules! impl_bytes {
+    ($response:ty) => {
+        impl $response {
+            /// Serializes the response to a compact [postcard](https://docs.rs/postcard) blob,
+            /// which can be embedded with `include_bytes!`.
+            pub fn to_bytes(&self) -> Result<Vec<u8>, postcard::Error> {
+                postcard::to_allocvec(self)
+            }
+
+            /// Loads a response serialized with `to_bytes`.
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, postcard::Error> {
+                postcard::from_bytes(bytes)
+            }
+        }
+    };
+}
+
+impl_bytes!(HolidaysResponse);
+impl_bytes!(CountriesResponse);
+impl_bytes!(LanguagesResponse);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::Fixture;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_binary_round_trip() {
+        let response: HolidaysResponse = Fixture::named("holidays").unwrap().parse().unwrap();
+        let bytes = response.to_bytes().unwrap();
+        assert!(bytes.len() < Fixture::named("holidays").unwrap().body.len());
+        let loaded = HolidaysResponse::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.holidays.len(), response.holidays.len());
+
+        let calendar = loaded.year_calendar(Weekend::friday_saturday());
+        let calendar = YearCalendar::from_bytes(&calendar.to_bytes().unwrap()).unwrap();
+        assert_eq!(calendar.weekend(), Weekend::friday_saturday());
+        assert!(calendar.is_holiday(NaiveDate::from_ymd_opt(2021, 1, 1).unwrap()));
+
+        let countries: CountriesResponse = Fixture::named("countries").unwrap().parse().unwrap();
+        let loaded = CountriesResponse::from_bytes(&countries.to_bytes().unwrap()).unwrap();
+        assert_eq!(loaded.countries[0].code, countries.countries[0].code);
+        assert!(LanguagesResponse::from_bytes(&bytes[..4]).is_err());
+    }
+}
@@ -0,0 +1,39 @@
+use std::{
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs a future to completion on the current thread, without any async runtime.
+///
+/// Meant for clients using a blocking transport such as `UreqTransport`. Transports relying on
+/// a runtime, like `ReqwestTransport`, need to be driven by that runtime instead.
+///
+/// # Examples
+/// ```no_run
+/// use holidayapi_rust::prelude::*;
+///
+/// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+/// let holidays = block_on(api.holidays("us", 2021).get());
+/// ```
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::HolidayAPIError;
+
+/// Requests spent on a day and in a month.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Spent {
+    day: Option<NaiveDate>,
+    today: u32,
+    month: Option<NaiveDate>,
+    this_month: u32,
+}
+
+/// Hard limit on the number of requests sent per UTC day and per UTC month, set with
+/// [`HolidayAPI::budget`](crate::HolidayAPI::budget).
+///
+/// Once a limit is reached, requests fail with [`HolidayAPIError::BudgetExceeded`] without
+/// being sent, until the day or month ends. Requests going through a
+/// [`Scheduler`](crate::prelude::Scheduler) wait for the reset instead.
+///
+/// Clones share their spending, so a budget can be shared by several clients.
+///
+/// # Examples
+/// ```
+/// use holidayapi_rust::prelude::*;
+///
+/// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+///     .unwrap()
+///     .budget(&Budget::new().per_day(500).per_month(9000));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Budget {
+    per_day: Option<u32>,
+    per_month: Option<u32>,
+    spent: Arc<Mutex<Spent>>,
+}
+
+impl Budget {
+    /// Budget without limits, see [`per_day`](Self::per_day) and
+    /// [`per_month`](Self::per_month).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn per_day(&mut self, requests: u32) -> Self {
+        self.per_day = Some(requests);
+        self.to_owned()
+    }
+
+    pub fn per_month(&mut self, requests: u32) -> Self {
+        self.per_month = Some(requests);
+        self.to_owned()
+    }
+
+    /// Requests spent on the day and in the month of `now`.
+    pub fn spent(&self, now: DateTime<Utc>) -> (u32, u32) {
+        let mut spent = self.spent.lock().expect("Lock is not poisoned");
+        Self::roll(&mut spent, now.date_naive());
+        (spent.today, spent.this_month)
+    }
+
+    /// Spending of the current day and month, to be saved.
+    pub(crate) fn spending(&self) -> Spent {
+        self.spent.lock().expect("Lock is not poisoned").clone()
+    }
+
+    /// Resumes from a saved spending, see [`HolidayAPI::persist_usage`].
+    ///
+    /// [`HolidayAPI::persist_usage`]: crate::HolidayAPI::persist_usage
+    pub(crate) fn resume(&self, spent: &Spent) {
+        *self.spent.lock().expect("Lock is not poisoned") = spent.clone();
+    }
+
+    /// Spends one request, or fails with the time left until the exhausted limit resets.
+    pub(crate) fn spend(&self, now: DateTime<Utc>) -> Result<(), HolidayAPIError> {
+        let today = now.date_naive();
+        let mut spent = self.spent.lock().expect("Lock is not poisoned");
+        Self::roll(&mut spent, today);
+
+        let month_start = today.with_day(1).expect("First day of the month exists");
+        let reset = if self
+            .per_month
+            .is_some_and(|limit| spent.this_month >= limit)
+        {
+            Some(month_start + Months::new(1))
+        } else if self.per_day.is_some_and(|limit| spent.today >= limit) {
+            today.succ_opt()
+        } else {
+            None
+        };
+        if let Some(reset) = reset {
+            let midnight = reset.and_time(chrono::NaiveTime::MIN).and_utc();
+            return Err(HolidayAPIError::BudgetExceeded(
+                (midnight - now).to_std().unwrap_or(Duration::ZERO),
+            ));
+        }
+        spent.today += 1;
+        spent.this_month += 1;
+        Ok(())
+    }
+
+    /// Resets the counters of a day or month which has ended.
+    fn roll(spent: &mut Spent, today: NaiveDate) {
+        let month = today.with_day(1);
+        if spent.day != Some(today) {
+            spent.day = Some(today);
+            spent.today = 0;
+        }
+        if spent.month != month {
+            spent.month = month;
+            spent.this_month = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        NaiveDate::from_ymd_opt(2021, 1, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+            .and_utc()
+    }
+
+    #[test]
+    fn test_budget_windows() {
+        let budget = Budget::new().per_day(2).per_month(3);
+        assert!(budget.spend(at(1, 10)).is_ok());
+        assert!(budget.clone().spend(at(1, 11)).is_ok());
+        match budget.spend(at(1, 12)) {
+            Err(HolidayAPIError::BudgetExceeded(resets_in)) => {
+                assert_eq!(resets_in, Duration::from_secs(12 * 3600))
+            }
+            other => unreachable!("Should exceed the daily budget, got {:?}", other),
+        }
+        assert_eq!(budget.spent(at(1, 12)), (2, 2));
+
+        assert!(budget.spend(at(2, 0)).is_ok());
+        match budget.spend(at(2, 1)) {
+            Err(HolidayAPIError::BudgetExceeded(resets_in)) => {
+                assert_eq!(resets_in, Duration::from_secs((30 * 24 - 1) * 3600))
+            }
+            other => unreachable!("Should exceed the monthly budget, got {:?}", other),
+        }
+        assert_eq!(budget.spent(at(3, 0)), (0, 3));
+    }
+}
@@ -0,0 +1,514 @@
+use crate::{requests::Endpoint, version::Version};
+use http::header::{HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+/// Parameters sent by [`Request`](crate::requests::Request) by default, left out of
+/// canonical parameters.
+const DEFAULT_PARAMETERS: [(&str, &str); 6] = [
+    ("format", "json"),
+    ("pretty", "false"),
+    ("public", "false"),
+    ("subdivisions", "false"),
+    ("previous", "false"),
+    ("upcoming", "false"),
+];
+
+/// Parameters which the API reads case-insensitively, or as numbers.
+const CODE_PARAMETERS: [&str; 2] = ["country", "language"];
+const NUMBER_PARAMETERS: [&str; 4] = ["year", "month", "day", "days"];
+
+/// Parameters of a request in a canonical form, so logically identical requests compare
+/// equal: codes trimmed and lowercased, numbers trimmed and without leading zeros, default
+/// values left out, and sorted by name. Names and the values of other parameters are kept as
+/// sent, since the API may read them case-sensitively.
+///
+/// Anything identifying requests, such as cache keys, should build on it.
+pub(crate) fn canonical_parameters(parameters: &HashMap<String, String>) -> Vec<(String, String)> {
+    let mut canonical: Vec<(String, String)> = parameters
+        .iter()
+        .map(|(name, value)| (name.clone(), canonical_value(name, value)))
+        .filter(|(name, value)| !DEFAULT_PARAMETERS.contains(&(name.as_str(), value.as_str())))
+        .collect();
+    canonical.sort();
+    canonical
+}
+
+/// Value of the parameter `name` in its canonical form, see [`canonical_parameters`]. Also used
+/// to compare the codes of responses, such as the country of holidays when deduplicating them.
+pub(crate) fn canonical_value(name: &str, value: &str) -> String {
+    if CODE_PARAMETERS.contains(&name) {
+        value.trim().to_ascii_lowercase()
+    } else if NUMBER_PARAMETERS.contains(&name) {
+        let value = value.trim();
+        value
+            .parse::<i64>()
+            .map_or_else(|_| value.to_string(), |number| number.to_string())
+    } else {
+        value.to_string()
+    }
+}
+
+/// Identifies a cached response by its API version, endpoint and
+/// [canonical parameters](canonical_parameters).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct CacheKey {
+    version: Version,
+    endpoint: Endpoint,
+    parameters: Vec<(String, String)>,
+}
+
+impl CacheKey {
+    pub(crate) fn new(
+        version: Version,
+        endpoint: Endpoint,
+        parameters: &HashMap<String, String>,
+    ) -> Self {
+        Self {
+            version,
+            endpoint,
+            parameters: canonical_parameters(parameters),
+        }
+    }
+
+    pub(crate) fn version(&self) -> Version {
+        self.version
+    }
+
+    pub(crate) fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+
+    fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Validators sent back by the API, used to revalidate an entry with a conditional request.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Validators {
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+}
+
+impl Validators {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        Self {
+            etag: headers.get(ETAG).cloned(),
+            last_modified: headers.get(LAST_MODIFIED).cloned(),
+        }
+    }
+
+    /// Headers turning a request into a conditional one.
+    pub(crate) fn conditional_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(etag) = &self.etag {
+            headers.insert(IF_NONE_MATCH, etag.clone());
+        }
+        if let Some(last_modified) = &self.last_modified {
+            headers.insert(IF_MODIFIED_SINCE, last_modified.clone());
+        }
+        headers
+    }
+}
+
+/// Outcome of revalidating a cached response against the API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Revalidation {
+    /// Nothing was cached, the response was fetched for the first time.
+    Fetched,
+    /// The API confirmed the cached response is still current (`304 Not Modified`).
+    NotModified,
+    /// The API sent the response again, with the same content as the cached one.
+    Unchanged,
+    /// The API sent a different response, which replaced the cached one.
+    Changed,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CacheEntry {
+    /// Parameters of the request as sent, to fetch the response again.
+    pub(crate) parameters: HashMap<String, String>,
+    pub(crate) body: String,
+    pub(crate) validators: Validators,
+    hash: u64,
+    expires_at: Instant,
+}
+
+fn hash_body(body: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// In-memory cache of raw JSON responses, shared by every clone of the client, along with the
+/// TTLs of its endpoints.
+///
+/// Expired entries are kept around so they can be revalidated with a conditional request
+/// instead of being downloaded again, until the cache holds [`Cache::MAX_ENTRIES`] entries:
+/// storing another one then evicts the expired entries, or the entry expiring first when all
+/// are fresh.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    ttl: Duration,
+    capacity: usize,
+    endpoint_ttls: Arc<RwLock<HashMap<Endpoint, Duration>>>,
+    entries: Arc<Mutex<HashMap<CacheKey, CacheEntry>>>,
+}
+
+impl Cache {
+    /// How long countries and languages are kept by [`HolidayAPI::with_default_cache`]: they
+    /// change a few times a year.
+    ///
+    /// [`HolidayAPI::with_default_cache`]: crate::HolidayAPI::with_default_cache
+    pub const DEFAULT_REFERENCE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+    /// How long holidays are kept by [`HolidayAPI::with_default_cache`], and responses of the
+    /// endpoints without a default.
+    ///
+    /// [`HolidayAPI::with_default_cache`]: crate::HolidayAPI::with_default_cache
+    pub const DEFAULT_HOLIDAYS_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+    /// How long workday computations are kept by [`HolidayAPI::with_default_cache`], as they are
+    /// typically relative to the current date.
+    ///
+    /// [`HolidayAPI::with_default_cache`]: crate::HolidayAPI::with_default_cache
+    pub const DEFAULT_WORKDAY_TTL: Duration = Duration::from_secs(15 * 60);
+
+    /// Number of entries, fresh or expired, from which storing a new one evicts others.
+    pub const MAX_ENTRIES: usize = 10_000;
+
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            capacity: Self::MAX_ENTRIES,
+            endpoint_ttls: Arc::default(),
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Cache keeping each endpoint for its default TTL.
+    pub(crate) fn with_defaults() -> Self {
+        let cache = Self::new(Self::DEFAULT_HOLIDAYS_TTL);
+        for (endpoint, ttl) in [
+            (Endpoint::Countries, Self::DEFAULT_REFERENCE_TTL),
+            (Endpoint::Languages, Self::DEFAULT_REFERENCE_TTL),
+            (Endpoint::Workday, Self::DEFAULT_WORKDAY_TTL),
+            (Endpoint::Workdays, Self::DEFAULT_WORKDAY_TTL),
+        ] {
+            cache.set_ttl(endpoint, ttl);
+        }
+        cache
+    }
+
+    /// Sets how long the responses of an endpoint are kept, for the entries stored afterwards
+    /// by every client sharing the cache.
+    pub(crate) fn set_ttl(&self, endpoint: Endpoint, ttl: Duration) {
+        self.endpoint_ttls
+            .write()
+            .expect("Lock is not poisoned")
+            .insert(endpoint, ttl);
+    }
+
+    /// How long responses of the endpoints without their own TTL are kept, see
+    /// [`Cache::ttl_for`].
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// How long responses of `endpoint` are kept.
+    pub fn ttl_for(&self, endpoint: &Endpoint) -> Duration {
+        self.endpoint_ttls
+            .read()
+            .expect("Lock is not poisoned")
+            .get(endpoint)
+            .copied()
+            .unwrap_or(self.ttl)
+    }
+
+    /// Number of entries that are still fresh.
+    pub fn len(&self) -> usize {
+        let now = Instant::now();
+        self.entries
+            .lock()
+            .expect("Lock is not poisoned")
+            .values()
+            .filter(|entry| entry.expires_at > now)
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes every entry, returning how many were removed.
+    pub fn invalidate_all(&self) -> usize {
+        let mut entries = self.entries.lock().expect("Lock is not poisoned");
+        let removed = entries.len();
+        entries.clear();
+        removed
+    }
+
+    /// Removes every entry of the given endpoint, returning how many were removed.
+    pub fn invalidate_endpoint(&self, endpoint: Endpoint) -> usize {
+        self.invalidate_where(|key| key.endpoint == endpoint)
+    }
+
+    /// Removes the cached holidays of a country for a year, whatever their other parameters,
+    /// returning how many entries were removed.
+    pub fn invalidate(&self, country: &str, year: i32) -> usize {
+        let year = year.to_string();
+        self.invalidate_where(|key| {
+            key.endpoint == Endpoint::Holidays
+                && key
+                    .parameter("country")
+                    .is_some_and(|code| code.eq_ignore_ascii_case(country.trim()))
+                && key.parameter("year") == Some(year.as_str())
+        })
+    }
+
+    fn invalidate_where(&self, predicate: impl Fn(&CacheKey) -> bool) -> usize {
+        let mut entries = self.entries.lock().expect("Lock is not poisoned");
+        let before = entries.len();
+        entries.retain(|key, _| !predicate(key));
+        before - entries.len()
+    }
+
+    pub(crate) fn contains(&self, key: &CacheKey) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns the body of a fresh entry.
+    pub(crate) fn get(&self, key: &CacheKey) -> Option<String> {
+        let entries = self.entries.lock().expect("Lock is not poisoned");
+        entries
+            .get(key)
+            .filter(|entry| entry.expires_at > Instant::now())
+            .map(|entry| entry.body.clone())
+    }
+
+    /// Returns an entry whether it is fresh or expired, so it can be revalidated.
+    pub(crate) fn entry(&self, key: &CacheKey) -> Option<CacheEntry> {
+        self.entries
+            .lock()
+            .expect("Lock is not poisoned")
+            .get(key)
+            .cloned()
+    }
+
+    /// Keys of the fresh entries expiring within `within`, with the parameters their request
+    /// was sent with.
+    pub(crate) fn expiring(&self, within: Duration) -> Vec<(CacheKey, HashMap<String, String>)> {
+        let now = Instant::now();
+        self.entries
+            .lock()
+            .expect("Lock is not poisoned")
+            .iter()
+            .filter(|(_, entry)| entry.expires_at > now && entry.expires_at <= now + within)
+            .map(|(key, entry)| (key.clone(), entry.parameters.clone()))
+            .collect()
+    }
+
+    /// Removes an entry.
+    pub(crate) fn remove(&self, key: &CacheKey) {
+        self.entries
+            .lock()
+            .expect("Lock is not poisoned")
+            .remove(key);
+    }
+
+    /// Marks an entry as fresh again after the API answered `304 Not Modified`.
+    pub(crate) fn refresh(&self, key: &CacheKey) {
+        if let Some(entry) = self
+            .entries
+            .lock()
+            .expect("Lock is not poisoned")
+            .get_mut(key)
+        {
+            entry.expires_at = Instant::now() + self.ttl_for(&key.endpoint);
+        }
+    }
+
+    /// Stores the response of a request sent with `parameters`, telling whether it differs
+    /// from the previously cached one.
+    ///
+    /// A new key evicts the expired entries of a full cache, or the entry expiring first.
+    pub(crate) fn insert(
+        &self,
+        key: CacheKey,
+        parameters: HashMap<String, String>,
+        body: String,
+        validators: Validators,
+    ) -> Revalidation {
+        let hash = hash_body(&body);
+        let entry = CacheEntry {
+            parameters,
+            body,
+            validators,
+            hash,
+            expires_at: Instant::now() + self.ttl_for(&key.endpoint),
+        };
+        let mut entries = self.entries.lock().expect("Lock is not poisoned");
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            let now = Instant::now();
+            entries.retain(|_, entry| entry.expires_at > now);
+            if entries.len() >= self.capacity {
+                let first = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.expires_at)
+                    .map(|(key, _)| key.clone());
+                if let Some(first) = first {
+                    entries.remove(&first);
+                }
+            }
+        }
+        let previous = entries.insert(key, entry);
+        match previous {
+            None => Revalidation::Fetched,
+            Some(previous) if previous.hash == hash => Revalidation::Unchanged,
+            Some(_) => Revalidation::Changed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_parameters() {
+        let parameters = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect()
+        };
+        let key = |pairs| CacheKey::new(Version::V1, Endpoint::Holidays, &parameters(pairs));
+
+        assert_eq!(
+            key(&[("country", "US"), ("year", "2021"), ("month", "07")]),
+            key(&[
+                ("month", " 7"),
+                ("country", " us "),
+                ("year", "2021"),
+                ("format", "json")
+            ])
+        );
+        assert_ne!(key(&[("Month", "7")]), key(&[("month", "7")]));
+        assert_eq!(
+            key(&[("country", "us"), ("public", "false")]),
+            key(&[("country", "us")])
+        );
+        assert_ne!(
+            key(&[("country", "us"), ("public", "true")]),
+            key(&[("country", "us")])
+        );
+        assert_ne!(key(&[("search", "Day")]), key(&[("search", "day")]));
+        assert_ne!(key(&[("search", "day ")]), key(&[("search", "day")]));
+        assert_eq!(
+            canonical_parameters(&parameters(&[("year", "2021"), ("country", "US-CA")])),
+            [
+                ("country".to_string(), "us-ca".to_string()),
+                ("year".to_string(), "2021".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_eviction() {
+        let key = |year: i32| {
+            let parameters = HashMap::from([("year".to_string(), year.to_string())]);
+            CacheKey::new(Version::V1, Endpoint::Holidays, &parameters)
+        };
+        let insert = |cache: &Cache, year| {
+            cache.insert(
+                key(year),
+                HashMap::new(),
+                year.to_string(),
+                Validators::default(),
+            )
+        };
+
+        let cache = Cache {
+            capacity: 2,
+            ..Cache::new(Duration::from_secs(60))
+        };
+        cache.set_ttl(Endpoint::Holidays, Duration::ZERO);
+        insert(&cache, 2020);
+        insert(&cache, 2021);
+        cache.set_ttl(Endpoint::Holidays, Duration::from_secs(60));
+        insert(&cache, 2022);
+        assert!(cache.entry(&key(2020)).is_none());
+        assert!(cache.entry(&key(2021)).is_none());
+
+        insert(&cache, 2023);
+        assert_eq!(insert(&cache, 2023), Revalidation::Unchanged);
+        insert(&cache, 2024);
+        assert!(cache.get(&key(2022)).is_none());
+        assert_eq!(cache.get(&key(2023)).as_deref(), Some("2023"));
+        assert_eq!(cache.get(&key(2024)).as_deref(), Some("2024"));
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_ttls() {
+        use crate::{
+            fixtures::Fixture, mock::MockTransport, test_support::EXPIRED_KEY, HolidayAPI,
+        };
+
+        let transport = MockTransport::new(Fixture::named("countries").unwrap().mock_response())
+            .on_call(2, Fixture::named("holidays").unwrap().mock_response())
+            .on_call(3, Fixture::named("holidays").unwrap().mock_response())
+            .on_call(4, Fixture::named("holidays").unwrap().mock_response());
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+            .cache_ttl("holidays", Duration::ZERO);
+        let cache = api.cache().unwrap();
+        assert_eq!(
+            cache.ttl_for(&Endpoint::Countries),
+            Cache::DEFAULT_REFERENCE_TTL
+        );
+        assert_eq!(
+            cache.ttl_for(&Endpoint::Workdays),
+            Cache::DEFAULT_WORKDAY_TTL
+        );
+        assert_eq!(cache.ttl_for(&Endpoint::Holidays), Duration::ZERO);
+        assert_eq!(
+            cache.ttl_for(&Endpoint::Other("flag".into())),
+            Cache::DEFAULT_HOLIDAYS_TTL
+        );
+
+        api.countries().get().await.unwrap();
+        api.holidays("us", 2021).get().await.unwrap();
+        api.countries().get().await.unwrap();
+        api.holidays("us", 2021).get().await.unwrap();
+        assert_eq!(transport.calls(), 3);
+        assert_eq!(cache.len(), 1);
+
+        api.clone()
+            .cache_ttl("holidays", Duration::from_secs(60))
+            .holidays("us", 2021)
+            .parameter("month", "07")
+            .get()
+            .await
+            .unwrap();
+        let expiring = cache.expiring(Duration::from_secs(3600));
+        let (key, parameters) = expiring
+            .iter()
+            .find(|(key, _)| key.endpoint() == &Endpoint::Holidays)
+            .unwrap();
+        assert_eq!(key.parameter("month"), Some("7"));
+        assert_eq!(parameters["month"], "07");
+        assert_eq!(
+            api.cache().unwrap().ttl_for(&Endpoint::Holidays),
+            Duration::from_secs(60)
+        );
+    }
+}
@@ -0,0 +1,461 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    ops::Range,
+};
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::responses::{Holiday, HolidaysResponse};
+
+/// Days of the week that are not worked in a country.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Weekend {
+    days: [bool; 7],
+}
+
+impl Default for Weekend {
+    /// Saturday and Sunday.
+    fn default() -> Self {
+        Self::new(&[Weekday::Sat, Weekday::Sun])
+    }
+}
+
+impl Weekend {
+    pub fn new(days: &[Weekday]) -> Self {
+        let mut weekend = Self { days: [false; 7] };
+        for day in days {
+            weekend.days[day.num_days_from_monday() as usize] = true;
+        }
+        weekend
+    }
+
+    /// Friday and Saturday, as in most of the Middle East.
+    pub fn friday_saturday() -> Self {
+        Self::new(&[Weekday::Fri, Weekday::Sat])
+    }
+
+    pub fn contains(&self, day: Weekday) -> bool {
+        self.days[day.num_days_from_monday() as usize]
+    }
+}
+
+/// Inclusive range of dates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl DateRange {
+    /// Number of days in the range, both ends included.
+    pub fn days(&self) -> i64 {
+        (self.end - self.start).num_days() + 1
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        (self.start..=self.end).contains(&date)
+    }
+}
+
+/// Working day which, taken off, joins days off into a longer break.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BridgeDay {
+    pub date: NaiveDate,
+    /// Break obtained by taking the day off, the bridge day included.
+    pub days_off: DateRange,
+}
+
+impl BridgeDay {
+    /// Length of the resulting break, in days.
+    pub fn break_days(&self) -> i64 {
+        self.days_off.days()
+    }
+}
+
+/// Working and non-working days of a country, built from its public holidays and weekend.
+///
+/// Holidays are taken off on their observed date.
+///
+/// # Examples
+/// ```
+/// use holidayapi_rust::prelude::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+///     if let Ok(holidays) = api.holidays("us", 2021).get().await {
+///         let calendar = Calendar::new(&holidays, Weekend::default());
+///         for range in calendar.long_weekends() {
+///             println!("{} to {} ({} days)", range.start, range.end, range.days());
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Calendar {
+    weekend: Weekend,
+    holidays: BTreeSet<NaiveDate>,
+}
+
+impl Calendar {
+    /// Minimum number of consecutive days off making a long weekend.
+    pub const LONG_WEEKEND_DAYS: i64 = 3;
+
+    /// Minimum length of the break a [`BridgeDay`] must create.
+    pub const BRIDGE_BREAK_DAYS: i64 = 4;
+
+    /// Days walked on each side of a date by [`days_off_around`](Self::days_off_around), which
+    /// only stops earlier when the weekend leaves no working day.
+    pub const MAX_DAYS_OFF_WALK: i64 = 366;
+
+    /// Builds a calendar from holidays, ignoring those which are not public or whose observed
+    /// date cannot be parsed.
+    pub fn new(holidays: &[Holiday], weekend: Weekend) -> Self {
+        Self {
+            weekend,
+            holidays: holidays
+                .iter()
+                .filter(|holiday| holiday.public)
+                .filter_map(Holiday::parse_observed)
+                .collect(),
+        }
+    }
+
+    pub fn weekend(&self) -> Weekend {
+        self.weekend
+    }
+
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.holidays.contains(&date)
+    }
+
+    /// Whether the date falls on the weekend or a public holiday.
+    pub fn is_day_off(&self, date: NaiveDate) -> bool {
+        self.weekend.contains(date.weekday()) || self.is_holiday(date)
+    }
+
+    pub fn is_working_day(&self, date: NaiveDate) -> bool {
+        !self.is_day_off(date)
+    }
+
+    /// Returns the longest run of consecutive days off containing `date`, or `None` if `date`
+    /// is a working day.
+    ///
+    /// The run extends at most [`MAX_DAYS_OFF_WALK`](Self::MAX_DAYS_OFF_WALK) days on each side
+    /// of `date`, and stops at the dates representable by [`NaiveDate`].
+    pub fn days_off_around(&self, date: NaiveDate) -> Option<DateRange> {
+        if !self.is_day_off(date) {
+            return None;
+        }
+        let walk = |step: Duration| {
+            let mut last = date;
+            for _ in 0..Self::MAX_DAYS_OFF_WALK {
+                match last.checked_add_signed(step) {
+                    Some(next) if self.is_day_off(next) => last = next,
+                    _ => break,
+                }
+            }
+            last
+        };
+        Some(DateRange {
+            start: walk(Duration::days(-1)),
+            end: walk(Duration::days(1)),
+        })
+    }
+
+    /// Returns the runs of at least [`LONG_WEEKEND_DAYS`](Self::LONG_WEEKEND_DAYS) consecutive
+    /// days off that include a holiday, in chronological order.
+    pub fn long_weekends(&self) -> Vec<DateRange> {
+        let mut ranges: Vec<DateRange> = Vec::new();
+        for &holiday in &self.holidays {
+            if ranges.last().is_some_and(|range| range.contains(holiday)) {
+                continue;
+            }
+            if let Some(range) = self.days_off_around(holiday) {
+                if range.days() >= Self::LONG_WEEKEND_DAYS {
+                    ranges.push(range);
+                }
+            }
+        }
+        ranges
+    }
+
+    /// Suggests single working days to take off next to a holiday to create breaks of at least
+    /// [`BRIDGE_BREAK_DAYS`](Self::BRIDGE_BREAK_DAYS) days, longest breaks first.
+    pub fn bridge_days(&self) -> Vec<BridgeDay> {
+        let mut candidates = BTreeSet::new();
+        for &holiday in &self.holidays {
+            if let Some(range) = self.days_off_around(holiday) {
+                candidates.insert(range.start - Duration::days(1));
+                candidates.insert(range.end + Duration::days(1));
+            }
+        }
+
+        let mut bridges: Vec<BridgeDay> = candidates
+            .into_iter()
+            .filter_map(|date| {
+                let before = self.days_off_around(date - Duration::days(1));
+                let after = self.days_off_around(date + Duration::days(1));
+                let days_off = DateRange {
+                    start: before.map_or(date, |range| range.start),
+                    end: after.map_or(date, |range| range.end),
+                };
+                Some(BridgeDay { date, days_off })
+                    .filter(|bridge| bridge.break_days() >= Self::BRIDGE_BREAK_DAYS)
+            })
+            .collect();
+        bridges.sort_by(|a, b| {
+            b.break_days()
+                .cmp(&a.break_days())
+                .then(a.date.cmp(&b.date))
+        });
+        bridges
+    }
+}
+
+/// Holidays indexed by observed date, answering lookups in constant time instead of scanning
+/// the holidays, e.g. in scheduling loops.
+///
+/// Unlike [`Calendar`], every holiday is kept, public or not: [`holidays_on`](Self::holidays_on)
+/// and [`next_after`](Self::next_after) return all of them, while
+/// [`is_holiday`](Self::is_holiday) and the days off only consider public holidays.
+///
+/// # Examples
+/// ```
+/// use chrono::NaiveDate;
+/// use holidayapi_rust::prelude::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+///     if let Ok(response) = api.holidays("us", 2021).get_full().await {
+///         let calendar = YearCalendar::new(&response, Weekend::default());
+///         let date = NaiveDate::from_ymd_opt(2021, 7, 1).unwrap();
+///         if let Some(holiday) = calendar.next_after(date) {
+///             println!("Next holiday: {} on {}", holiday.name, holiday.observed);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct YearCalendar {
+    weekend: Weekend,
+    /// Holidays sorted by observed date.
+    holidays: Vec<Holiday>,
+    /// Parsed observed date of each holiday.
+    observed: Vec<NaiveDate>,
+    /// Holidays observed on each date, as a range of `holidays`, and whether one of them is
+    /// public. Only dates with holidays are kept, so outlier dates cost a single entry.
+    days: HashMap<NaiveDate, (Range<usize>, bool)>,
+}
+
+impl YearCalendar {
+    /// Indexes the holidays of a response, ignoring those whose observed date cannot be parsed.
+    pub fn new(response: &HolidaysResponse, weekend: Weekend) -> Self {
+        Self::from_holidays(&response.holidays, weekend)
+    }
+
+    /// Indexes holidays, ignoring those whose observed date cannot be parsed.
+    pub fn from_holidays(holidays: &[Holiday], weekend: Weekend) -> Self {
+        let mut holidays: Vec<(NaiveDate, Holiday)> = holidays
+            .iter()
+            .filter_map(|holiday| Some((holiday.parse_observed()?, holiday.clone())))
+            .collect();
+        holidays.sort_by_key(|(observed, _)| *observed);
+
+        let mut days: HashMap<NaiveDate, (Range<usize>, bool)> = HashMap::new();
+        for (index, (observed, holiday)) in holidays.iter().enumerate() {
+            let (range, public) = days.entry(*observed).or_insert((index..index, false));
+            range.end = index + 1;
+            *public |= holiday.public;
+        }
+        let (observed, holidays) = holidays.into_iter().unzip();
+        Self {
+            weekend,
+            holidays,
+            observed,
+            days,
+        }
+    }
+
+    pub fn weekend(&self) -> Weekend {
+        self.weekend
+    }
+
+    /// Every indexed holiday, sorted by observed date.
+    pub fn holidays(&self) -> &[Holiday] {
+        &self.holidays
+    }
+
+    /// Whether a public holiday is observed on the date.
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.days.get(&date).is_some_and(|(_, public)| *public)
+    }
+
+    /// Whether the date falls on the weekend or a public holiday.
+    pub fn is_day_off(&self, date: NaiveDate) -> bool {
+        self.weekend.contains(date.weekday()) || self.is_holiday(date)
+    }
+
+    pub fn is_working_day(&self, date: NaiveDate) -> bool {
+        !self.is_day_off(date)
+    }
+
+    /// Holidays observed on the date, public or not.
+    pub fn holidays_on(&self, date: NaiveDate) -> &[Holiday] {
+        match self.days.get(&date) {
+            Some((range, _)) => &self.holidays[range.clone()],
+            None => &[],
+        }
+    }
+
+    /// First holiday observed strictly after the date, public or not.
+    pub fn next_after(&self, date: NaiveDate) -> Option<&Holiday> {
+        let index = self.observed.partition_point(|observed| *observed <= date);
+        self.holidays.get(index)
+    }
+}
+
+impl HolidaysResponse {
+    /// Returns the long weekends created by the public holidays of this response, see
+    /// [`Calendar::long_weekends`].
+    pub fn long_weekends(&self, weekend: Weekend) -> Vec<DateRange> {
+        Calendar::new(&self.holidays, weekend).long_weekends()
+    }
+
+    /// Suggests bridge days around the public holidays of this response, see
+    /// [`Calendar::bridge_days`].
+    pub fn bridge_days(&self, weekend: Weekend) -> Vec<BridgeDay> {
+        Calendar::new(&self.holidays, weekend).bridge_days()
+    }
+
+    /// Indexes the holidays of this response for fast lookups, see [`YearCalendar`].
+    pub fn year_calendar(&self, weekend: Weekend) -> YearCalendar {
+        YearCalendar::new(self, weekend)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::holiday;
+
+    fn date(date: &str) -> NaiveDate {
+        crate::date::parse(date).unwrap()
+    }
+
+    #[test]
+    fn test_long_weekends() {
+        // Friday, Monday, and Thursday + Friday.
+        let holidays = [
+            holiday("2021-01-01"),
+            holiday("2021-01-18"),
+            holiday("2021-11-25"),
+            holiday("2021-11-26"),
+            holiday("2021-07-07"),
+        ];
+        let calendar = Calendar::new(&holidays, Weekend::default());
+        let ranges = calendar.long_weekends();
+        assert_eq!(ranges.len(), 3);
+        assert_eq!(ranges[0].start, date("2021-01-01"));
+        assert_eq!(ranges[0].end, date("2021-01-03"));
+        assert_eq!(ranges[1].start, date("2021-01-16"));
+        assert_eq!(ranges[2].days(), 4);
+
+        // Thursday + Friday off with a Friday-Saturday weekend only spans three days.
+        let ranges = Calendar::new(&holidays, Weekend::friday_saturday()).long_weekends();
+        assert!(ranges
+            .iter()
+            .all(|range| !range.contains(date("2021-01-18"))));
+        assert_eq!(
+            ranges.last().unwrap(),
+            &DateRange {
+                start: date("2021-11-25"),
+                end: date("2021-11-27")
+            }
+        );
+
+        use chrono::Weekday::*;
+        let always_off = Calendar::new(&[], Weekend::new(&[Mon, Tue, Wed, Thu, Fri, Sat, Sun]));
+        let range = always_off.days_off_around(date("2021-07-07")).unwrap();
+        assert_eq!(range.days(), 2 * Calendar::MAX_DAYS_OFF_WALK + 1);
+        let range = always_off.days_off_around(NaiveDate::MAX).unwrap();
+        assert_eq!(range.end, NaiveDate::MAX);
+    }
+
+    #[test]
+    fn test_bridge_days() {
+        // Thursday, Tuesday, and Thursday + Monday.
+        let holidays = [
+            holiday("2021-05-13"),
+            holiday("2021-06-01"),
+            holiday("2021-12-23"),
+            holiday("2021-12-27"),
+        ];
+        let bridges = Calendar::new(&holidays, Weekend::default()).bridge_days();
+        assert_eq!(bridges.len(), 4);
+        assert_eq!(bridges[0].date, date("2021-12-24"));
+        assert_eq!(bridges[0].break_days(), 5);
+        assert_eq!(bridges[1].date, date("2021-05-14"));
+        assert_eq!(bridges[1].days_off.end, date("2021-05-16"));
+        assert_eq!(bridges[2].date, date("2021-05-31"));
+        assert_eq!(bridges[2].break_days(), 4);
+        assert_eq!(bridges[3].date, date("2021-12-28"));
+    }
+
+    #[test]
+    fn test_year_calendar() {
+        let mut halloween = holiday("2021-10-31");
+        halloween.public = false;
+        let response = HolidaysResponse {
+            status: 200,
+            requests: crate::responses::APIRequests {
+                available: 0,
+                used: 0,
+                resets: "".into(),
+            },
+            holidays: vec![
+                holiday("2021-12-27"),
+                halloween,
+                holiday("2021-01-01"),
+                holiday("2021-12-27"),
+            ],
+            error: None,
+            warning: None,
+        };
+        let calendar = response.year_calendar(Weekend::default());
+        assert!(calendar.is_holiday(date("2021-01-01")));
+        assert!(!calendar.is_holiday(date("2021-10-31")));
+        assert!(calendar.is_day_off(date("2021-10-31")));
+        assert!(!calendar.is_holiday(date("2020-12-31")));
+        assert!(!calendar.is_holiday(date("2022-01-01")));
+        assert!(calendar.is_working_day(date("2021-12-28")));
+        assert_eq!(calendar.holidays_on(date("2021-12-27")).len(), 2);
+        assert_eq!(
+            calendar.holidays_on(date("2021-10-31"))[0].name,
+            "2021-10-31"
+        );
+        assert!(calendar.holidays_on(date("2021-06-01")).is_empty());
+        assert_eq!(
+            calendar.next_after(date("2021-01-01")).unwrap().observed,
+            "2021-10-31"
+        );
+        assert_eq!(
+            calendar.next_after(date("2020-06-01")).unwrap().observed,
+            "2021-01-01"
+        );
+        assert!(calendar.next_after(date("2021-12-27")).is_none());
+
+        let outlier = YearCalendar::from_holidays(
+            &[holiday("2021-01-01"), holiday("9999-12-31")],
+            Weekend::default(),
+        );
+        assert!(outlier.is_holiday(date("9999-12-31")));
+        assert!(!outlier.is_holiday(date("5000-01-01")));
+        assert_eq!(
+            outlier.next_after(date("2021-01-01")).unwrap().observed,
+            "9999-12-31"
+        );
+    }
+}
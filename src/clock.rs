@@ -0,0 +1,60 @@
+use std::fmt::Debug;
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+
+/// Source of the current time, used by every "today", "upcoming" and "current year" feature.
+///
+/// Clients use [`SystemClock`] unless configured otherwise with
+/// [`HolidayAPI::clock`](crate::HolidayAPI::clock), tests can pin the time with a
+/// [`FixedClock`].
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Current date in UTC.
+    fn today(&self) -> NaiveDate {
+        self.now().date_naive()
+    }
+
+    /// Current year in UTC.
+    fn current_year(&self) -> i32 {
+        self.today().year()
+    }
+}
+
+/// [`Clock`] reading the system time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// [`Clock`] always returning the same time.
+///
+/// # Examples
+/// ```
+/// use chrono::NaiveDate;
+/// use holidayapi_rust::prelude::*;
+///
+/// let new_year = NaiveDate::from_ymd_opt(2021, 12, 31).unwrap();
+/// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+///     .unwrap()
+///     .clock(FixedClock::on(new_year));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl FixedClock {
+    /// Fixes the time at midnight UTC of the given date.
+    pub fn on(date: NaiveDate) -> Self {
+        Self(date.and_time(chrono::NaiveTime::MIN).and_utc())
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
@@ -0,0 +1,210 @@
+use std::{collections::BTreeSet, sync::Arc, sync::Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    requests::Request,
+    responses::{CountriesResponse, Country, Language, LanguagesResponse, Subdivision},
+    HolidayAPIError,
+};
+
+/// Deserializes language codes, sharing one allocation per distinct code.
+fn interned_codes<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Arc<str>>, D::Error> {
+    Ok(intern(Vec::<String>::deserialize(deserializer)?))
+}
+
+fn intern(codes: impl IntoIterator<Item = String>) -> Vec<Arc<str>> {
+    static CODES: Mutex<BTreeSet<Arc<str>>> = Mutex::new(BTreeSet::new());
+    let mut interned = CODES.lock().expect("Lock is not poisoned");
+    codes
+        .into_iter()
+        .map(|code| match interned.get(code.as_str()) {
+            Some(code) => code.clone(),
+            None => {
+                let code: Arc<str> = code.into();
+                interned.insert(code.clone());
+                code
+            }
+        })
+        .collect()
+}
+
+/// Countries of a [`CountriesResponse`], for services holding the list in memory.
+///
+/// Strings are `Arc<str>`, which are smaller than `String` and have no spare capacity, and the
+/// language codes are interned, so the thousands of codes repeated in the list share a few
+/// allocations. Returned by [`Request::get_compact`], or converted from a parsed response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactCountries {
+    pub countries: Vec<CompactCountry>,
+}
+
+/// [`Country`] of [`CompactCountries`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactCountry {
+    pub code: Arc<str>,
+    pub name: Arc<str>,
+    #[serde(deserialize_with = "interned_codes")]
+    pub languages: Vec<Arc<str>>,
+    pub codes: CompactCodes,
+    pub flag: Arc<str>,
+    pub subdivisions: Vec<CompactSubdivision>,
+}
+
+/// [`Codes`](crate::responses::Codes) of a [`CompactCountry`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactCodes {
+    #[serde(rename = "alpha-2")]
+    pub alpha_2: Arc<str>,
+    #[serde(rename = "alpha-3")]
+    pub alpha_3: Arc<str>,
+    pub numeric: Arc<str>,
+}
+
+/// [`Subdivision`] of a [`CompactCountry`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactSubdivision {
+    pub code: Arc<str>,
+    pub name: Arc<str>,
+    #[serde(deserialize_with = "interned_codes")]
+    pub languages: Vec<Arc<str>>,
+}
+
+/// Languages of a [`LanguagesResponse`], with `Arc<str>` strings, see [`CompactCountries`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactLanguages {
+    pub languages: Vec<CompactLanguage>,
+}
+
+/// [`Language`] of [`CompactLanguages`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactLanguage {
+    pub code: Arc<str>,
+    pub name: Arc<str>,
+}
+
+impl CompactCountries {
+    /// Returns the country with the given code, case-insensitively.
+    pub fn country(&self, code: &str) -> Option<&CompactCountry> {
+        self.countries
+            .iter()
+            .find(|country| country.code.eq_ignore_ascii_case(code.trim()))
+    }
+
+    /// Returns the subdivision with the given ISO 3166-2 code (e.g. `"US-CA"`), looking it up
+    /// in its parent country.
+    pub fn subdivision(&self, code: &str) -> Option<&CompactSubdivision> {
+        let country = code.trim().split('-').next()?;
+        self.country(country)?
+            .subdivisions
+            .iter()
+            .find(|subdivision| subdivision.code.eq_ignore_ascii_case(code.trim()))
+    }
+}
+
+impl From<CountriesResponse> for CompactCountries {
+    fn from(response: CountriesResponse) -> Self {
+        Self {
+            countries: response.countries.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<Country> for CompactCountry {
+    fn from(country: Country) -> Self {
+        Self {
+            code: country.code.into(),
+            name: country.name.into(),
+            languages: intern(country.languages),
+            codes: CompactCodes {
+                alpha_2: country.codes.alpha_2.into(),
+                alpha_3: country.codes.alpha_3.into(),
+                numeric: country.codes.numeric.into(),
+            },
+            flag: country.flag.into(),
+            subdivisions: country.subdivisions.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<Subdivision> for CompactSubdivision {
+    fn from(subdivision: Subdivision) -> Self {
+        Self {
+            code: subdivision.code.into(),
+            name: subdivision.name.into(),
+            languages: intern(subdivision.languages),
+        }
+    }
+}
+
+impl From<LanguagesResponse> for CompactLanguages {
+    fn from(response: LanguagesResponse) -> Self {
+        Self {
+            languages: response.languages.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<Language> for CompactLanguage {
+    fn from(language: Language) -> Self {
+        Self {
+            code: language.code.into(),
+            name: language.name.into(),
+        }
+    }
+}
+
+impl Request<CountriesResponse> {
+    /// Fetches the countries like [`Request::get_full`], parsing them straight into
+    /// [`CompactCountries`].
+    ///
+    /// Post-processors set with [`HolidayAPI::post_process`](crate::HolidayAPI::post_process)
+    /// are not applied.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let countries = api.countries().get_compact().await.unwrap();
+    ///     let us = countries.country("us").unwrap();
+    /// }
+    /// ```
+    pub async fn get_compact(self) -> Result<CompactCountries, HolidayAPIError> {
+        self.parse(&self.get_body().await?)
+    }
+}
+
+impl Request<LanguagesResponse> {
+    /// Fetches the languages like [`Request::get_full`], parsing them straight into
+    /// [`CompactLanguages`].
+    pub async fn get_compact(self) -> Result<CompactLanguages, HolidayAPIError> {
+        self.parse(&self.get_body().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::Fixture;
+
+    #[test]
+    fn test_language_codes_are_interned() {
+        let fixture = Fixture::named("countries").unwrap();
+        let countries: CompactCountries = fixture.parse().unwrap();
+        let us = countries.country("us").unwrap();
+        let california = countries.subdivision("us-ca").unwrap();
+        assert_eq!(&*california.name, "California");
+        assert!(Arc::ptr_eq(&us.languages[0], &california.languages[0]));
+
+        let converted = CompactCountries::from(fixture.parse::<CountriesResponse>().unwrap());
+        assert!(Arc::ptr_eq(
+            &converted.country("US").unwrap().languages[0],
+            &us.languages[0]
+        ));
+    }
+}
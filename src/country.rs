@@ -0,0 +1,135 @@
+use std::fmt;
+
+use crate::HolidayAPIError;
+
+/// Country code, normalized to trimmed uppercase (`" us "` becomes `"US"`).
+///
+/// Either an ISO 3166-1 alpha-2 or alpha-3 country code (`"US"`, `"USA"`), or an ISO 3166-2
+/// subdivision code made of an alpha-2 country code and up to three letters or digits
+/// (`"US-CA"`).
+///
+/// Every method taking a country accepts `impl Into<CountryCode>`, so both a `CountryCode` and
+/// a plain `&str` or `String` can be passed.
+///
+/// # Examples
+/// ```
+/// use holidayapi_rust::prelude::*;
+///
+/// let code = CountryCode::from(" us ");
+/// assert_eq!(code.as_str(), "US");
+///
+/// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+/// let request = api.holidays(code, 2021);
+/// let request = api.holidays("jp", 2021);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CountryCode(String);
+
+impl CountryCode {
+    pub fn new(code: &str) -> Self {
+        Self(code.trim().to_uppercase())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The country part of the code, e.g. `"US"` for `"US-CA"`.
+    pub fn country(&self) -> &str {
+        self.0.split('-').next().unwrap_or(&self.0)
+    }
+
+    /// The subdivision part of the code, e.g. `Some("CA")` for `"US-CA"`.
+    pub fn subdivision(&self) -> Option<&str> {
+        self.0.split_once('-').map(|(_, subdivision)| subdivision)
+    }
+
+    pub fn is_subdivision(&self) -> bool {
+        self.subdivision().is_some()
+    }
+
+    /// Checks the format of the code, without checking that the country exists.
+    pub fn validate(&self) -> Result<(), HolidayAPIError> {
+        let letters = |code: &str| code.chars().all(|c| c.is_ascii_uppercase());
+        let valid = match self.0.split_once('-') {
+            None => (2..=3).contains(&self.0.len()) && letters(&self.0),
+            Some((country, subdivision)) => {
+                country.len() == 2
+                    && letters(country)
+                    && (1..=3).contains(&subdivision.len())
+                    && subdivision
+                        .chars()
+                        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+            }
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(HolidayAPIError::InvalidCountry(self.0.clone()))
+        }
+    }
+}
+
+impl fmt::Display for CountryCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for CountryCode {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for CountryCode {
+    fn from(code: &str) -> Self {
+        Self::new(code)
+    }
+}
+
+impl From<String> for CountryCode {
+    fn from(code: String) -> Self {
+        Self::new(&code)
+    }
+}
+
+impl From<&String> for CountryCode {
+    fn from(code: &String) -> Self {
+        Self::new(code)
+    }
+}
+
+impl From<&CountryCode> for CountryCode {
+    fn from(code: &CountryCode) -> Self {
+        code.clone()
+    }
+}
+
+impl From<CountryCode> for String {
+    fn from(code: CountryCode) -> Self {
+        code.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_country_code_format() {
+        for code in ["us", "USA", " us-ca ", "FR-75", "JP-13", "GB-ENG"] {
+            assert!(CountryCode::new(code).validate().is_ok(), "{}", code);
+        }
+        for code in [
+            "U", "USAA", "U1", "USA-CA", "US-", "US-CALI", "US-C_", "US-CA-X",
+        ] {
+            assert!(CountryCode::new(code).validate().is_err(), "{}", code);
+        }
+        let code = CountryCode::new("us-ca");
+        assert_eq!(code.as_str(), "US-CA");
+        assert_eq!(code.country(), "US");
+        assert_eq!(code.subdivision(), Some("CA"));
+        assert!(!CountryCode::new("US").is_subdivision());
+    }
+}
@@ -0,0 +1,95 @@
+use polars::prelude::{df, DataFrame, PolarsResult};
+
+use crate::responses::{CountriesResponse, HolidaysResponse};
+
+impl HolidaysResponse {
+    /// Flattens the holidays into a [`DataFrame`], one row per holiday.
+    ///
+    /// Columns: `name`, `date` and `observed` (as dates, null when they cannot be parsed),
+    /// `public`, `country`, `uuid`, `weekday` and `observed_weekday`.
+    pub fn to_dataframe(&self) -> PolarsResult<DataFrame> {
+        let holidays = &self.holidays;
+        df!(
+            "name" => holidays.iter().map(|h| h.name.as_str()).collect::<Vec<_>>(),
+            "date" => holidays.iter().map(|h| h.parse_date()).collect::<Vec<_>>(),
+            "observed" => holidays.iter().map(|h| h.parse_observed()).collect::<Vec<_>>(),
+            "public" => holidays.iter().map(|h| h.public).collect::<Vec<_>>(),
+            "country" => holidays.iter().map(|h| h.country.as_str()).collect::<Vec<_>>(),
+            "uuid" => holidays.iter().map(|h| h.uuid.as_str()).collect::<Vec<_>>(),
+            "weekday" => holidays
+                .iter()
+                .map(|h| h.weekday.date.name.as_str())
+                .collect::<Vec<_>>(),
+            "observed_weekday" => holidays
+                .iter()
+                .map(|h| h.weekday.observed.name.as_str())
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+impl CountriesResponse {
+    /// Flattens the countries into a [`DataFrame`], one row per country.
+    ///
+    /// Columns: `code`, `name`, `alpha_2`, `alpha_3`, `numeric`, `languages` (comma separated),
+    /// `flag` and `subdivisions` (their number).
+    pub fn to_dataframe(&self) -> PolarsResult<DataFrame> {
+        let countries = &self.countries;
+        df!(
+            "code" => countries.iter().map(|c| &*c.code).collect::<Vec<_>>(),
+            "name" => countries.iter().map(|c| &*c.name).collect::<Vec<_>>(),
+            "alpha_2" => countries.iter().map(|c| &*c.codes.alpha_2).collect::<Vec<_>>(),
+            "alpha_3" => countries.iter().map(|c| &*c.codes.alpha_3).collect::<Vec<_>>(),
+            "numeric" => countries.iter().map(|c| &*c.codes.numeric).collect::<Vec<_>>(),
+            "languages" => countries.iter().map(|c| c.languages.join(",")).collect::<Vec<_>>(),
+            "flag" => countries.iter().map(|c| &*c.flag).collect::<Vec<_>>(),
+            "subdivisions" => countries
+                .iter()
+                .map(|c| c.subdivisions.len() as u32)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Flattens the subdivisions of every country into a [`DataFrame`], one row per
+    /// subdivision.
+    ///
+    /// Columns: `country`, `code`, `name` and `languages` (comma separated).
+    pub fn subdivisions_to_dataframe(&self) -> PolarsResult<DataFrame> {
+        let subdivisions: Vec<_> = self
+            .countries
+            .iter()
+            .flat_map(|country| {
+                country
+                    .subdivisions
+                    .iter()
+                    .map(move |subdivision| (country, subdivision))
+            })
+            .collect();
+        df!(
+            "country" => subdivisions.iter().map(|(c, _)| &*c.code).collect::<Vec<_>>(),
+            "code" => subdivisions.iter().map(|(_, s)| &*s.code).collect::<Vec<_>>(),
+            "name" => subdivisions.iter().map(|(_, s)| &*s.name).collect::<Vec<_>>(),
+            "languages" => subdivisions
+                .iter()
+                .map(|(_, s)| s.languages.join(","))
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::Fixture;
+    use crate::responses::{CountriesResponse, HolidaysResponse};
+
+    #[test]
+    fn test_to_dataframe() {
+        let holidays: HolidaysResponse = Fixture::named("holidays").unwrap().parse().unwrap();
+        let frame = holidays.to_dataframe().unwrap();
+        assert_eq!(frame.shape(), (3, 8));
+
+        let countries: CountriesResponse = Fixture::named("countries").unwrap().parse().unwrap();
+        assert_eq!(countries.to_dataframe().unwrap().height(), 2);
+        assert_eq!(countries.subdivisions_to_dataframe().unwrap().height(), 3);
+    }
+}
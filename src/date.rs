@@ -0,0 +1,87 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Deserializer, Serializer};
+use serde_with::{DeserializeAs, SerializeAs};
+
+/// Date format used by HolidayAPI.
+pub(crate) const FORMAT: &str = "%Y-%m-%d";
+
+/// Parses a date, tolerating missing leading zeros (`2021-1-5`) and datetimes
+/// (`2021-01-05 00:00:00`, `2021-01-05T00:00:00Z`), whose time is dropped.
+pub(crate) fn parse(date: &str) -> Option<NaiveDate> {
+    let date = date.trim();
+    NaiveDate::parse_from_str(date, FORMAT)
+        .ok()
+        .or_else(|| {
+            DateTime::parse_from_rfc3339(date)
+                .ok()
+                .map(|d| d.date_naive())
+        })
+        .or_else(|| {
+            ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"]
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+                .map(|datetime| datetime.date())
+        })
+        .or_else(|| {
+            // Dates followed by anything else, e.g. a timezone abbreviation.
+            let (day, _) = date.split_once([' ', 'T'])?;
+            NaiveDate::parse_from_str(day, FORMAT).ok()
+        })
+}
+
+/// [`serde_with`] adapter deserializing a [`NaiveDate`] from any date format HolidayAPI may
+/// send, and serializing it as `YYYY-MM-DD`.
+///
+/// # Examples
+/// ```
+/// use chrono::NaiveDate;
+/// use holidayapi_rust::prelude::*;
+/// use serde::Deserialize;
+/// use serde_with::serde_as;
+///
+/// #[serde_as]
+/// #[derive(Deserialize)]
+/// struct Row {
+///     #[serde_as(as = "FlexibleDate")]
+///     date: NaiveDate,
+/// }
+///
+/// let row: Row = serde_json::from_str(r#"{ "date": "2021-1-5 00:00:00" }"#).unwrap();
+/// assert_eq!(row.date, NaiveDate::from_ymd_opt(2021, 1, 5).unwrap());
+/// ```
+pub struct FlexibleDate;
+
+impl<'de> DeserializeAs<'de, NaiveDate> for FlexibleDate {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+        let date = String::deserialize(deserializer)?;
+        parse(&date).ok_or_else(|| serde::de::Error::custom(format!("invalid date `{}`", date)))
+    }
+}
+
+impl SerializeAs<NaiveDate> for FlexibleDate {
+    fn serialize_as<S: Serializer>(date: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&date.format(FORMAT))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_variations() {
+        let expected = NaiveDate::from_ymd_opt(2021, 1, 5);
+        for date in [
+            "2021-01-05",
+            " 2021-1-5 ",
+            "2021-01-05 00:00:00",
+            "2021-01-05T10:30:00",
+            "2021-01-05T10:30:00+02:00",
+            "2021-01-05 00:00:00 UTC",
+        ] {
+            assert_eq!(parse(date), expected, "{}", date);
+        }
+        assert_eq!(parse("05/01/2021"), None);
+        assert_eq!(parse("2021-02-30"), None);
+    }
+}
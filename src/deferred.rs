@@ -0,0 +1,189 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use futures_channel::oneshot;
+use serde::de::DeserializeOwned;
+
+#[cfg(feature = "tokio")]
+use crate::runtime::TokioRuntime;
+use crate::{
+    requests::Request,
+    runtime::Runtime,
+    shutdown::{self, TaskHandle},
+    HolidayAPIError,
+};
+
+/// Request sent later, returned by [`Request::schedule_after`] and [`Request::schedule_at`].
+///
+/// Resolves to the parsed response once the request is sent. The request is sent whether or
+/// not the handle is awaited, unless it is cancelled.
+#[derive(Debug)]
+#[must_use = "the handle resolves to the response of the request"]
+pub struct ScheduledRequest<T> {
+    at: DateTime<Utc>,
+    receiver: oneshot::Receiver<Result<T, HolidayAPIError>>,
+    task: TaskHandle,
+}
+
+impl<T> ScheduledRequest<T> {
+    /// When the request is sent, according to the clock of the client.
+    pub fn at(&self) -> DateTime<Utc> {
+        self.at
+    }
+
+    /// Cancels the request, or drops its response if it is in flight. The handle then resolves
+    /// to [`HolidayAPIError::SchedulerStopped`].
+    pub fn cancel(&self) {
+        self.task.abort();
+    }
+}
+
+impl<T> Future for ScheduledRequest<T> {
+    type Output = Result<T, HolidayAPIError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.receiver)
+            .poll(cx)
+            .map(|result| result.unwrap_or(Err(HolidayAPIError::SchedulerStopped)))
+    }
+}
+
+impl<T> Request<T>
+where
+    T: Clone + DeserializeOwned + Send + Sync + 'static,
+{
+    /// Sends the request after `delay` on the current tokio runtime, see [`ScheduledRequest`].
+    ///
+    /// The request goes through the budget and rate limiting of the client when it is sent,
+    /// use [`Request::schedule_at`] to send it right after they reset.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let scheduled = api
+    ///         .holidays("us", 2021)
+    ///         .schedule_after(Duration::from_secs(60));
+    ///     println!("Fetching the holidays at {}", scheduled.at());
+    ///     let holidays = scheduled.await;
+    /// }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn schedule_after(self, delay: Duration) -> ScheduledRequest<T> {
+        self.schedule_with_runtime(delay, Arc::new(TokioRuntime))
+    }
+
+    /// Sends the request at `at` on the current tokio runtime, or right away if it is in the
+    /// past, according to the clock of the client, see [`ScheduledRequest`].
+    ///
+    /// # Examples
+    ///
+    /// Fetching next year's holidays once the quota resets, at the start of the next month
+    ///
+    /// ```no_run
+    /// use chrono::{Datelike, TimeZone, Utc};
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let today = Utc::now().date_naive();
+    ///     let (year, month) = match today.month() {
+    ///         12 => (today.year() + 1, 1),
+    ///         month => (today.year(), month + 1),
+    ///     };
+    ///     let reset = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).unwrap();
+    ///     let holidays = api.holidays("us", today.year() + 1).schedule_at(reset).await;
+    /// }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn schedule_at(self, at: DateTime<Utc>) -> ScheduledRequest<T> {
+        let delay = (at - self.api().clock.now()).to_std().unwrap_or_default();
+        self.schedule_with_runtime(delay, Arc::new(TokioRuntime))
+    }
+
+    /// Sends the request after `delay`, waiting with the given runtime, see
+    /// [`Request::schedule_after`].
+    pub fn schedule_with_runtime(
+        self,
+        delay: Duration,
+        runtime: Arc<dyn Runtime>,
+    ) -> ScheduledRequest<T> {
+        let at = chrono::Duration::from_std(delay)
+            .ok()
+            .and_then(|delay| self.api().clock.now().checked_add_signed(delay))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        let (sender, receiver) = oneshot::channel();
+        let (task, guard) = shutdown::task();
+        runtime.clone().spawn(Box::pin(async move {
+            if guard.until_aborted(runtime.sleep(delay)).await.is_none() {
+                return;
+            }
+            if let Some(result) = guard.until_aborted(self.get_full()).await {
+                let _ = sender.send(result);
+            }
+        }));
+        ScheduledRequest { at, receiver, task }
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use std::time::Instant;
+
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::{
+        clock::FixedClock, fixtures::Fixture, mock::MockTransport, test_support::EXPIRED_KEY,
+        HolidayAPI,
+    };
+
+    #[tokio::test]
+    async fn test_schedule() {
+        let now = Utc.with_ymd_and_hms(2021, 6, 30, 23, 59, 59).unwrap();
+        let transport = MockTransport::new(Fixture::named("holidays").unwrap().mock_response());
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+            .clock(FixedClock(now));
+
+        let start = Instant::now();
+        let scheduled = api
+            .holidays("us", 2021)
+            .schedule_at(now + chrono::Duration::milliseconds(50));
+        assert_eq!(scheduled.at(), now + chrono::Duration::milliseconds(50));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(transport.calls(), 0);
+        assert!(!scheduled.await.unwrap().holidays.is_empty());
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        let past = api
+            .holidays("us", 2021)
+            .schedule_at(now - chrono::Duration::days(1));
+        assert!(past.await.is_ok());
+
+        let cancelled = api
+            .holidays("us", 2021)
+            .schedule_after(Duration::from_secs(3600));
+        cancelled.cancel();
+        assert!(matches!(
+            cancelled.await,
+            Err(HolidayAPIError::SchedulerStopped)
+        ));
+        assert_eq!(transport.calls(), 2);
+    }
+}
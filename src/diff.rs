@@ -0,0 +1,389 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::{
+    cache,
+    responses::{Holiday, HolidaysResponse},
+};
+
+/// A holiday whose date or observed date changed between two pulls.
+#[derive(Debug, Clone)]
+pub struct HolidayChange {
+    pub before: Holiday,
+    pub after: Holiday,
+}
+
+impl HolidayChange {
+    pub fn date_changed(&self) -> bool {
+        self.before.date != self.after.date
+    }
+
+    pub fn observed_changed(&self) -> bool {
+        self.before.observed != self.after.observed
+    }
+}
+
+/// Identity under which holidays are matched: their `uuid`, parsed with the `uuid` feature so
+/// that spellings differing in case or format match, raw otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum HolidayId {
+    #[cfg(feature = "uuid")]
+    Uuid(uuid::Uuid),
+    Raw(String),
+}
+
+impl HolidayId {
+    pub(crate) fn of(holiday: &Holiday) -> Self {
+        #[cfg(feature = "uuid")]
+        if let Some(uuid) = holiday.uuid() {
+            return Self::Uuid(uuid);
+        }
+        Self::Raw(holiday.uuid.clone())
+    }
+}
+
+/// Differences between two lists of holidays, matched by their `uuid`.
+///
+/// With the `uuid` feature, identifiers are compared as parsed UUIDs, so `"ABC…"` and `"abc…"`
+/// are the same holiday.
+#[derive(Debug, Clone, Default)]
+pub struct HolidaysDiff {
+    /// Holidays only present in the newer list.
+    pub added: Vec<Holiday>,
+    /// Holidays only present in the older list.
+    pub removed: Vec<Holiday>,
+    /// Holidays present in both lists, with a different date or observed date.
+    pub modified: Vec<HolidayChange>,
+}
+
+impl HolidaysDiff {
+    /// Compares an older list of holidays with a newer one.
+    pub fn between(old: &[Holiday], new: &[Holiday]) -> Self {
+        let old_by_uuid: HashMap<HolidayId, &Holiday> = old
+            .iter()
+            .map(|holiday| (HolidayId::of(holiday), holiday))
+            .collect();
+        let new_by_uuid: HashMap<HolidayId, &Holiday> = new
+            .iter()
+            .map(|holiday| (HolidayId::of(holiday), holiday))
+            .collect();
+
+        let mut diff = Self::default();
+        for holiday in new {
+            match old_by_uuid.get(&HolidayId::of(holiday)) {
+                None => diff.added.push(holiday.clone()),
+                Some(before)
+                    if before.date != holiday.date || before.observed != holiday.observed =>
+                {
+                    diff.modified.push(HolidayChange {
+                        before: (*before).clone(),
+                        after: holiday.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        diff.removed = old
+            .iter()
+            .filter(|holiday| !new_by_uuid.contains_key(&HolidayId::of(holiday)))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+impl HolidaysResponse {
+    /// Compares this response with a newer pull of the same data.
+    pub fn diff(&self, newer: &HolidaysResponse) -> HolidaysDiff {
+        HolidaysDiff::between(&self.holidays, &newer.holidays)
+    }
+}
+
+/// Two holidays with the same `uuid`, date and observed date which disagree on another field,
+/// such as their name when merging responses in two languages.
+#[derive(Debug, Clone)]
+pub struct HolidayConflict {
+    pub kept: Holiday,
+    pub discarded: Holiday,
+}
+
+/// Holidays merged from several responses, without duplicates.
+#[derive(Debug, Clone, Default)]
+pub struct Deduplicated {
+    /// Distinct holidays, in the order they first appeared.
+    pub holidays: Vec<Holiday>,
+    /// Number of holidays removed, conflicting ones included.
+    pub removed: usize,
+    /// Removed holidays which differ from the one kept.
+    pub conflicts: Vec<HolidayConflict>,
+}
+
+impl Deduplicated {
+    /// Collapses holidays sharing a `uuid`, date and observed date, keeping the first one.
+    /// Identifiers are compared as in [`HolidaysDiff`], dates once parsed and country codes
+    /// case-insensitively, as in the cache keys of requests, so that results of requests
+    /// spelling them differently still collapse.
+    ///
+    /// Overlapping requests, such as two years whose holidays are observed across New Year,
+    /// return the same holiday twice. Holidays moved to another observed date are kept apart.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let mut holidays = api.holidays("us", 2021).get().await.unwrap();
+    ///     holidays.extend(api.holidays("us", 2022).get().await.unwrap());
+    ///     let merged = Deduplicated::from_holidays(holidays);
+    ///     for conflict in merged.conflicts {
+    ///         println!("{} differs from {}", conflict.discarded.name, conflict.kept.name);
+    ///     }
+    /// }
+    /// ```
+    pub fn from_holidays(holidays: impl IntoIterator<Item = Holiday>) -> Self {
+        let mut merged = Self::default();
+        let mut seen: HashMap<(HolidayId, String, String), usize> = HashMap::new();
+        for holiday in holidays {
+            let key = (
+                HolidayId::of(&holiday),
+                canonical_date(holiday.parse_date(), &holiday.date),
+                canonical_date(holiday.parse_observed(), &holiday.observed),
+            );
+            match seen.get(&key) {
+                Some(&index) => {
+                    merged.removed += 1;
+                    let kept = &merged.holidays[index];
+                    if kept.name != holiday.name
+                        || kept.public != holiday.public
+                        || canonical_code(&kept.country) != canonical_code(&holiday.country)
+                        || !kept
+                            .subdivisions
+                            .iter()
+                            .map(|code| canonical_code(code))
+                            .eq(holiday.subdivisions.iter().map(|code| canonical_code(code)))
+                    {
+                        merged.conflicts.push(HolidayConflict {
+                            kept: kept.clone(),
+                            discarded: holiday,
+                        });
+                    }
+                }
+                None => {
+                    seen.insert(key, merged.holidays.len());
+                    merged.holidays.push(holiday);
+                }
+            }
+        }
+        merged
+    }
+}
+
+/// Date in the ISO format when it could be parsed, as sent otherwise.
+fn canonical_date(parsed: Option<NaiveDate>, raw: &str) -> String {
+    parsed.map_or_else(|| raw.to_string(), |date| date.to_string())
+}
+
+/// Country or subdivision code normalized like the `country` parameter of cache keys.
+fn canonical_code(code: &str) -> String {
+    cache::canonical_value("country", code)
+}
+
+/// A holiday present in two consecutive years, falling on a different day of the year.
+#[derive(Debug, Clone)]
+pub struct HolidayShift {
+    pub previous: Holiday,
+    pub current: Holiday,
+    /// Days between the current date and the anniversary of the previous one, negative when
+    /// the holiday moved earlier.
+    pub days: i64,
+}
+
+/// Alignment of the holidays of a country over two years.
+///
+/// Holidays are matched by `uuid` first, compared as in [`HolidaysDiff`], then by name for
+/// those whose `uuid` changed between the two years.
+#[derive(Debug, Clone, Default)]
+pub struct YearOverYear {
+    /// Holidays only present in the current year.
+    pub added: Vec<Holiday>,
+    /// Holidays only present in the previous year.
+    pub removed: Vec<Holiday>,
+    /// Holidays present in both years on a different day, e.g. movable feasts.
+    pub shifted: Vec<HolidayShift>,
+}
+
+impl YearOverYear {
+    pub fn between(previous: &[Holiday], current: &[Holiday]) -> Self {
+        let mut unmatched: Vec<&Holiday> = previous.iter().collect();
+        let mut comparison = Self::default();
+        let mut by_name = Vec::new();
+        for holiday in current {
+            let id = HolidayId::of(holiday);
+            match unmatched
+                .iter()
+                .position(|before| HolidayId::of(before) == id)
+            {
+                Some(index) => comparison.push_match(unmatched.remove(index), holiday),
+                None => by_name.push(holiday),
+            }
+        }
+        for holiday in by_name {
+            match unmatched
+                .iter()
+                .position(|before| before.name == holiday.name)
+            {
+                Some(index) => comparison.push_match(unmatched.remove(index), holiday),
+                None => comparison.added.push(holiday.clone()),
+            }
+        }
+        comparison.removed = unmatched.into_iter().cloned().collect();
+        comparison
+    }
+
+    fn push_match(&mut self, previous: &Holiday, current: &Holiday) {
+        let days = match (previous.parse_date(), current.parse_date()) {
+            (Some(before), Some(after)) => shift(before, after),
+            _ => return,
+        };
+        if days != 0 {
+            self.shifted.push(HolidayShift {
+                previous: previous.clone(),
+                current: current.clone(),
+                days,
+            });
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.shifted.is_empty()
+    }
+}
+
+/// Days between `after` and the anniversary of `before` in the year of `after`, February 29th
+/// falling on February 28th in common years.
+fn shift(before: NaiveDate, after: NaiveDate) -> i64 {
+    let anniversary = before
+        .with_year(after.year())
+        .or_else(|| before.pred_opt()?.with_year(after.year()));
+    anniversary.map_or(0, |anniversary| (after - anniversary).num_days())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    fn holiday(uuid: &str, date: &str, observed: &str) -> Holiday {
+        Holiday {
+            name: uuid.into(),
+            uuid: uuid.into(),
+            observed: observed.into(),
+            ..test_support::holiday(date)
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = [
+            holiday("a", "2021-01-01", "2021-01-01"),
+            holiday("b", "2021-07-04", "2021-07-04"),
+            holiday("c", "2021-12-25", "2021-12-25"),
+        ];
+        let new = [
+            holiday("a", "2021-01-01", "2021-01-01"),
+            holiday("b", "2021-07-04", "2021-07-05"),
+            holiday("d", "2021-11-25", "2021-11-25"),
+        ];
+        let diff = HolidaysDiff::between(&old, &new);
+        assert_eq!(diff.added[0].uuid, "d");
+        assert_eq!(diff.removed[0].uuid, "c");
+        assert_eq!(diff.modified.len(), 1);
+        assert!(diff.modified[0].observed_changed());
+        assert!(!diff.modified[0].date_changed());
+        assert!(HolidaysDiff::between(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_deduplicated() {
+        let mut translated = holiday("a", "2021-01-01", "2021-01-01");
+        translated.name = "Neujahr".into();
+        let merged = Deduplicated::from_holidays([
+            holiday("a", "2021-01-01", "2021-01-01"),
+            holiday("b", "2022-01-01", "2021-12-31"),
+            holiday("b", "2022-01-01", "2021-12-31"),
+            holiday("b", "2022-01-01", "2022-01-03"),
+            translated,
+        ]);
+        let observed: Vec<&str> = merged
+            .holidays
+            .iter()
+            .map(|holiday| holiday.observed.as_str())
+            .collect();
+        assert_eq!(observed, ["2021-01-01", "2021-12-31", "2022-01-03"]);
+        assert_eq!(merged.removed, 2);
+        assert_eq!(merged.conflicts.len(), 1);
+        assert_eq!(merged.conflicts[0].kept.name, "a");
+        assert_eq!(merged.conflicts[0].discarded.name, "Neujahr");
+    }
+
+    #[test]
+    fn test_deduplicated_canonicalizes() {
+        let kept = holiday("a", "2021-01-01", "2021-01-01");
+        let mut respelled = holiday("a", "2021-1-1 00:00:00", "2021-01-01");
+        respelled.country = " us ".into();
+        let merged = Deduplicated::from_holidays([kept, respelled]);
+        assert_eq!(merged.holidays.len(), 1);
+        assert_eq!(merged.removed, 1);
+        assert!(merged.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_year_over_year() {
+        let previous = [
+            holiday("new-year-2020", "2020-01-01", "2020-01-01"),
+            holiday("easter", "2020-04-12", "2020-04-12"),
+            holiday("leap", "2020-02-29", "2020-02-29"),
+            holiday("removed", "2020-05-01", "2020-05-01"),
+        ];
+        let mut new_year = holiday("new-year-2021", "2021-01-01", "2021-01-01");
+        new_year.name = "new-year-2020".into();
+        let current = [
+            new_year,
+            holiday("easter", "2021-04-04", "2021-04-04"),
+            holiday("leap", "2021-02-28", "2021-02-28"),
+            holiday("added", "2021-06-01", "2021-06-01"),
+        ];
+        let comparison = YearOverYear::between(&previous, &current);
+        assert_eq!(comparison.added[0].uuid, "added");
+        assert_eq!(comparison.removed[0].uuid, "removed");
+        assert_eq!(comparison.shifted.len(), 1);
+        assert_eq!(comparison.shifted[0].current.uuid, "easter");
+        assert_eq!(comparison.shifted[0].days, -8);
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_uuids_match_case_insensitively() {
+        let old = [holiday(
+            "82f78b8a-019e-479e-a19f-99040275f9bf",
+            "2021-01-01",
+            "2021-01-01",
+        )];
+        let new = [Holiday {
+            uuid: old[0].uuid.to_uppercase(),
+            ..old[0].clone()
+        }];
+        assert!(HolidaysDiff::between(&old, &new).is_empty());
+        assert!(YearOverYear::between(&old, &new).is_empty());
+        let merged = Deduplicated::from_holidays(old.into_iter().chain(new));
+        assert_eq!(merged.holidays.len(), 1);
+        assert!(merged.conflicts.is_empty());
+    }
+}
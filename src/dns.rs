@@ -0,0 +1,147 @@
+use std::{
+    collections::HashMap,
+    fmt, io,
+    net::{SocketAddr, ToSocketAddrs},
+    sync::Arc,
+};
+
+type Lookup = Arc<dyn Fn(&str) -> io::Result<Vec<SocketAddr>> + Send + Sync>;
+
+/// DNS resolution used by the built-in transports, set with
+/// [`HolidayAPI::resolver`](crate::HolidayAPI::resolver).
+///
+/// Hosts mapped with [`host`](Self::host) resolve to the given addresses, other hosts go
+/// through the [`custom`](Self::custom) lookup if any, then through the system resolver. This
+/// allows reaching HolidayAPI in environments where `holidayapi.com` only resolves through an
+/// internal forwarder.
+///
+/// The port of the addresses is replaced by the port of the requested URL. Mapped hosts are
+/// answered in place. The reqwest and hyper transports run the custom and system lookups on
+/// the blocking thread pool of tokio, the ureq transport on the thread sending the request.
+///
+/// # Examples
+/// ```
+/// use holidayapi_rust::prelude::*;
+///
+/// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+///     .unwrap()
+///     .resolver(&Resolver::new().host("holidayapi.com", &["10.0.0.8:443".parse().unwrap()]))
+///     .unwrap();
+/// ```
+#[derive(Clone, Default)]
+pub struct Resolver {
+    hosts: HashMap<String, Vec<SocketAddr>>,
+    custom: Option<Lookup>,
+}
+
+impl fmt::Debug for Resolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Resolver")
+            .field("hosts", &self.hosts)
+            .field("custom", &self.custom.is_some())
+            .finish()
+    }
+}
+
+impl Resolver {
+    /// Resolver deferring to the system resolver, see [`host`](Self::host) and
+    /// [`custom`](Self::custom).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves `host` to `addresses`, which are tried in order.
+    pub fn host(&mut self, host: &str, addresses: &[SocketAddr]) -> Self {
+        self.hosts
+            .insert(host.to_ascii_lowercase(), addresses.to_vec());
+        self.to_owned()
+    }
+
+    /// Resolves hosts which are not mapped with [`host`](Self::host) with `lookup`, from a
+    /// host name to its addresses.
+    pub fn custom(
+        &mut self,
+        lookup: impl Fn(&str) -> io::Result<Vec<SocketAddr>> + Send + Sync + 'static,
+    ) -> Self {
+        self.custom = Some(Arc::new(lookup));
+        self.to_owned()
+    }
+
+    /// Whether the resolver defers every host to the system resolver.
+    pub fn is_system(&self) -> bool {
+        self.hosts.is_empty() && self.custom.is_none()
+    }
+
+    /// Addresses of `host`, with port `port`.
+    pub fn lookup(&self, host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        let mut addresses = match (self.hosts.get(&host.to_ascii_lowercase()), &self.custom) {
+            (Some(addresses), _) => addresses.clone(),
+            (None, Some(custom)) => custom(host)?,
+            (None, None) => (host, port).to_socket_addrs()?.collect(),
+        };
+        if addresses.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no address found for {}", host),
+            ));
+        }
+        for address in &mut addresses {
+            address.set_port(port);
+        }
+        Ok(addresses)
+    }
+
+    /// [`lookup`](Self::lookup) for async transports, running the custom and system lookups
+    /// on the blocking thread pool so that they do not stall the runtime.
+    #[cfg(any(feature = "reqwest", feature = "hyper"))]
+    pub(crate) async fn lookup_blocking(
+        &self,
+        host: &str,
+        port: u16,
+    ) -> io::Result<Vec<SocketAddr>> {
+        if self.hosts.contains_key(&host.to_ascii_lowercase()) {
+            return self.lookup(host, port);
+        }
+        let resolver = self.clone();
+        let host = host.to_string();
+        tokio::task::spawn_blocking(move || resolver.lookup(&host, port))
+            .await
+            .map_err(io::Error::other)?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolver_lookup() {
+        let internal: SocketAddr = "10.0.0.8:8443".parse().unwrap();
+        let forwarded: SocketAddr = "10.0.0.9:0".parse().unwrap();
+        let resolver = Resolver::new().host("HolidayAPI.com", &[internal]);
+        assert!(!resolver.is_system());
+        assert_eq!(
+            resolver.lookup("holidayapi.com", 443).unwrap(),
+            ["10.0.0.8:443".parse().unwrap()]
+        );
+        assert!(Resolver::new()
+            .host("holidayapi.com", &[])
+            .lookup("holidayapi.com", 443)
+            .is_err());
+
+        let resolver = resolver.clone().custom(move |host| match host {
+            "forwarded.test" => Ok(vec![forwarded]),
+            _ => Err(io::Error::new(io::ErrorKind::NotFound, host)),
+        });
+        assert_eq!(
+            resolver.lookup("forwarded.test", 80).unwrap(),
+            ["10.0.0.9:80".parse().unwrap()]
+        );
+        assert!(resolver.lookup("unknown.test", 80).is_err());
+        assert_eq!(
+            resolver.lookup("holidayapi.com", 443).unwrap()[0].ip(),
+            internal.ip()
+        );
+        assert!(Resolver::new().is_system());
+    }
+}
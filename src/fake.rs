@@ -0,0 +1,341 @@
+use chrono::{Datelike, NaiveDate};
+
+use crate::responses::{
+    APIRequests, Codes, CountriesResponse, Country, Date, Holiday, HolidaysResponse, Language,
+    LanguagesResponse, Subdivision, Weekday,
+};
+
+const HOLIDAY_NAMES: &[&str] = &[
+    "New Year's Day",
+    "Epiphany",
+    "Carnival",
+    "Good Friday",
+    "Easter Monday",
+    "Labour Day",
+    "Ascension Day",
+    "Whit Monday",
+    "Independence Day",
+    "National Day",
+    "Assumption Day",
+    "Constitution Day",
+    "All Saints' Day",
+    "Thanksgiving Day",
+    "Christmas Eve",
+    "Christmas Day",
+    "Boxing Day",
+    "New Year's Eve",
+];
+
+struct FakeCountry {
+    code: &'static str,
+    name: &'static str,
+    alpha_3: &'static str,
+    numeric: &'static str,
+    languages: &'static [&'static str],
+    /// Local code and name of the subdivisions.
+    subdivisions: &'static [(&'static str, &'static str)],
+}
+
+const COUNTRIES: &[FakeCountry] = &[
+    FakeCountry {
+        code: "US",
+        name: "United States",
+        alpha_3: "USA",
+        numeric: "840",
+        languages: &["en"],
+        subdivisions: &[("CA", "California"), ("NY", "New York"), ("TX", "Texas")],
+    },
+    FakeCountry {
+        code: "CA",
+        name: "Canada",
+        alpha_3: "CAN",
+        numeric: "124",
+        languages: &["en", "fr"],
+        subdivisions: &[("ON", "Ontario"), ("QC", "Quebec")],
+    },
+    FakeCountry {
+        code: "DE",
+        name: "Germany",
+        alpha_3: "DEU",
+        numeric: "276",
+        languages: &["de"],
+        subdivisions: &[("BY", "Bavaria"), ("BE", "Berlin")],
+    },
+    FakeCountry {
+        code: "FR",
+        name: "France",
+        alpha_3: "FRA",
+        numeric: "250",
+        languages: &["fr"],
+        subdivisions: &[("IDF", "Île-de-France")],
+    },
+    FakeCountry {
+        code: "JP",
+        name: "Japan",
+        alpha_3: "JPN",
+        numeric: "392",
+        languages: &["ja"],
+        subdivisions: &[("13", "Tokyo")],
+    },
+    FakeCountry {
+        code: "BR",
+        name: "Brazil",
+        alpha_3: "BRA",
+        numeric: "076",
+        languages: &["pt"],
+        subdivisions: &[("SP", "São Paulo"), ("RJ", "Rio de Janeiro")],
+    },
+    FakeCountry {
+        code: "IN",
+        name: "India",
+        alpha_3: "IND",
+        numeric: "356",
+        languages: &["hi", "en"],
+        subdivisions: &[("MH", "Maharashtra")],
+    },
+    FakeCountry {
+        code: "AE",
+        name: "United Arab Emirates",
+        alpha_3: "ARE",
+        numeric: "784",
+        languages: &["ar"],
+        subdivisions: &[("DU", "Dubai")],
+    },
+];
+
+const LANGUAGES: &[(&str, &str)] = &[
+    ("ar", "Arabic"),
+    ("de", "German"),
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fr", "French"),
+    ("hi", "Hindi"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("nl", "Dutch"),
+    ("pt", "Portuguese"),
+    ("zh", "Chinese"),
+];
+
+/// Seeded generator of realistic responses, for tests running without network access.
+///
+/// The same seed always generates the same values.
+///
+/// # Examples
+/// ```
+/// use holidayapi_rust::prelude::*;
+///
+/// let mut faker = Faker::new(42);
+/// let response = faker.holidays_response("us", 2021, 10);
+/// assert_eq!(response.holidays.len(), 10);
+/// assert!(response.holidays.iter().all(|holiday| holiday.date.starts_with("2021-")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Faker {
+    state: u64,
+}
+
+impl Faker {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Next pseudo-random number, using SplitMix64.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Pseudo-random number in `0..bound`.
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound.max(1)
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+
+    fn uuid(&mut self) -> String {
+        let hex = format!("{:016x}{:016x}", self.next_u64(), self.next_u64());
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..]
+        )
+    }
+
+    fn weekday(date: NaiveDate) -> Date {
+        Date {
+            name: date.format("%A").to_string(),
+            numeric: date.weekday().number_from_monday().to_string(),
+        }
+    }
+
+    pub fn requests(&mut self) -> APIRequests {
+        let used = self.below(10_000) as u32;
+        APIRequests {
+            available: 10_000 - used,
+            used,
+            resets: "2021-01-01 00:00:00".into(),
+        }
+    }
+
+    /// Generates a holiday of `country` in `year`, observed on the next Monday when it falls on
+    /// the weekend.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `year` is outside of the range of [`NaiveDate`].
+    pub fn holiday(&mut self, country: &str, year: i32) -> Holiday {
+        let start = NaiveDate::from_ymd_opt(year, 1, 1).expect("Year is in chrono's range");
+        let days = if NaiveDate::from_ymd_opt(year, 2, 29).is_some() {
+            366
+        } else {
+            365
+        };
+        let date = start + chrono::Duration::days(self.below(days) as i64);
+        let observed = match date.weekday() {
+            chrono::Weekday::Sat => date + chrono::Duration::days(2),
+            chrono::Weekday::Sun => date + chrono::Duration::days(1),
+            _ => date,
+        };
+        Holiday {
+            name: self.pick(HOLIDAY_NAMES).to_string(),
+            date: date.format(crate::date::FORMAT).to_string(),
+            observed: observed.format(crate::date::FORMAT).to_string(),
+            public: self.below(4) != 0,
+            country: country.to_uppercase(),
+            uuid: self.uuid(),
+            weekday: Weekday {
+                date: Self::weekday(date),
+                observed: Self::weekday(observed),
+            },
+            subdivisions: Vec::new(),
+        }
+    }
+
+    /// Generates `count` holidays of `country` in `year`, sorted by date.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `year` is outside of the range of [`NaiveDate`], see [`Faker::holiday`].
+    pub fn holidays_response(
+        &mut self,
+        country: &str,
+        year: i32,
+        count: usize,
+    ) -> HolidaysResponse {
+        let mut holidays: Vec<Holiday> = (0..count).map(|_| self.holiday(country, year)).collect();
+        holidays.sort_by(|a, b| a.date.cmp(&b.date));
+        HolidaysResponse {
+            requests: self.requests(),
+            status: 200,
+            holidays,
+            error: None,
+            warning: None,
+        }
+    }
+
+    pub fn country(&mut self) -> Country {
+        let country = self.pick(COUNTRIES);
+        let languages: Vec<String> = country
+            .languages
+            .iter()
+            .map(|code| code.to_string())
+            .collect();
+        Country {
+            code: country.code.into(),
+            name: country.name.into(),
+            languages: languages.clone(),
+            codes: Codes {
+                alpha_2: country.code.into(),
+                alpha_3: country.alpha_3.into(),
+                numeric: country.numeric.into(),
+            },
+            flag: format!("https://flagsapi.com/{}/flat/64.png", country.code),
+            subdivisions: country
+                .subdivisions
+                .iter()
+                .map(|(local, name)| Subdivision {
+                    code: format!("{}-{}", country.code, local),
+                    name: name.to_string(),
+                    languages: languages.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Generates `count` countries, picked with repetition from a fixed set.
+    pub fn countries_response(&mut self, count: usize) -> CountriesResponse {
+        CountriesResponse {
+            requests: self.requests(),
+            status: 200,
+            error: None,
+            warning: None,
+            countries: (0..count).map(|_| self.country()).collect(),
+        }
+    }
+
+    pub fn language(&mut self) -> Language {
+        let (code, name) = *self.pick(LANGUAGES);
+        Language {
+            code: code.into(),
+            name: name.into(),
+        }
+    }
+
+    /// Generates `count` languages, picked with repetition from a fixed set.
+    pub fn languages_response(&mut self, count: usize) -> LanguagesResponse {
+        LanguagesResponse {
+            requests: self.requests(),
+            status: 200,
+            languages: (0..count).map(|_| self.language()).collect(),
+            error: None,
+            warning: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_faker_is_deterministic() {
+        let first = Faker::new(7).holidays_response("jp", 2020, 20);
+        let second = Faker::new(7).holidays_response("jp", 2020, 20);
+        let uuids = |response: &HolidaysResponse| -> Vec<String> {
+            response.holidays.iter().map(|h| h.uuid.clone()).collect()
+        };
+        assert_eq!(uuids(&first), uuids(&second));
+        assert_ne!(
+            uuids(&first),
+            uuids(&Faker::new(8).holidays_response("jp", 2020, 20))
+        );
+
+        for holiday in &first.holidays {
+            let date = holiday.parse_date().unwrap();
+            let observed = holiday.parse_observed().unwrap();
+            assert_eq!(date.year(), 2020);
+            assert!(observed >= date);
+            assert!(!matches!(
+                observed.weekday(),
+                chrono::Weekday::Sat | chrono::Weekday::Sun
+            ));
+            assert_eq!(holiday.country, "JP");
+        }
+        let mut faker = Faker::new(1);
+        assert_eq!(faker.languages_response(3).languages.len(), 3);
+        let country = faker.countries_response(1).countries.remove(0);
+        assert!(country
+            .subdivisions
+            .iter()
+            .all(|subdivision| subdivision.country_code() == country.code));
+    }
+}
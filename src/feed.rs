@@ -0,0 +1,220 @@
+use std::fmt::Write;
+
+use crate::responses::{Holiday, HolidaysResponse};
+
+/// Title, link and author of a feed of holidays, rendered as RSS 2.0 or Atom.
+///
+/// # Examples
+/// ```no_run
+/// use holidayapi_rust::prelude::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+///     let upcoming = api.upcoming_in("us", 90).await.unwrap();
+///     let feed = Feed::new("US holidays", "https://intranet.example/holidays/us")
+///         .description("Holidays of the next 90 days");
+///     std::fs::write("us.xml", feed.rss(&upcoming)).unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Feed {
+    title: String,
+    link: String,
+    description: String,
+    author: String,
+}
+
+impl Feed {
+    pub fn new(title: &str, link: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            link: link.to_string(),
+            description: title.to_string(),
+            author: title.to_string(),
+        }
+    }
+
+    /// Sets the description of the feed, its title by default.
+    pub fn description(&mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self.to_owned()
+    }
+
+    /// Sets the author of an Atom feed, its title by default.
+    pub fn author(&mut self, author: &str) -> Self {
+        self.author = author.to_string();
+        self.to_owned()
+    }
+
+    /// Renders the holidays as an RSS 2.0 document, one item per holiday dated at its start.
+    pub fn rss(&self, holidays: &[Holiday]) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<rss version=\"2.0\">\n<channel>\n");
+        let _ = writeln!(xml, "<title>{}</title>", escape(&self.title));
+        let _ = writeln!(xml, "<link>{}</link>", escape(&self.link));
+        let _ = writeln!(
+            xml,
+            "<description>{}</description>",
+            escape(&self.description)
+        );
+        for holiday in holidays {
+            xml.push_str("<item>\n");
+            let _ = writeln!(xml, "<title>{}</title>", escape(&holiday.name));
+            let _ = writeln!(
+                xml,
+                "<description>{}</description>",
+                escape(&summary(holiday))
+            );
+            let _ = writeln!(
+                xml,
+                "<guid isPermaLink=\"false\">{}</guid>",
+                escape(&holiday.uuid)
+            );
+            if let Some(starts_at) = holiday.starts_at() {
+                let _ = writeln!(xml, "<pubDate>{}</pubDate>", starts_at.to_rfc2822());
+            }
+            xml.push_str("</item>\n");
+        }
+        xml.push_str("</channel>\n</rss>\n");
+        xml
+    }
+
+    /// Renders the holidays as an Atom document, one entry per holiday updated at its start and
+    /// linking to the feed's link with the [slug](Holiday::slug) of the holiday as fragment.
+    /// The feed is updated at the start of its latest holiday.
+    pub fn atom(&self, holidays: &[Holiday]) -> String {
+        let updated = holidays
+            .iter()
+            .filter_map(Holiday::starts_at)
+            .max()
+            .unwrap_or_default()
+            .to_rfc3339();
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        let _ = writeln!(xml, "<title>{}</title>", escape(&self.title));
+        let _ = writeln!(xml, "<subtitle>{}</subtitle>", escape(&self.description));
+        let _ = writeln!(xml, "<link href=\"{}\"/>", escape(&self.link));
+        let _ = writeln!(xml, "<id>{}</id>", escape(&self.link));
+        let _ = writeln!(xml, "<updated>{}</updated>", updated);
+        let _ = writeln!(
+            xml,
+            "<author>\n<name>{}</name>\n</author>",
+            escape(&self.author)
+        );
+        let base = self.link.split('#').next().unwrap_or_default();
+        for holiday in holidays {
+            xml.push_str("<entry>\n");
+            let _ = writeln!(xml, "<title>{}</title>", escape(&holiday.name));
+            let _ = writeln!(xml, "<id>urn:uuid:{}</id>", escape(&holiday.uuid));
+            let link = format!("{}#{}", base, holiday.slug());
+            let _ = writeln!(xml, "<link href=\"{}\"/>", escape(&link));
+            let updated = holiday.starts_at().unwrap_or_default().to_rfc3339();
+            let _ = writeln!(xml, "<updated>{}</updated>", updated);
+            let _ = writeln!(xml, "<summary>{}</summary>", escape(&summary(holiday)));
+            xml.push_str("</entry>\n");
+        }
+        xml.push_str("</feed>\n");
+        xml
+    }
+}
+
+impl HolidaysResponse {
+    /// Renders the holidays of this response as an RSS 2.0 document, see [`Feed::rss`].
+    pub fn to_rss(&self, feed: &Feed) -> String {
+        feed.rss(&self.holidays)
+    }
+
+    /// Renders the holidays of this response as an Atom document, see [`Feed::atom`].
+    pub fn to_atom(&self, feed: &Feed) -> String {
+        feed.atom(&self.holidays)
+    }
+}
+
+/// One line description of a holiday: `"Public holiday in US on 2021-07-04, observed on
+/// 2021-07-05"`.
+fn summary(holiday: &Holiday) -> String {
+    let kind = if holiday.public {
+        "Public holiday"
+    } else {
+        "Holiday"
+    };
+    let mut summary = format!("{} in {} on {}", kind, holiday.country, holiday.date);
+    if holiday.observed != holiday.date {
+        let _ = write!(summary, ", observed on {}", holiday.observed);
+    }
+    summary
+}
+
+/// Escapes the characters with a special meaning in XML text and attributes.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::Fixture;
+
+    #[test]
+    fn test_feeds() {
+        let mut response: HolidaysResponse = Fixture::named("holidays").unwrap().parse().unwrap();
+        response.holidays.truncate(2);
+        response.holidays[0].name = "Tom & Jerry's <Day>".into();
+        let feed = Feed::new(
+            "US holidays",
+            "https://intranet.example/?country=us&year=2021",
+        );
+
+        let rss = response.to_rss(&feed);
+        assert!(
+            rss.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">")
+        );
+        assert!(rss.contains("<link>https://intranet.example/?country=us&amp;year=2021</link>"));
+        assert!(rss.contains("<title>Tom &amp; Jerry&apos;s &lt;Day&gt;</title>"));
+        assert_eq!(rss.matches("<item>").count(), 2);
+        let first = &response.holidays[0];
+        assert!(rss.contains(&format!(
+            "<pubDate>{}</pubDate>",
+            first.starts_at().unwrap().to_rfc2822()
+        )));
+        assert!(rss.contains(&format!(
+            "<guid isPermaLink=\"false\">{}</guid>",
+            first.uuid
+        )));
+
+        let atom = response.to_atom(&feed.clone().description("Holidays in the US"));
+        assert!(atom.contains("<subtitle>Holidays in the US</subtitle>"));
+        assert!(atom.contains("<author>\n<name>US holidays</name>\n</author>"));
+        assert!(atom.contains(&format!(
+            "<link href=\"https://intranet.example/?country=us&amp;year=2021#{}\"/>",
+            response.holidays[1].slug()
+        )));
+        assert_eq!(atom.matches("<link href=").count(), 3);
+        let atom = response.to_atom(&feed.clone().author("People team"));
+        assert!(atom.contains("<name>People team</name>"));
+        assert_eq!(atom.matches("<entry>").count(), 2);
+        assert!(atom.contains(&format!("<id>urn:uuid:{}</id>", first.uuid)));
+        let latest = response
+            .holidays
+            .iter()
+            .filter_map(Holiday::starts_at)
+            .max()
+            .unwrap();
+        assert!(atom.contains(&format!(
+            "<updated>{}</updated>\n<author>",
+            latest.to_rfc3339()
+        )));
+    }
+}
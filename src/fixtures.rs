@@ -0,0 +1,125 @@
+use http::StatusCode;
+use serde::de::DeserializeOwned;
+
+use crate::{mock::MockResponse, requests::Endpoint};
+
+/// Real-shaped JSON payload of HolidayAPI, shipped with the `mock` feature.
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    pub name: &'static str,
+    /// Endpoint answering with this payload, `None` for errors any endpoint can return.
+    pub endpoint: Option<Endpoint>,
+    pub status: StatusCode,
+    pub body: &'static str,
+}
+
+macro_rules! fixture {
+    ($name:literal, $endpoint:expr, $status:expr) => {
+        Fixture {
+            name: $name,
+            endpoint: $endpoint,
+            status: $status,
+            body: include_str!(concat!("../fixtures/", $name, ".json")),
+        }
+    };
+}
+
+impl Fixture {
+    /// Every fixture: a success for each endpoint, a success with a warning, holidays of a
+    /// subdivision, and each documented error.
+    pub const ALL: &'static [Fixture] = &[
+        fixture!("countries", Some(Endpoint::Countries), StatusCode::OK),
+        fixture!("holidays", Some(Endpoint::Holidays), StatusCode::OK),
+        fixture!("holidays_warning", Some(Endpoint::Holidays), StatusCode::OK),
+        fixture!(
+            "holidays_subdivision",
+            Some(Endpoint::Holidays),
+            StatusCode::OK
+        ),
+        fixture!("languages", Some(Endpoint::Languages), StatusCode::OK),
+        fixture!("workday", Some(Endpoint::Workday), StatusCode::OK),
+        fixture!("workdays", Some(Endpoint::Workdays), StatusCode::OK),
+        fixture!("error_400", None, StatusCode::BAD_REQUEST),
+        fixture!("error_401", None, StatusCode::UNAUTHORIZED),
+        fixture!("error_402", None, StatusCode::PAYMENT_REQUIRED),
+        fixture!("error_403", None, StatusCode::FORBIDDEN),
+        fixture!("error_429", None, StatusCode::TOO_MANY_REQUESTS),
+        fixture!("error_500", None, StatusCode::INTERNAL_SERVER_ERROR),
+    ];
+
+    /// Returns the fixture with the given name, e.g. `"holidays"` or `"error_401"`.
+    pub fn named(name: &str) -> Option<Fixture> {
+        Self::ALL
+            .iter()
+            .find(|fixture| fixture.name == name)
+            .cloned()
+    }
+
+    /// Parses the payload into a typed response, such as
+    /// [`HolidaysResponse`](crate::responses::HolidaysResponse) or, for errors,
+    /// [`ErrorResponse`](crate::responses::ErrorResponse).
+    ///
+    /// # Examples
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let fixture = Fixture::named("holidays").unwrap();
+    /// let response: HolidaysResponse = fixture.parse().unwrap();
+    /// assert_eq!(response.holidays[0].name, "New Year's Day");
+    /// ```
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_str(self.body)
+    }
+
+    /// Scripted response answering with this fixture, for a
+    /// [`MockTransport`](crate::mock::MockTransport).
+    pub fn mock_response(&self) -> MockResponse {
+        MockResponse::new(self.status, self.body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{responses::*, test_support::EXPIRED_KEY};
+
+    #[test]
+    fn test_fixtures_parse() {
+        for fixture in Fixture::ALL {
+            let parsed = match &fixture.endpoint {
+                Some(Endpoint::Countries) => fixture.parse::<CountriesResponse>().map(drop),
+                Some(Endpoint::Holidays) => fixture.parse::<HolidaysResponse>().map(drop),
+                Some(Endpoint::Languages) => fixture.parse::<LanguagesResponse>().map(drop),
+                Some(Endpoint::Workday) => fixture.parse::<WorkdayResponse>().map(drop),
+                Some(Endpoint::Workdays) => fixture.parse::<WorkdaysResponse>().map(drop),
+                Some(Endpoint::Other(path)) => unreachable!("No fixture for {}", path),
+                None => fixture.parse::<ErrorResponse>().map(|response| {
+                    assert_eq!(response.status, u32::from(fixture.status.as_u16()))
+                }),
+            };
+            assert!(parsed.is_ok(), "{}: {:?}", fixture.name, parsed);
+        }
+        let warning: HolidaysResponse =
+            Fixture::named("holidays_warning").unwrap().parse().unwrap();
+        assert!(warning.warning.is_some());
+        assert!(warning.holidays[0].subdivisions.is_empty());
+        let subdivision: HolidaysResponse = Fixture::named("holidays_subdivision")
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(subdivision.holidays[0].subdivisions[0], "US-CA");
+    }
+
+    #[tokio::test]
+    async fn test_error_fixtures_map_to_errors() {
+        let api = crate::HolidayAPI::new(EXPIRED_KEY).unwrap().transport(
+            crate::mock::MockTransport::new(Fixture::named("error_401").unwrap().mock_response()),
+        );
+        match api.holidays("us", 2021).get().await {
+            Err(crate::HolidayAPIError::InvalidOrExpiredKey(_, message)) => {
+                assert_eq!(message, "Missing or invalid API key.")
+            }
+            other => unreachable!("Should fail with a key error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use http::HeaderMap;
+use url::Url;
+
+use crate::{requests::Endpoint, responses::Country, HolidayAPI, HolidayAPIError};
+
+impl Country {
+    /// Downloads the flag image of the country from its [`flag`](Self::flag) URL, returning
+    /// the image bytes.
+    ///
+    /// The image is fetched with the transport of the client, so it goes through the same HTTP
+    /// client, proxy and DNS settings as the API requests, and counts against
+    /// [`HolidayAPI::max_concurrency`] and [`HolidayAPI::max_response_size`] but not against the
+    /// request budget. The API key is not sent.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let countries = api.countries().get_full().await.unwrap();
+    ///     let us = countries.country("us").unwrap();
+    ///     std::fs::write("us.png", us.fetch_flag(&api).await.unwrap()).unwrap();
+    /// }
+    /// ```
+    pub async fn fetch_flag(&self, api: &HolidayAPI) -> Result<Vec<u8>, HolidayAPIError> {
+        let url =
+            Url::parse(&self.flag).map_err(|err| HolidayAPIError::TransportError(err.into()))?;
+        let _permit = match &api.concurrency {
+            Some(limit) => Some(limit.acquire().await),
+            None => None,
+        };
+        let response = api
+            .transport_get(&Endpoint::Other("flag".into()), url, HeaderMap::new())
+            .await?;
+        if !response.status().is_success() {
+            return Err(HolidayAPIError::RequestError(
+                response.status(),
+                response.text(),
+            ));
+        }
+        Ok(response.bytes().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{
+        fixtures::Fixture,
+        mock::{MockResponse, MockTransport},
+        responses::CountriesResponse,
+        test_support::EXPIRED_KEY,
+    };
+
+    #[tokio::test]
+    async fn test_fetch_flag() {
+        let transport = MockTransport::new(MockResponse::ok("PNG"))
+            .on_call(2, MockResponse::new(StatusCode::NOT_FOUND, "Not found"));
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone());
+        let countries: CountriesResponse = Fixture::named("countries").unwrap().parse().unwrap();
+        let mut us = countries.country("us").unwrap().clone();
+
+        assert_eq!(us.fetch_flag(&api).await.unwrap(), b"PNG");
+        assert_eq!(
+            transport.requests()[0].as_str(),
+            "https://holidayapi.com/flags/us.png"
+        );
+        assert!(matches!(
+            us.fetch_flag(&api).await,
+            Err(HolidayAPIError::RequestError(StatusCode::NOT_FOUND, _))
+        ));
+        us.flag = "flags/us.png".into();
+        assert!(matches!(
+            us.fetch_flag(&api).await,
+            Err(HolidayAPIError::TransportError(_))
+        ));
+        assert_eq!(transport.calls(), 2);
+    }
+}
@@ -0,0 +1,160 @@
+use std::time::{Duration, Instant};
+
+use crate::{tenant::KeyHealth, HolidayAPI, HolidayAPIError, KeyStatus};
+
+/// Summary of the state of a client, returned by [`HolidayAPI::health_check`].
+#[derive(Debug)]
+pub struct HealthReport {
+    /// Whether the API answered the probe request, whatever its status, or `None` if no probe
+    /// was sent, as while the key of the tenant is parked or the budget is spent.
+    pub reachable: Option<bool>,
+    /// Validity of the key, as returned by [`HolidayAPI::validate_key`].
+    pub key: KeyStatus,
+    /// Time taken by the probe request, retries included.
+    pub latency: Duration,
+    /// Requests available in the quota of the key, when the API reported it.
+    pub remaining: Option<u32>,
+    /// State of the cache enabled with [`HolidayAPI::with_cache`], if any.
+    pub cache: Option<CacheStatus>,
+    /// Failures and parking of the key of the tenant, for clients returned by
+    /// [`HolidayAPI::for_tenant`].
+    pub tenant: Option<KeyHealth>,
+}
+
+/// State of the cache of a client, see [`HealthReport::cache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStatus {
+    /// Number of live entries.
+    pub entries: usize,
+    /// Time to live of the entries.
+    pub ttl: Duration,
+}
+
+impl HealthReport {
+    /// Whether the client can serve requests: the API is reachable and the key is valid and has
+    /// requests left.
+    pub fn is_ready(&self) -> bool {
+        matches!(self.key, KeyStatus::Valid(_)) && self.remaining != Some(0)
+    }
+}
+
+impl HolidayAPI {
+    /// Checks the connectivity and key of the client with a probe request, and summarizes the
+    /// state of its quota, cache and tenant, to be served on a readiness endpoint.
+    ///
+    /// The probe is the request sent by [`HolidayAPI::validate_key`], and counts against the
+    /// quota like any other request.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let report = api.health_check().await;
+    ///     if !report.is_ready() {
+    ///         eprintln!("HolidayAPI is not ready: {:?}", report);
+    ///     }
+    /// }
+    /// ```
+    pub async fn health_check(&self) -> HealthReport {
+        let start = Instant::now();
+        let key = self.validate_key().await;
+        let latency = start.elapsed();
+
+        let remaining = match &key {
+            KeyStatus::Valid(requests) => requests.as_ref().map(|requests| requests.available),
+            KeyStatus::QuotaExceeded(_) => Some(0),
+            _ => None,
+        };
+        let reachable = match &key {
+            KeyStatus::Unreachable(
+                HolidayAPIError::KeyParked(_, _) | HolidayAPIError::BudgetExceeded(_),
+            ) => None,
+            KeyStatus::Unreachable(err) => Some(!err.is_network()),
+            _ => Some(true),
+        };
+        HealthReport {
+            reachable,
+            key,
+            latency,
+            remaining,
+            cache: self.cache.as_ref().map(|cache| CacheStatus {
+                entries: cache.len(),
+                ttl: cache.ttl(),
+            }),
+            tenant: self.tenant.as_ref().map(|tenant| tenant.health()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{
+        fixtures::Fixture,
+        mock::{MockResponse, MockTransport},
+        tenant::KeyRegistry,
+        test_support::EXPIRED_KEY,
+    };
+
+    #[tokio::test]
+    async fn test_health_check() {
+        let transport = MockTransport::new(
+            Fixture::named("holidays")
+                .unwrap()
+                .mock_response()
+                .latency(Duration::from_millis(20)),
+        )
+        .on_call(2, MockResponse::transport_error("reset"));
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport)
+            .with_cache(Duration::from_secs(60));
+
+        let report = api.health_check().await;
+        assert_eq!(report.reachable, Some(true));
+        assert!(report.is_ready());
+        assert!(report.latency >= Duration::from_millis(20));
+        assert!(report.remaining.is_some());
+        assert_eq!(report.cache.unwrap().ttl, Duration::from_secs(60));
+        assert!(report.tenant.is_none());
+
+        let report = api.health_check().await;
+        assert_eq!(report.reachable, Some(false));
+        assert!(!report.is_ready());
+        assert!(report.remaining.is_none());
+
+        let registry = KeyRegistry::new()
+            .insert("acme", "aaaaaaaa-aaaa-aaaa-aaaa-aaaaaaaaaaaa")
+            .unwrap();
+        let transport = MockTransport::new(MockResponse::new(
+            StatusCode::PAYMENT_REQUIRED,
+            r#"{ "status": 402, "error": "Payment required." }"#,
+        ));
+        let acme = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+            .key_registry(&registry)
+            .for_tenant("acme")
+            .unwrap();
+        assert!(acme.holidays("us", 2021).get().await.is_err());
+        let report = acme.health_check().await;
+        assert!(matches!(
+            report.key,
+            KeyStatus::Unreachable(HolidayAPIError::KeyParked(_, _))
+        ));
+        assert!(!report.is_ready());
+        assert_eq!(report.reachable, None);
+        assert_eq!(transport.calls(), 1);
+        let tenant = report.tenant.unwrap();
+        assert_eq!(tenant.last_failure, Some(StatusCode::PAYMENT_REQUIRED));
+        assert!(tenant.parked_until.is_some());
+    }
+}
@@ -0,0 +1,119 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crate::{responses::APIRequests, HolidayAPIError};
+
+type RetryHook = Arc<dyn Fn(u32, &HolidayAPIError) + Send + Sync>;
+type RateLimitedHook = Arc<dyn Fn(Option<Duration>) + Send + Sync>;
+type QuotaLowHook = Arc<dyn Fn(&APIRequests) + Send + Sync>;
+
+/// Level below which the quota is low.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum QuotaThreshold {
+    /// Number of available requests.
+    Available(u32),
+    /// Percentage of the requests of the period still available.
+    Percent(u32),
+}
+
+impl QuotaThreshold {
+    fn is_above(self, requests: &APIRequests) -> bool {
+        match self {
+            QuotaThreshold::Available(threshold) => requests.available >= threshold,
+            QuotaThreshold::Percent(percent) => {
+                let total = u64::from(requests.available) + u64::from(requests.used);
+                u64::from(requests.available) * 100 >= total * u64::from(percent)
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct QuotaLow {
+    threshold: QuotaThreshold,
+    hook: QuotaLowHook,
+    /// Whether the quota was below the threshold in the last response, so the hook only runs
+    /// when the quota drops below it.
+    low: Arc<AtomicBool>,
+}
+
+/// Callbacks registered with [`HolidayAPI::on_retry`](crate::HolidayAPI::on_retry),
+/// [`HolidayAPI::on_rate_limited`](crate::HolidayAPI::on_rate_limited) and
+/// [`HolidayAPI::on_quota_low`](crate::HolidayAPI::on_quota_low).
+#[derive(Clone, Default)]
+pub(crate) struct Hooks {
+    retry: Option<RetryHook>,
+    rate_limited: Option<RateLimitedHook>,
+    quota_low: Option<QuotaLow>,
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hooks")
+            .field("retry", &self.retry.is_some())
+            .field("rate_limited", &self.rate_limited.is_some())
+            .field(
+                "quota_low",
+                &self.quota_low.as_ref().map(|quota_low| quota_low.threshold),
+            )
+            .finish()
+    }
+}
+
+impl Hooks {
+    pub(crate) fn set_retry(
+        &mut self,
+        hook: impl Fn(u32, &HolidayAPIError) + Send + Sync + 'static,
+    ) {
+        self.retry = Some(Arc::new(hook));
+    }
+
+    pub(crate) fn set_rate_limited(
+        &mut self,
+        hook: impl Fn(Option<Duration>) + Send + Sync + 'static,
+    ) {
+        self.rate_limited = Some(Arc::new(hook));
+    }
+
+    pub(crate) fn set_quota_low(
+        &mut self,
+        threshold: QuotaThreshold,
+        hook: impl Fn(&APIRequests) + Send + Sync + 'static,
+    ) {
+        self.quota_low = Some(QuotaLow {
+            threshold,
+            hook: Arc::new(hook),
+            low: Arc::new(AtomicBool::new(false)),
+        });
+    }
+
+    /// Called before sending the `attempt`-th try of a request which failed with `error`.
+    pub(crate) fn retry(&self, attempt: u32, error: &HolidayAPIError) {
+        if let Some(hook) = &self.retry {
+            hook(attempt, error);
+        }
+    }
+
+    pub(crate) fn rate_limited(&self, retry_after: Option<Duration>) {
+        if let Some(hook) = &self.rate_limited {
+            hook(retry_after);
+        }
+    }
+
+    pub(crate) fn quota(&self, requests: &APIRequests) {
+        if let Some(quota_low) = &self.quota_low {
+            let low = !quota_low.threshold.is_above(requests);
+            if low && !quota_low.low.swap(low, Ordering::SeqCst) {
+                (quota_low.hook)(requests);
+            } else if !low {
+                quota_low.low.store(false, Ordering::SeqCst);
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use http::StatusCode;
+
+use crate::HolidayAPIError;
+
+impl HolidayAPIError {
+    /// HTTP status for a service proxying holiday data to answer its own clients with.
+    ///
+    /// Errors of the caller map to `4xx`: invalid parameters to `400 Bad Request`, rejected by
+    /// the client or by the API, unknown tenants to `404 Not Found`, and rate limiting to
+    /// `429 Too Many Requests`. Errors of the upstream map to `5xx`: an exhausted plan or budget
+    /// or a parked key to `503 Service Unavailable`, timeouts to `504 Gateway Timeout`, other
+    /// failures of the API and rejected keys to `502 Bad Gateway`, and misconfigurations of the
+    /// client to `500 Internal Server Error`.
+    pub fn http_status(&self) -> StatusCode {
+        match self {
+            HolidayAPIError::InvalidYear(_)
+            | HolidayAPIError::InvalidCountry(_)
+            | HolidayAPIError::InvalidDate(_)
+            | HolidayAPIError::InvalidVersion(_)
+            | HolidayAPIError::UnknownCountry(_)
+            | HolidayAPIError::UnknownLanguage(_)
+            | HolidayAPIError::MissingParameter(_) => StatusCode::BAD_REQUEST,
+            HolidayAPIError::UnknownTenant(_) => StatusCode::NOT_FOUND,
+            HolidayAPIError::RateLimited(_, _, _) => StatusCode::TOO_MANY_REQUESTS,
+            HolidayAPIError::BudgetExceeded(_)
+            | HolidayAPIError::FreePlanLimitation(_)
+            | HolidayAPIError::KeyParked(_, _) => StatusCode::SERVICE_UNAVAILABLE,
+            HolidayAPIError::RequestError(status, _) => match *status {
+                StatusCode::BAD_REQUEST | StatusCode::NOT_FOUND => *status,
+                StatusCode::TOO_MANY_REQUESTS => StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::PAYMENT_REQUIRED => StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::REQUEST_TIMEOUT | StatusCode::GATEWAY_TIMEOUT => {
+                    StatusCode::GATEWAY_TIMEOUT
+                }
+                _ => StatusCode::BAD_GATEWAY,
+            },
+            HolidayAPIError::Timeout { .. } => StatusCode::GATEWAY_TIMEOUT,
+            HolidayAPIError::InvalidOrExpiredKey(_, _)
+            | HolidayAPIError::StatusMismatch(_, _)
+            | HolidayAPIError::InvalidResponse(_)
+            | HolidayAPIError::IntegrityMismatch(_)
+            | HolidayAPIError::ResponseTooLarge(_)
+            | HolidayAPIError::UnexpectedContentType(_)
+            | HolidayAPIError::TransportError(_) => StatusCode::BAD_GATEWAY,
+            HolidayAPIError::InvalidKeyFormat(_)
+            | HolidayAPIError::InvalidBaseUrl(_)
+            | HolidayAPIError::InvalidEndpoint(_)
+            | HolidayAPIError::InvalidTransport(_)
+            | HolidayAPIError::SnapshotError(_)
+            | HolidayAPIError::UsageFileError(_)
+            | HolidayAPIError::SchedulerStopped
+            | HolidayAPIError::CacheDisabled => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Status and message for a service proxying holiday data, see
+    /// [`HolidayAPIError::http_status`]. The tuple is a response of axum as is.
+    ///
+    /// Errors of the caller keep their message. Errors of the upstream are answered with the
+    /// reason of the status only, so that keys, URLs and API messages do not leak to clients.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    /// use http::StatusCode;
+    ///
+    /// let (status, message) = HolidayAPIError::InvalidYear("0".into()).http_response();
+    /// assert_eq!(status, StatusCode::BAD_REQUEST);
+    /// assert_eq!(message, "Invalid year: 0");
+    /// ```
+    pub fn http_response(&self) -> (StatusCode, String) {
+        let status = self.http_status();
+        let message = if status.is_client_error() {
+            self.to_string()
+        } else {
+            status.canonical_reason().unwrap_or_default().to_string()
+        };
+        (status, message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_http_status() {
+        let request_error =
+            |status: StatusCode| HolidayAPIError::RequestError(status, "Rejected.".into());
+        let cases = [
+            (
+                HolidayAPIError::MissingParameter("year".into()),
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                request_error(StatusCode::BAD_REQUEST),
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                HolidayAPIError::RateLimited(None, "".into(), None),
+                StatusCode::TOO_MANY_REQUESTS,
+            ),
+            (
+                request_error(StatusCode::PAYMENT_REQUIRED),
+                StatusCode::SERVICE_UNAVAILABLE,
+            ),
+            (
+                HolidayAPIError::BudgetExceeded(Duration::from_secs(60)),
+                StatusCode::SERVICE_UNAVAILABLE,
+            ),
+            (
+                request_error(StatusCode::UNAUTHORIZED),
+                StatusCode::BAD_GATEWAY,
+            ),
+            (
+                request_error(StatusCode::INTERNAL_SERVER_ERROR),
+                StatusCode::BAD_GATEWAY,
+            ),
+            (
+                HolidayAPIError::Timeout {
+                    elapsed: Duration::from_secs(5),
+                    endpoint: "holidays".into(),
+                },
+                StatusCode::GATEWAY_TIMEOUT,
+            ),
+            (
+                HolidayAPIError::CacheDisabled,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+        ];
+        for (err, status) in cases {
+            assert_eq!(err.http_status(), status, "{:?}", err);
+        }
+
+        let leaky = HolidayAPIError::InvalidKeyFormat("secret".into());
+        assert_eq!(
+            leaky.http_response(),
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Internal Server Error".to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use chrono::Datelike;
+
+use crate::{country::CountryCode, responses::HolidaysResponse};
+
+/// First holiday of the response outside of the requested country, year, month or day, as a
+/// message naming the holiday and the parameter it contradicts.
+///
+/// Requests for holidays before or after a date, see [`Request::previous`] and
+/// [`Request::upcoming`], may return holidays of another year or month, only their country is
+/// checked.
+///
+/// [`Request::previous`]: crate::requests::Request::previous
+/// [`Request::upcoming`]: crate::requests::Request::upcoming
+pub(crate) fn holidays_mismatch(
+    parameters: &HashMap<String, String>,
+    response: &HolidaysResponse,
+) -> Option<String> {
+    let relative = parameters.contains_key("previous") || parameters.contains_key("upcoming");
+    let number = |name: &str| {
+        parameters
+            .get(name)
+            .filter(|_| !relative)
+            .and_then(|value| value.parse::<u32>().ok())
+    };
+    let (year, month, day) = (number("year"), number("month"), number("day"));
+    let country = parameters.get("country").map(|code| CountryCode::new(code));
+
+    for holiday in &response.holidays {
+        if let Some(country) = &country {
+            if !holiday.country.eq_ignore_ascii_case(country.country()) {
+                return Some(format!(
+                    "{} is a holiday of {}, not of the requested country {}",
+                    holiday.name,
+                    holiday.country,
+                    country.country()
+                ));
+            }
+        }
+        let Some(date) = holiday.parse_date() else {
+            continue;
+        };
+        let checks = [
+            ("year", year, date.year() as u32),
+            ("month", month, date.month()),
+            ("day", day, date.day()),
+        ];
+        for (parameter, requested, actual) in checks {
+            if requested.is_some_and(|requested| requested != actual) {
+                return Some(format!(
+                    "{} on {} is outside of the requested {} {}",
+                    holiday.name,
+                    holiday.date,
+                    parameter,
+                    requested.unwrap_or_default()
+                ));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::Fixture;
+
+    #[test]
+    fn test_holidays_mismatch() {
+        let response: HolidaysResponse = Fixture::named("holidays").unwrap().parse().unwrap();
+        let parameters = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        };
+        let first = &response.holidays[0];
+        let year = &first.date[..4];
+
+        assert_eq!(
+            holidays_mismatch(
+                &parameters(&[("country", "us-ca"), ("year", year)]),
+                &response
+            ),
+            None
+        );
+        let wrong_country = holidays_mismatch(&parameters(&[("country", "ca")]), &response);
+        assert!(wrong_country
+            .unwrap()
+            .contains("not of the requested country CA"));
+        let wrong_year = holidays_mismatch(&parameters(&[("year", "1999")]), &response);
+        assert!(wrong_year
+            .unwrap()
+            .ends_with("outside of the requested year 1999"));
+        assert_eq!(
+            holidays_mismatch(
+                &parameters(&[("year", "1999"), ("upcoming", "true")]),
+                &response
+            ),
+            None
+        );
+    }
+}
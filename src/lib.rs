@@ -7,48 +7,257 @@
 //! ```console
 //! $ cargo add holidayapi_rust
 //! ```
+//! ## Features
+//! - `reqwest` *(default)*: sends requests with [`reqwest`](https://docs.rs/reqwest), see
+//!   `ReqwestTransport`. Without any transport feature, a `Transport` must be set with
+//!   [`HolidayAPI::transport`].
+//! - `hyper`: `HyperTransport`, a lean transport for applications already running hyper. It
+//!   becomes the default transport when `reqwest` is disabled.
+//! - `ureq`: blocking `UreqTransport` for synchronous builds without tokio or hyper, to be used
+//!   with [`block_on`]. It becomes the default transport when `reqwest` and `hyper` are disabled.
+//! - `tokio` *(default)*: runs background components such as the `Scheduler` on tokio, see
+//!   `TokioRuntime`. Other executors can be used by implementing `Runtime`.
+//! - `mock`: `MockTransport`, answering with scripted responses, failures and latency, to test
+//!   code using the client without network access, and `Fixture`, real-shaped payloads of every
+//!   endpoint and documented error.
+//! - `polars`: `to_dataframe` on `HolidaysResponse` and `CountriesResponse`, flattening them into
+//!   [polars](https://docs.rs/polars) data frames.
+//! - `sqlx`: `SQL_SCHEMA` and `insert_into` on `HolidaysResponse` and `CountriesResponse`,
+//!   upserting them into Postgres or SQLite with [sqlx](https://docs.rs/sqlx).
+//! - `postcard`: `to_bytes` and `from_bytes` on `YearCalendar` and the responses, storing them
+//!   as compact [postcard](https://docs.rs/postcard) blobs to be embedded with `include_bytes!`.
+//! - `compact-strings`: `CompactCountries` and `CompactLanguages`, holding the countries and
+//!   languages responses with `Arc<str>` strings and interned language codes, returned by
+//!   `get_compact`, to reduce the memory held by a cached list of countries.
+//! - `feed`: `Feed`, rendering holidays such as the output of [`HolidayAPI::upcoming_in`] as
+//!   RSS or Atom feeds, and `to_rss` and `to_atom` on `HolidaysResponse`.
+//! - `flags`: `fetch_flag` on `Country`, downloading its flag image through the transport of
+//!   the client.
+//! - `uuid`: `uuid` on `Holiday`, parsing its `uuid` field as a `uuid::Uuid`.
+//! - `prometheus`: `render_metrics` on `HolidayAPI`, rendering its request, error, cache and
+//!   quota counters in the Prometheus text format.
+//! - `mmap`: `map_snapshot` on `HolidayAPI`, reading a snapshot through a memory map and
+//!   decoding the holidays of a country and year only when they are read.
+//! - `http`: `http_status` and `http_response` on `HolidayAPIError`, mapping errors to the
+//!   status and message a web service proxying holiday data answers with.
+//! - `xlsx`: `to_xlsx` on `HolidaysResponse` and `HolidaysResponse::to_xlsx_sheets`, rendering
+//!   holidays as Excel workbooks, with one sheet per response for several countries.
+//! - `fake`: `Faker`, generating realistic holidays, countries and languages from a seed, to
+//!   property-test code using the responses.
+//!
 //! ## Usage
 //! ```
 //! use holidayapi_rust::prelude::*;
 //!
 //! #[tokio::main]
 //! async fn main() {
-//! 	let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
-//! 	let request = api.holidays("us", 2021).month(10).day(20).public().upcoming();
-//! 	let response = request.get().await;
-//!		match response {
-//! 		Ok(_) => { /* */ },
-//! 		Err(_) => { /* */ },
-//! 	}
+//!     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+//!     let request = api.holidays("us", 2021).month(10).day(20).public().upcoming();
+//!     let response = request.get().await;
+//!     match response {
+//!         Ok(_) => { /* */ },
+//!         Err(_) => { /* */ },
+//!     }
 //! }
 //! ```
 pub mod prelude;
 
+mod batch;
+#[cfg(feature = "postcard")]
+mod binary;
+mod blocking;
+mod budget;
+mod cache;
+mod calendar;
+mod clock;
+#[cfg(feature = "compact-strings")]
+mod compact;
+mod country;
+#[cfg(feature = "polars")]
+mod dataframe;
+mod date;
+mod deferred;
+mod diff;
+mod dns;
+#[cfg(any(test, feature = "fake"))]
+mod fake;
+#[cfg(feature = "feed")]
+mod feed;
+#[cfg(any(test, feature = "mock"))]
+mod fixtures;
+#[cfg(feature = "flags")]
+mod flag;
+mod health;
+mod hooks;
+#[cfg(feature = "http")]
+mod http_status;
+mod integrity;
+mod iso;
+mod limit;
+mod locale;
+#[cfg(feature = "mmap")]
+mod mapped;
+#[cfg(feature = "prometheus")]
+mod metrics;
+#[cfg(any(test, feature = "mock"))]
+mod mock;
+mod postprocess;
+mod refresh;
+mod reminder;
 mod requests;
 mod responses;
-use requests::Request;
+mod retry;
+mod runtime;
+mod scheduler;
+mod search;
+mod shutdown;
+mod snapshot;
+#[cfg(feature = "sqlx")]
+mod sql;
+mod stats;
+mod tenant;
+#[cfg(test)]
+mod test_support;
+mod transport;
+mod usage;
+mod version;
+mod watch;
+#[cfg(feature = "xlsx")]
+mod xlsx;
+pub use blocking::block_on;
+use budget::Budget;
+pub use cache::{Cache, Revalidation};
+use chrono::{Datelike, NaiveDate};
+use clock::{Clock, SystemClock};
+use country::CountryCode;
+pub use date::FlexibleDate;
+use diff::YearOverYear;
+use dns::Resolver;
+use futures_util::stream::{self, BoxStream, StreamExt};
+use hooks::{Hooks, QuotaThreshold};
+use limit::ConcurrencyLimit;
+#[cfg(feature = "prometheus")]
+use metrics::Metrics;
+use postprocess::PostProcessors;
+use reminder::Reminder;
+use requests::{Endpoint, EndpointSpec, Request};
 use responses::{
-    CountriesResponse, HolidaysResponse, LanguagesResponse, WorkdayResponse, WorkdaysResponse,
+    APIRequests, CountriesResponse, Holiday, HolidaysResponse, LanguageCountries,
+    LanguagesResponse, Subdivision, WorkdayResponse, WorkdaysResponse,
 };
+use retry::RetryPolicy;
+use serde::Deserialize;
 use serde_json::Value;
-use std::{collections::HashMap, error::Error, fmt};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    ops::{Range, RangeInclusive},
+    time::{Duration, Instant},
+};
+use tenant::{KeyRegistry, TenantHandle};
+use usage::UsageFile;
+use version::Version;
 
+use http::{
+    header::{HeaderMap, CONTENT_TYPE, RETRY_AFTER},
+    StatusCode,
+};
 use regex::Regex;
-pub use reqwest::Response;
-use reqwest::Url;
+use std::sync::Arc;
+pub use transport::Response;
+use transport::{BodyTooLarge, QuotaInfo, Transport, TransportError, TransportRequest};
+use url::Url;
 
+/// Client of the API.
+///
+/// Cloning is cheap and does not allocate: the configuration is shared by reference counting,
+/// so every [`Request`] can carry its own handle on the client.
 #[derive(Debug, Clone)]
 pub struct HolidayAPI {
-    base_url: String,
-    key: String,
+    version: Version,
+    base_urls: Arc<[Url]>,
+    key: Arc<str>,
+    free_plan: bool,
+    cache: Option<Cache>,
+    transport: Arc<dyn Transport>,
+    custom_transport: bool,
+    default_country: Option<Arc<CountryCode>>,
+    default_language: Option<Arc<str>>,
+    clock: Arc<dyn Clock>,
+    validation: Validation,
+    integrity: Validation,
+    budget: Option<Budget>,
+    usage: Option<UsageFile>,
+    hooks: Hooks,
+    #[cfg(feature = "prometheus")]
+    metrics: Metrics,
+    post_processors: PostProcessors,
+    concurrency: Option<ConcurrencyLimit>,
+    stream_concurrency: Option<usize>,
+    retry: Option<RetryPolicy>,
+    max_response_size: Option<usize>,
+    keys: Option<KeyRegistry>,
+    tenant: Option<TenantHandle>,
 }
 
+/// Error returned by the client.
+///
+/// Errors are `Send + Sync + 'static` and at most a few words large, the transport error and
+/// rarely used details being boxed, so they move across tasks and convert into
+/// `Box<dyn Error + Send + Sync>`, `anyhow` or `eyre` errors with `?`.
 #[derive(Debug)]
 pub enum HolidayAPIError {
     InvalidKeyFormat(String),
-    InvalidOrExpiredKey(String),
+    InvalidOrExpiredKey(StatusCode, String),
     InvalidVersion(String),
-    RequestError(reqwest::Error, String),
+    InvalidYear(String),
+    InvalidCountry(String),
+    InvalidDate(String),
+    UnknownCountry(String),
+    UnknownLanguage(String),
+    UnknownTenant(String),
+    /// The key of the tenant was rejected by the API and is parked until the given time, see
+    /// [`KeyRegistry`].
+    KeyParked(String, chrono::DateTime<chrono::Utc>),
+    InvalidBaseUrl(String),
+    /// The path of an [`Endpoint::Other`] is not relative to the base url, or has `..`
+    /// segments leaving it.
+    InvalidEndpoint(String),
+    /// The transport cannot be built, or cannot be combined with a transport set with
+    /// [`HolidayAPI::transport`], see [`HolidayAPI::resolver`].
+    InvalidTransport(String),
+    MissingParameter(String),
+    FreePlanLimitation(String),
+    /// Rate limited, with the `Retry-After` delay and the rate limit headers if sent.
+    RateLimited(Option<Duration>, String, Option<Box<QuotaInfo>>),
+    BudgetExceeded(Duration),
+    SnapshotError(String),
+    /// The file of [`HolidayAPI::persist_usage`] cannot be read.
+    UsageFileError(String),
+    SchedulerStopped,
+    CacheDisabled,
+    RequestError(StatusCode, String),
+    /// The HTTP status and the `status` field of the payload disagree, which happens when a
+    /// proxy rewrites the response.
+    StatusMismatch(StatusCode, u16),
+    /// The body of a successful response is not the expected JSON.
+    InvalidResponse(String),
+    /// The response does not match the parameters of the request, see
+    /// [`HolidayAPI::integrity_checks`].
+    IntegrityMismatch(String),
+    /// The body of a response is longer than the limit set with
+    /// [`HolidayAPI::max_response_size`].
+    ResponseTooLarge(usize),
+    /// An HTML page, such as the error or maintenance page of a proxy, was received instead of
+    /// a response of the API.
+    UnexpectedContentType(Box<UnexpectedContent>),
+    /// The HTTP client or the connection timed out while requesting `endpoint`.
+    Timeout {
+        elapsed: Duration,
+        endpoint: Box<str>,
+    },
+    TransportError(TransportError),
 }
 
 impl fmt::Display for HolidayAPIError {
@@ -56,24 +265,354 @@ impl fmt::Display for HolidayAPIError {
         match self {
             HolidayAPIError::InvalidKeyFormat(key) => write!(f, "Invalid key: {}", key),
             HolidayAPIError::InvalidVersion(version) => write!(f, "Invalid version: {}", version),
-            HolidayAPIError::InvalidOrExpiredKey(key) => {
-                write!(f, "Invalid or expired key: {}", key)
+            HolidayAPIError::InvalidYear(year) => write!(f, "Invalid year: {}", year),
+            HolidayAPIError::InvalidCountry(country) => write!(f, "Invalid country: {}", country),
+            HolidayAPIError::InvalidDate(date) => write!(f, "Invalid date: {}", date),
+            HolidayAPIError::UnknownCountry(country) => write!(f, "Unknown country: {}", country),
+            HolidayAPIError::UnknownLanguage(language) => {
+                write!(f, "Unknown language: {}", language)
             }
-            HolidayAPIError::RequestError(req, err) => {
-                write!(
+            HolidayAPIError::UnknownTenant(tenant) => write!(f, "Unknown tenant: {}", tenant),
+            HolidayAPIError::KeyParked(tenant, until) => write!(
+                f,
+                "Key of tenant {} is parked until {}",
+                tenant,
+                until.to_rfc3339()
+            ),
+            HolidayAPIError::InvalidBaseUrl(url) => write!(f, "Invalid base url: {}", url),
+            HolidayAPIError::InvalidEndpoint(path) => {
+                write!(f, "Endpoint path leaves the base url: {}", path)
+            }
+            HolidayAPIError::InvalidTransport(err) => write!(f, "Invalid transport: {}", err),
+            HolidayAPIError::MissingParameter(parameter) => {
+                write!(f, "Missing required parameter: {}", parameter)
+            }
+            HolidayAPIError::FreePlanLimitation(reason) => {
+                write!(f, "Not available on the free plan: {}", reason)
+            }
+            HolidayAPIError::RateLimited(retry_after, err, _) => match retry_after {
+                Some(retry_after) => write!(
                     f,
-                    "{}: {}\nRaw url: '{}'",
-                    req.status().unwrap(),
+                    "Rate limited: {}\nRetry after {}s",
                     err,
-                    req.url().unwrap(),
-                )
+                    retry_after.as_secs()
+                ),
+                None => write!(f, "Rate limited: {}", err),
+            },
+            HolidayAPIError::BudgetExceeded(resets_in) => write!(
+                f,
+                "Request budget exceeded, resets in {}s",
+                resets_in.as_secs()
+            ),
+            HolidayAPIError::SnapshotError(err) => write!(f, "Snapshot error: {}", err),
+            HolidayAPIError::UsageFileError(err) => write!(f, "Usage file error: {}", err),
+            HolidayAPIError::SchedulerStopped => write!(f, "Scheduler stopped before answering"),
+            HolidayAPIError::CacheDisabled => write!(f, "Cache is not enabled on this client"),
+            HolidayAPIError::InvalidOrExpiredKey(_, err) => {
+                write!(f, "Invalid or expired key: {}", err)
+            }
+            HolidayAPIError::RequestError(status, err) => write!(f, "{}: {}", status, err),
+            HolidayAPIError::StatusMismatch(status, payload) => write!(
+                f,
+                "HTTP status {} does not match the response status {}",
+                status.as_u16(),
+                payload
+            ),
+            HolidayAPIError::InvalidResponse(err) => write!(f, "Invalid response: {}", err),
+            HolidayAPIError::IntegrityMismatch(mismatch) => {
+                write!(f, "Response does not match the request: {}", mismatch)
+            }
+            HolidayAPIError::ResponseTooLarge(limit) => {
+                write!(f, "Response longer than the limit of {} bytes", limit)
+            }
+            HolidayAPIError::UnexpectedContentType(content) => write!(
+                f,
+                "Unexpected {} response with status {}: {}",
+                content.content_type.as_deref().unwrap_or("HTML"),
+                content.status.as_u16(),
+                content.snippet
+            ),
+            HolidayAPIError::Timeout { elapsed, endpoint } => write!(
+                f,
+                "Request to {} timed out after {}ms",
+                endpoint,
+                elapsed.as_millis()
+            ),
+            HolidayAPIError::TransportError(err) => write!(f, "Transport error: {}", err),
+        }
+    }
+}
+impl Error for HolidayAPIError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            HolidayAPIError::TransportError(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl HolidayAPIError {
+    /// HTTP status the API answered with, when the error carries it.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            HolidayAPIError::RequestError(status, _)
+            | HolidayAPIError::InvalidOrExpiredKey(status, _)
+            | HolidayAPIError::StatusMismatch(status, _) => Some(*status),
+            HolidayAPIError::RateLimited(_, _, _) => Some(StatusCode::TOO_MANY_REQUESTS),
+            HolidayAPIError::UnexpectedContentType(content) => Some(content.status),
+            _ => None,
+        }
+    }
+
+    /// Whether sending the same request again later may succeed: network failures, rate
+    /// limiting, request timeouts and server errors.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            HolidayAPIError::TransportError(_)
+            | HolidayAPIError::Timeout { .. }
+            | HolidayAPIError::RateLimited(_, _, _) => true,
+            HolidayAPIError::RequestError(status, _) => {
+                status.is_server_error() || *status == StatusCode::REQUEST_TIMEOUT
+            }
+            HolidayAPIError::UnexpectedContentType(content) => content.status.is_server_error(),
+            _ => false,
+        }
+    }
+
+    /// Whether the request never got an answer: DNS, connection and TLS failures, resets and
+    /// timeouts.
+    ///
+    /// Network errors and [API errors](Self::is_api) are exclusive. Errors raised before
+    /// sending, such as validation or the budget, and answers which may come from an
+    /// intermediary, such as [`HolidayAPIError::UnexpectedContentType`], are neither.
+    pub fn is_network(&self) -> bool {
+        matches!(
+            self,
+            HolidayAPIError::TransportError(_) | HolidayAPIError::Timeout { .. }
+        )
+    }
+
+    /// Whether HolidayAPI answered with an error: rejected parameters or key, rate limiting,
+    /// exhausted plan or server errors, including keys parked after being rejected. See
+    /// [`HolidayAPIError::is_network`].
+    pub fn is_api(&self) -> bool {
+        matches!(
+            self,
+            HolidayAPIError::RequestError(_, _)
+                | HolidayAPIError::RateLimited(_, _, _)
+                | HolidayAPIError::InvalidOrExpiredKey(_, _)
+                | HolidayAPIError::KeyParked(_, _)
+        )
+    }
+
+    /// Whether the API could not be reached or failed on its side.
+    pub(crate) fn is_upstream_failure(&self) -> bool {
+        match self {
+            HolidayAPIError::TransportError(_) | HolidayAPIError::Timeout { .. } => true,
+            HolidayAPIError::RequestError(status, _) => status.is_server_error(),
+            HolidayAPIError::UnexpectedContentType(content) => content.status.is_server_error(),
+            _ => false,
+        }
+    }
+
+    /// Whether the error comes from the plan or quota of the key: rate limiting, exhausted or
+    /// insufficient plan (`402`), or a request blocked by the free plan guard, the budget or
+    /// the parking of a rejected tenant key.
+    pub fn is_quota(&self) -> bool {
+        match self {
+            HolidayAPIError::RateLimited(_, _, _)
+            | HolidayAPIError::FreePlanLimitation(_)
+            | HolidayAPIError::BudgetExceeded(_)
+            | HolidayAPIError::KeyParked(_, _) => true,
+            HolidayAPIError::RequestError(status, _) => *status == StatusCode::PAYMENT_REQUIRED,
+            _ => false,
+        }
+    }
+
+    /// Name of the variant of the error, such as `"RateLimited"`, stable across releases.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            HolidayAPIError::InvalidKeyFormat(_) => "InvalidKeyFormat",
+            HolidayAPIError::InvalidOrExpiredKey(_, _) => "InvalidOrExpiredKey",
+            HolidayAPIError::InvalidVersion(_) => "InvalidVersion",
+            HolidayAPIError::InvalidYear(_) => "InvalidYear",
+            HolidayAPIError::InvalidCountry(_) => "InvalidCountry",
+            HolidayAPIError::InvalidDate(_) => "InvalidDate",
+            HolidayAPIError::UnknownCountry(_) => "UnknownCountry",
+            HolidayAPIError::UnknownLanguage(_) => "UnknownLanguage",
+            HolidayAPIError::UnknownTenant(_) => "UnknownTenant",
+            HolidayAPIError::KeyParked(_, _) => "KeyParked",
+            HolidayAPIError::InvalidBaseUrl(_) => "InvalidBaseUrl",
+            HolidayAPIError::InvalidEndpoint(_) => "InvalidEndpoint",
+            HolidayAPIError::InvalidTransport(_) => "InvalidTransport",
+            HolidayAPIError::MissingParameter(_) => "MissingParameter",
+            HolidayAPIError::FreePlanLimitation(_) => "FreePlanLimitation",
+            HolidayAPIError::RateLimited(_, _, _) => "RateLimited",
+            HolidayAPIError::BudgetExceeded(_) => "BudgetExceeded",
+            HolidayAPIError::SnapshotError(_) => "SnapshotError",
+            HolidayAPIError::UsageFileError(_) => "UsageFileError",
+            HolidayAPIError::SchedulerStopped => "SchedulerStopped",
+            HolidayAPIError::CacheDisabled => "CacheDisabled",
+            HolidayAPIError::RequestError(_, _) => "RequestError",
+            HolidayAPIError::StatusMismatch(_, _) => "StatusMismatch",
+            HolidayAPIError::InvalidResponse(_) => "InvalidResponse",
+            HolidayAPIError::IntegrityMismatch(_) => "IntegrityMismatch",
+            HolidayAPIError::ResponseTooLarge(_) => "ResponseTooLarge",
+            HolidayAPIError::UnexpectedContentType(_) => "UnexpectedContentType",
+            HolidayAPIError::Timeout { .. } => "Timeout",
+            HolidayAPIError::TransportError(_) => "TransportError",
+        }
+    }
+
+    /// Delay before the request may succeed: the `Retry-After` delay of rate limiting, or the
+    /// time until the budget resets.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            HolidayAPIError::RateLimited(retry_after, _, _) => *retry_after,
+            HolidayAPIError::BudgetExceeded(resets_in) => Some(*resets_in),
+            _ => None,
+        }
+    }
+
+    /// Endpoint of the failed request, when the error carries it.
+    pub fn endpoint(&self) -> Option<&str> {
+        match self {
+            HolidayAPIError::Timeout { endpoint, .. } => Some(endpoint),
+            _ => None,
+        }
+    }
+
+    /// Message of the error without the API key, which transport errors carry in the URL of
+    /// the request and [`HolidayAPIError::InvalidKeyFormat`] in its text.
+    fn redacted_message(&self) -> String {
+        match self {
+            HolidayAPIError::InvalidKeyFormat(_) => "Invalid key".to_string(),
+            err => {
+                let key = Regex::new(r"(?i)([?&]key=)[^&#\s)]*").expect("Regex is correct");
+                key.replace_all(&err.to_string(), "${1}REDACTED")
+                    .into_owned()
             }
         }
     }
 }
-impl Error for HolidayAPIError {}
+
+/// Serializes the error as an object with its `status`, `kind`, `message`, `endpoint` and
+/// `retry_after` in seconds, for structured error responses and logs. Fields which the error
+/// does not carry are `null`. The API key is redacted from the message.
+impl serde::Serialize for HolidayAPIError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut error = serializer.serialize_struct("HolidayAPIError", 5)?;
+        error.serialize_field("status", &self.status().map(|status| status.as_u16()))?;
+        error.serialize_field("kind", self.kind())?;
+        error.serialize_field("message", &self.redacted_message())?;
+        error.serialize_field("endpoint", &self.endpoint())?;
+        error.serialize_field(
+            "retry_after",
+            &self.retry_after().map(|retry_after| retry_after.as_secs()),
+        )?;
+        error.end()
+    }
+}
+
+/// Response received instead of a response of the API, see
+/// [`HolidayAPIError::UnexpectedContentType`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnexpectedContent {
+    pub status: StatusCode,
+    /// `Content-Type` header of the response, if any.
+    pub content_type: Option<String>,
+    /// Start of the body, at most [`UnexpectedContent::SNIPPET_LENGTH`] characters.
+    pub snippet: String,
+}
+
+impl UnexpectedContent {
+    pub const SNIPPET_LENGTH: usize = 200;
+
+    /// Describes the response if it is an HTML page: its content type is HTML or, without a
+    /// content type, its body starts like an HTML document.
+    fn detect(response: &Response) -> Option<Self> {
+        let content_type = response.header(CONTENT_TYPE.as_str()).map(str::to_string);
+        let text = response.text();
+        let is_html = match &content_type {
+            Some(content_type) => content_type.to_ascii_lowercase().contains("html"),
+            None => {
+                let start = text.trim_start().to_ascii_lowercase();
+                start.starts_with("<!doctype html") || start.starts_with("<html")
+            }
+        };
+        is_html.then(|| Self {
+            status: response.status(),
+            content_type,
+            snippet: text.trim().chars().take(Self::SNIPPET_LENGTH).collect(),
+        })
+    }
+}
+
+/// Fields shared by every JSON response, read before the response is parsed.
+#[derive(Deserialize)]
+struct Envelope {
+    status: Option<u16>,
+    /// Kept raw so that an unexpected shape does not prevent checking the status.
+    requests: Option<Value>,
+}
+
+/// Outcome of [`HolidayAPI::prefetch`].
+#[derive(Debug, Default)]
+pub struct PrefetchSummary {
+    /// Responses fetched from the API and stored in the cache.
+    pub fetched: usize,
+    /// Responses that were already cached.
+    pub cached: usize,
+    /// Requests that failed, with their country and year.
+    pub failed: Vec<(String, i32, HolidayAPIError)>,
+}
+
+/// How requests handle parameters failing the client-side checks of their country and language
+/// codes, year and dates, set with [`HolidayAPI::validation`], and responses failing the
+/// integrity checks, set with [`HolidayAPI::integrity_checks`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Validation {
+    /// The request fails without being sent.
+    #[default]
+    Strict,
+    /// A warning is logged with the `log` crate and the request is sent anyway.
+    Warn,
+    /// The parameters are sent without being checked.
+    Skip,
+}
+
+/// Outcome of [`HolidayAPI::validate_key`].
+#[derive(Debug)]
+pub enum KeyStatus {
+    /// The key works, with its quota usage when the API reported it.
+    Valid(Option<APIRequests>),
+    /// The API does not know the key (`401`).
+    Invalid(String),
+    /// The key exists but is no longer active (`403`).
+    Expired(String),
+    /// The key is valid but its quota is used up (`402` or `429`).
+    QuotaExceeded(String),
+    /// The API could not be reached, or answered with an unexpected error.
+    Unreachable(HolidayAPIError),
+}
 
 impl HolidayAPI {
+    /// Number of requests [`HolidayAPI::prefetch`] and the batch helpers keep in flight by
+    /// default, see [`HolidayAPI::stream_concurrency`].
+    pub const PREFETCH_CONCURRENCY: usize = 4;
+
+    /// Longest response body accepted by default, see [`HolidayAPI::max_response_size`].
+    pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 16 * 1024 * 1024;
+
+    /// Years for which HolidayAPI serves holiday data.
+    pub const SUPPORTED_YEARS: RangeInclusive<i32> = 2010..=2099;
+
+    /// Days of upcoming holidays [`HolidayAPI::reminders_before`] computes reminders for, today
+    /// included: a full year, even when it spans February 29th.
+    pub const REMINDER_WINDOW: u32 = 366;
+
     pub fn is_valid_key(key: &str) -> Result<(), HolidayAPIError> {
         let uuid_regex =
             Regex::new(r"[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}")
@@ -87,20 +626,77 @@ impl HolidayAPI {
     }
 
     pub fn is_valid_version(version: &i32) -> Result<(), HolidayAPIError> {
-        let valid_versions = [1];
-        if !valid_versions.contains(version) {
-            Err(HolidayAPIError::InvalidVersion(format!(
-                "Invalid version: {}, please choose: {:?}",
-                version, valid_versions
+        Version::try_from(*version).map(|_| ())
+    }
+
+    pub fn is_valid_year(year: &i32) -> Result<(), HolidayAPIError> {
+        if !Self::SUPPORTED_YEARS.contains(year) {
+            Err(HolidayAPIError::InvalidYear(format!(
+                "{}, please choose a year between {} and {}",
+                year,
+                Self::SUPPORTED_YEARS.start(),
+                Self::SUPPORTED_YEARS.end()
             )))
         } else {
             Ok(())
         }
     }
-    fn construct_api(key: &str, version: i32) -> HolidayAPI {
+
+    /// Free accounts can only query these endpoints.
+    const FREE_PLAN_ENDPOINTS: [Endpoint; 3] =
+        [Endpoint::Countries, Endpoint::Holidays, Endpoint::Languages];
+
+    /// Rejects requests that the free plan would answer with `402 Payment Required`.
+    fn check_free_plan(
+        &self,
+        endpoint: &Endpoint,
+        parameters: &HashMap<String, String>,
+    ) -> Result<(), HolidayAPIError> {
+        if !Self::FREE_PLAN_ENDPOINTS.contains(endpoint) {
+            return Err(HolidayAPIError::FreePlanLimitation(format!(
+                "the `{}` endpoint requires a premium plan, free accounts can only use {:?}",
+                endpoint,
+                Self::FREE_PLAN_ENDPOINTS
+            )));
+        }
+        if let Some(year) = parameters.get("year") {
+            let last_year = self.clock.current_year() - 1;
+            if year.parse::<i32>().ok() != Some(last_year) {
+                return Err(HolidayAPIError::FreePlanLimitation(format!(
+                    "free accounts are limited to last year's historical data ({}), got {}",
+                    last_year, year
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn construct_api(key: &str, version: Version) -> HolidayAPI {
         HolidayAPI {
-            base_url: format!("https://holidayapi.com/v{}/", version),
-            key: key.to_owned(),
+            version,
+            base_urls: Arc::new([version.base_url()]),
+            key: key.into(),
+            free_plan: false,
+            cache: None,
+            transport: transport::default_transport(),
+            custom_transport: false,
+            default_country: None,
+            default_language: None,
+            clock: Arc::new(SystemClock),
+            validation: Validation::default(),
+            integrity: Validation::Skip,
+            budget: None,
+            usage: None,
+            hooks: Hooks::default(),
+            #[cfg(feature = "prometheus")]
+            metrics: Metrics::default(),
+            post_processors: PostProcessors::default(),
+            concurrency: None,
+            stream_concurrency: None,
+            retry: None,
+            max_response_size: Some(Self::DEFAULT_MAX_RESPONSE_SIZE),
+            keys: None,
+            tenant: None,
         }
     }
     /// Construct a new holiday API
@@ -121,7 +717,7 @@ impl HolidayAPI {
     pub fn new(key: &str) -> Result<HolidayAPI, HolidayAPIError> {
         Self::is_valid_key(key)?;
 
-        Ok(Self::construct_api(key, 1))
+        Ok(Self::construct_api(key, Version::default()))
     }
 
     /// Construct a new holiday API
@@ -129,7 +725,7 @@ impl HolidayAPI {
     /// # Errors
     ///
     /// Will return an `Err` if the given key is not plausibly a valid one. Or the api version is invalid.
-    /// Current valid versions: `[1]`
+    /// Current valid versions: [`Version::ALL`]
     ///
     /// # Examples
     ///
@@ -142,173 +738,2616 @@ impl HolidayAPI {
     /// ```
     pub fn with_version(key: &str, version: i32) -> Result<HolidayAPI, HolidayAPIError> {
         Self::is_valid_key(key)?;
-        Self::is_valid_version(&version)?;
+        let version = Version::try_from(version)?;
 
         Ok(Self::construct_api(key, version))
     }
 
-    /// Make a custom request.
+    /// Version of the API the client sends its requests to.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// The client, sending its requests to `version` instead, see [`Request::version`].
+    pub(crate) fn at_version(&self, version: Version) -> HolidayAPI {
+        let mut api = self.clone();
+        api.base_urls = self
+            .base_urls
+            .iter()
+            .map(|url| version.rebase(url, self.version))
+            .collect();
+        api.version = version;
+        api
+    }
+
+    /// Enables the free plan guard.
+    ///
+    /// Free accounts only have access to last year's historical data and to the `countries`,
+    /// `holidays` and `languages` endpoints. With the guard on, requests outside of these limits
+    /// fail with [`HolidayAPIError::FreePlanLimitation`] instead of being sent to the API.
+    ///
     /// # Examples
     ///
     /// Basic usage
     ///
     /// ```
-    /// use holidayapi_rust::{ HolidayAPI };
-    /// use std::collections::HashMap;
+    /// use holidayapi_rust::prelude::*;
     ///
-    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
-    /// let _future = api.custom_request("countries", HashMap::new());
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap().free_plan();
     /// ```
-    pub async fn custom_request(
-        &self,
-        endpoint: &str,
-        parameters: HashMap<String, String>,
-    ) -> Result<Response, HolidayAPIError> {
-        let client = reqwest::Client::new();
-        let url = Url::parse(self.base_url.as_str()).unwrap();
-        let url = url.join(endpoint.to_ascii_lowercase().as_str()).unwrap();
-        let url = Url::parse_with_params(&format!("{}?key={}", url, self.key), parameters)
-            .expect("Parameters are invalid");
-        let response = client
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| HolidayAPIError::RequestError(e, "".to_string()))?;
+    pub fn free_plan(&mut self) -> Self {
+        self.free_plan = true;
+        self.to_owned()
+    }
 
-        match response.error_for_status_ref() {
-            Ok(_) => Ok(response),
-            Err(err) => {
-                let val = serde_json::from_str::<Value>(&response.text().await.unwrap())
-                    .expect("Error response to be JSON");
-                let o = val.as_object();
-                let error = o.and_then(|o| o.get("error")).unwrap();
+    /// Enables an in-memory cache for parsed requests, keeping responses of every endpoint for
+    /// `ttl`. See [`HolidayAPI::with_default_cache`] for a TTL per endpoint.
+    ///
+    /// The cache is shared by every clone of the client and every request it generates.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .with_cache(Duration::from_secs(3600));
+    /// ```
+    pub fn with_cache(&mut self, ttl: Duration) -> Self {
+        self.cache = Some(Cache::new(ttl));
+        self.to_owned()
+    }
 
-                Err(HolidayAPIError::RequestError(
-                    err,
-                    error.as_str().unwrap().into(),
-                ))
-            }
-        }
+    /// Enables an in-memory cache keeping each endpoint for a TTL suited to how often its data
+    /// changes: [`Cache::DEFAULT_REFERENCE_TTL`] for countries and languages,
+    /// [`Cache::DEFAULT_WORKDAY_TTL`] for workdays and [`Cache::DEFAULT_HOLIDAYS_TTL`] for
+    /// holidays and the other endpoints.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .with_default_cache()
+    ///     .cache_ttl(Endpoint::Holidays, Duration::from_secs(24 * 3600));
+    /// ```
+    pub fn with_default_cache(&mut self) -> Self {
+        self.cache = Some(Cache::with_defaults());
+        self.to_owned()
     }
 
-    /// Generates a minimal `countries` request and returns it.
+    /// Sets how long the cache keeps the responses of `endpoint`, enabling it with
+    /// [`HolidayAPI::with_default_cache`] if it is not enabled yet. The TTL applies to every
+    /// clone of the client sharing the cache.
+    pub fn cache_ttl(&mut self, endpoint: impl Into<Endpoint>, ttl: Duration) -> Self {
+        self.cache
+            .get_or_insert_with(Cache::with_defaults)
+            .set_ttl(endpoint.into(), ttl);
+        self.to_owned()
+    }
+
+    /// Sends requests with the given [`Transport`] instead of the default one.
     ///
     /// # Examples
     ///
     /// Basic usage
+    ///
     /// ```
     /// use holidayapi_rust::prelude::*;
     ///
-    ///	let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
-    /// let request = api.countries();
+    /// let client = reqwest::Client::builder().build().unwrap();
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .transport(ReqwestTransport::new(client));
     /// ```
+    pub fn transport(&mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Arc::new(transport);
+        self.custom_transport = true;
+        self.to_owned()
+    }
+
+    /// Sends requests with the default transport, resolving host names with the given
+    /// [`Resolver`] instead of the system resolver. A transport set with
+    /// [`transport`](Self::transport) afterwards replaces it, and can be built with its own
+    /// `with_resolver`.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`HolidayAPIError::InvalidTransport`] if a transport was already set with
+    /// [`transport`](Self::transport), which the resolver would silently replace, or if the
+    /// HTTP client cannot be built.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
     ///
-    /// Adding optional parameters with builder pattern
     /// ```
     /// use holidayapi_rust::prelude::*;
     ///
-    ///	let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
-    /// let specific_request = api.countries().search("united states").public();
+    /// let forwarder = Resolver::new().host("holidayapi.com", &["10.0.0.8:443".parse().unwrap()]);
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .resolver(&forwarder)
+    ///     .unwrap();
     /// ```
-    pub fn countries(&self) -> Request<CountriesResponse> {
-        Request::<CountriesResponse>::new(self)
+    pub fn resolver(&mut self, resolver: &Resolver) -> Result<Self, HolidayAPIError> {
+        if self.custom_transport {
+            return Err(HolidayAPIError::InvalidTransport(
+                "a resolver cannot be applied to a transport set with `transport`, build it \
+                 with its own `with_resolver` instead"
+                    .to_string(),
+            ));
+        }
+        self.transport = transport::resolving_transport(resolver)
+            .map_err(|err| HolidayAPIError::InvalidTransport(err.to_string()))?;
+        Ok(self.to_owned())
     }
 
-    /// Generates a minimal `holidays` request and returns it.
+    /// Reads the current time from the given [`Clock`] instead of the system clock.
     ///
     /// # Examples
     ///
     /// Basic usage
+    ///
     /// ```
+    /// use chrono::NaiveDate;
     /// use holidayapi_rust::prelude::*;
     ///
-    ///	let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
-    /// let request = api.holidays("us", 2020);
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .clock(FixedClock::on(NaiveDate::from_ymd_opt(2021, 6, 1).unwrap()));
     /// ```
+    pub fn clock(&mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self.to_owned()
+    }
+
+    /// Sets how strictly parameters are checked before sending requests, e.g. to send country
+    /// codes newer than the embedded ISO lists.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
     ///
-    /// Adding optional parameters with builder pattern
     /// ```
     /// use holidayapi_rust::prelude::*;
     ///
-    ///	let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
-    /// let specific_request = api.holidays("us", 2020).month(12).upcoming();
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .validation(Validation::Warn);
     /// ```
-    pub fn holidays(&self, country: &str, year: i32) -> Request<HolidaysResponse> {
-        Request::<HolidaysResponse>::new(self, country.into(), year)
+    pub fn validation(&mut self, validation: Validation) -> Self {
+        self.validation = validation;
+        self.to_owned()
     }
 
-    /// Generates a minimal `workday` request and returns it.
+    /// Sets whether holidays responses, fetched or cached, are checked against the parameters
+    /// of their request: every holiday must be of the requested country, and of the requested
+    /// year, month and day if any. Off, [`Validation::Skip`], by default.
+    ///
+    /// With [`Validation::Strict`], mismatching responses fail with
+    /// [`HolidayAPIError::IntegrityMismatch`], are not cached, and are removed from the cache.
+    /// With [`Validation::Warn`], a warning is logged.
     ///
     /// # Examples
     ///
     /// Basic usage
+    ///
     /// ```
     /// use holidayapi_rust::prelude::*;
     ///
-    ///	let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
-    /// let request = api.workday("us","YYYY-MM-DD", 100);
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .integrity_checks(Validation::Strict);
     /// ```
-    pub fn workday(&self, country: &str, start: &str, days: i32) -> Request<WorkdayResponse> {
-        Request::<WorkdayResponse>::new(self, country.into(), start, days)
+    pub fn integrity_checks(&mut self, integrity: Validation) -> Self {
+        self.integrity = integrity;
+        self.to_owned()
     }
 
-    /// Generates a minimal `workdays` request and returns it.
+    /// Retries requests failing with a retryable error according to the given [`RetryPolicy`].
+    ///
+    /// The policy is the only one retrying requests. Each attempt tries the
+    /// [base urls](HolidayAPI::base_urls) in order until one can be connected to, which is not a
+    /// retry, so a request is sent at most [`max_attempts`](RetryPolicy::max_attempts) times per
+    /// base url.
+    /// Without a policy, requests are attempted once, and a [`Scheduler`](prelude::Scheduler)
+    /// dispatches rate limited ones again instead.
     ///
     /// # Examples
     ///
     /// Basic usage
+    ///
     /// ```
     /// use holidayapi_rust::prelude::*;
+    /// use std::time::Duration;
     ///
-    ///	let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
-    /// let request = api.workdays("us", "YYYY-MM-DD", "YYYY-MM-DD");
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///         .unwrap()
+    ///         .retry(&RetryPolicy::new().max_elapsed(Duration::from_secs(300)));
+    /// }
     /// ```
-    pub fn workdays(&self, country: &str, start: &str, days: &str) -> Request<WorkdaysResponse> {
-        Request::<WorkdaysResponse>::new(self, country, start, days)
+    pub fn retry(&mut self, policy: &RetryPolicy) -> Self {
+        self.retry = Some(policy.clone());
+        self.to_owned()
     }
 
-    /// Generates a minimal `languages` request and returns it.
+    /// Limits the number of requests in flight at the same time, across the clones of the
+    /// client and every helper sending several requests, such as [`HolidayAPI::prefetch`].
+    ///
+    /// Requests over the limit wait for an earlier one to complete.
     ///
     /// # Examples
     ///
     /// Basic usage
+    ///
     /// ```
     /// use holidayapi_rust::prelude::*;
     ///
-    ///	let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
-    /// let request = api.languages();
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .max_concurrency(2);
+    /// assert_eq!(api.concurrency(), Some(2));
     /// ```
+    pub fn max_concurrency(&mut self, requests: usize) -> Self {
+        self.concurrency = Some(ConcurrencyLimit::new(requests));
+        self.to_owned()
+    }
+
+    /// Limits the size of response bodies, [`HolidayAPI::DEFAULT_MAX_RESPONSE_SIZE`] by default.
+    ///
+    /// Longer responses are aborted, without being read to the end by the built-in transports,
+    /// and fail with [`HolidayAPIError::ResponseTooLarge`], so a base URL pointing at the wrong
+    /// server cannot exhaust the memory of the process. `None` accepts bodies of any size.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
     ///
-    /// Adding optional parameters with builder pattern
     /// ```
     /// use holidayapi_rust::prelude::*;
     ///
-    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
-    /// let specific_request = api.languages().search("united states");
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .max_response_size(Some(1024 * 1024));
     /// ```
-    pub fn languages(&self) -> Request<LanguagesResponse> {
-        Request::<LanguagesResponse>::new(self)
+    pub fn max_response_size(&mut self, bytes: Option<usize>) -> Self {
+        self.max_response_size = bytes;
+        self.to_owned()
     }
-}
 
-#[cfg(test)]
-mod tests {
+    /// Maximum number of requests in flight, if limited with [`HolidayAPI::max_concurrency`].
+    pub fn concurrency(&self) -> Option<usize> {
+        self.concurrency.as_ref().map(ConcurrencyLimit::max)
+    }
 
-    use super::*;
+    /// Sets how many requests the helpers streaming several responses keep in flight, and so
+    /// how many responses they buffer ahead of the consumer: [`HolidayAPI::get_many`],
+    /// [`HolidayAPI::prefetch`], [`HolidayAPI::workdays_stream`] and
+    /// [`HolidayAPI::holidays_by_month`].
+    ///
+    /// Defaults to [`HolidayAPI::PREFETCH_CONCURRENCY`], and to `1` for
+    /// [`HolidayAPI::holidays_by_month`], which requests each month when the stream is polled.
+    /// Lower values hold less memory, higher values fetch faster, within the limit of
+    /// [`HolidayAPI::max_concurrency`].
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .stream_concurrency(16);
+    /// ```
+    pub fn stream_concurrency(&mut self, requests: usize) -> Self {
+        self.stream_concurrency = Some(requests.max(1));
+        self.to_owned()
+    }
 
-    static EXPIRED_KEY: &str = "daaaaaab-aaaa-aaaa-aaaa-2aaaada37e14";
-    static INVALID_KEY: &str = "invalid-key-format";
+    /// Requests kept in flight by a streaming helper, `default` unless set with
+    /// [`HolidayAPI::stream_concurrency`].
+    pub(crate) fn streamed(&self, default: usize) -> usize {
+        self.stream_concurrency.unwrap_or(default)
+    }
 
-    #[test]
-    fn test_valid_key() {
-        match HolidayAPI::new(EXPIRED_KEY) {
-            Ok(_) => assert!(true),
-            Err(_) => unreachable!("Should not return an error on valid key"),
+    /// Limits the number of requests sent per day and per month, see [`Budget`].
+    ///
+    /// The budget is shared with the clones of the client and with every client given a clone
+    /// of the same budget. It resumes from the spending saved with
+    /// [`HolidayAPI::persist_usage`] if enabled.
+    pub fn budget(&mut self, budget: &Budget) -> Self {
+        if let Some(usage) = &self.usage {
+            budget.resume(&usage.budget());
         }
-        match HolidayAPI::new(INVALID_KEY) {
-            Ok(_) => unreachable!("Should return an error on invalid key"),
-            Err(_) => assert!(true),
+        self.budget = Some(budget.clone());
+        self.to_owned()
+    }
+
+    /// Registers a callback run before a request is attempted again, with the number of the
+    /// attempt about to be made (`2` for the first retry) and the error of the previous one.
+    ///
+    /// Requests are attempted again by the [`RetryPolicy`] of the client, see
+    /// [`HolidayAPI::retry`], or by a [`Scheduler`](prelude::Scheduler) when rate limited.
+    /// Trying the next base url within an attempt is not reported.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .on_retry(|attempt, err| eprintln!("Attempt {} after: {}", attempt, err))
+    ///     .on_rate_limited(|retry_after| eprintln!("Rate limited for {:?}", retry_after))
+    ///     .on_quota_low(100, |requests| eprintln!("{} requests left", requests.available));
+    /// ```
+    pub fn on_retry(
+        &mut self,
+        hook: impl Fn(u32, &HolidayAPIError) + Send + Sync + 'static,
+    ) -> Self {
+        self.hooks.set_retry(hook);
+        self.to_owned()
+    }
+
+    /// Registers a callback run when the API answers with `429 Too Many Requests`, with its
+    /// `Retry-After` delay.
+    pub fn on_rate_limited(
+        &mut self,
+        hook: impl Fn(Option<Duration>) + Send + Sync + 'static,
+    ) -> Self {
+        self.hooks.set_rate_limited(hook);
+        self.to_owned()
+    }
+
+    /// Registers a callback run when the quota reported by the API drops below `threshold`
+    /// available requests.
+    ///
+    /// The callback runs once per drop: it runs again only after a response reported a quota
+    /// above the threshold, e.g. once the quota was reset.
+    pub fn on_quota_low(
+        &mut self,
+        threshold: u32,
+        hook: impl Fn(&APIRequests) + Send + Sync + 'static,
+    ) -> Self {
+        self.hooks
+            .set_quota_low(QuotaThreshold::Available(threshold), hook);
+        self.to_owned()
+    }
+
+    /// Registers a callback run when the quota reported by the API drops below `percent`% of
+    /// the requests of the period, with the current usage, like [`HolidayAPI::on_quota_low`].
+    ///
+    /// Replaces the callback registered with [`HolidayAPI::on_quota_low`], if any.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .on_quota_low_percent(10, |requests| {
+    ///         eprintln!("{} requests used, {} left", requests.used, requests.available)
+    ///     });
+    /// ```
+    pub fn on_quota_low_percent(
+        &mut self,
+        percent: u32,
+        hook: impl Fn(&APIRequests) + Send + Sync + 'static,
+    ) -> Self {
+        self.hooks
+            .set_quota_low(QuotaThreshold::Percent(percent.min(100)), hook);
+        self.to_owned()
+    }
+
+    /// Replaces the base urls requests are sent to, e.g. to go through an internal mirror.
+    ///
+    /// Urls are tried in order: when a request cannot connect to one, failing to resolve it,
+    /// being refused or failing the TLS handshake, it is sent to the next one, within the same
+    /// attempt, see [`HolidayAPI::retry`]. Other errors, such as timeouts or errors answered by
+    /// a reachable server, are returned as is, since the request may already have been charged.
+    ///
+    /// # Errors
+    ///
+    /// Will return an `Err` if no url is given or one of them is not a valid url.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .base_urls(&["https://holidayapi.com/v1/", "https://holidays.internal/v1/"])
+    ///     .unwrap();
+    /// ```
+    pub fn base_urls(&mut self, urls: &[&str]) -> Result<Self, HolidayAPIError> {
+        if urls.is_empty() {
+            return Err(HolidayAPIError::InvalidBaseUrl(
+                "at least one base url is required".into(),
+            ));
         }
+        self.base_urls = urls
+            .iter()
+            .map(|url| {
+                // Without a trailing slash, joining the endpoint would replace the last segment.
+                let url = if url.ends_with('/') {
+                    url.to_string()
+                } else {
+                    format!("{}/", url)
+                };
+                Url::parse(&url)
+                    .map_err(|err| HolidayAPIError::InvalidBaseUrl(format!("{}: {}", url, err)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(self.to_owned())
+    }
+
+    /// Sets the country of every request requiring one: `holidays`, `workday`, `workdays` and
+    /// [`EndpointSpec`] requests listing `country`. Builders given a blank country use it
+    /// instead, as [`HolidayAPI::holidays_default`] does.
+    ///
+    /// The `country` filter of `countries` requests is not inherited.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .default_country("de");
+    /// let request = api.holidays_default(2021);
+    /// ```
+    pub fn default_country(&mut self, country: impl Into<CountryCode>) -> Self {
+        self.default_country = Some(Arc::new(country.into()));
+        self.to_owned()
+    }
+
+    /// Sets the language of every `holidays` request, including templates, unless overridden
+    /// with `Request::language`.
+    ///
+    /// The other endpoints have no translated names, and the `language` filter of `languages`
+    /// requests is not inherited.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .default_language("de");
+    /// let german = api.holidays("de", 2021);
+    /// let english = api.holidays("de", 2021).language("en");
+    /// ```
+    pub fn default_language(&mut self, language: &str) -> Self {
+        self.default_language = Some(language.into());
+        self.to_owned()
+    }
+
+    /// Returns the cache, if enabled with [`HolidayAPI::with_cache`].
+    pub fn cache(&self) -> Option<&Cache> {
+        self.cache.as_ref()
+    }
+
+    /// Fetches and caches the holidays of every country and year combination, keeping at most
+    /// [`HolidayAPI::PREFETCH_CONCURRENCY`] requests in flight by default, see
+    /// [`HolidayAPI::stream_concurrency`], or fewer when limited with
+    /// [`HolidayAPI::max_concurrency`].
+    ///
+    /// Combinations already in the cache are not requested again.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`HolidayAPIError::CacheDisabled`] if no cache is enabled, without sending
+    /// any request.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///         .unwrap()
+    ///         .with_cache(Duration::from_secs(3600));
+    ///     let summary = api.prefetch(&["us", "jp"], 2020..=2021).await.unwrap();
+    ///     println!("{} fetched, {} failed", summary.fetched, summary.failed.len());
+    /// }
+    /// ```
+    pub async fn prefetch(
+        &self,
+        countries: &[&str],
+        years: impl IntoIterator<Item = i32>,
+    ) -> Result<PrefetchSummary, HolidayAPIError> {
+        if self.cache.is_none() {
+            return Err(HolidayAPIError::CacheDisabled);
+        }
+        let mut summary = PrefetchSummary::default();
+        let mut pending = Vec::new();
+        let batch = self.batch();
+        for (country, year, request) in batch.prefetch_requests(countries, years) {
+            if request.is_cached() {
+                summary.cached += 1;
+            } else {
+                pending.push((country, year, request));
+            }
+        }
+
+        let mut results = stream::iter(pending)
+            .map(
+                |(country, year, request)| async move { (country, year, request.get_full().await) },
+            )
+            .buffer_unordered(self.streamed(Self::PREFETCH_CONCURRENCY));
+        while let Some((country, year, result)) = results.next().await {
+            match result {
+                Ok(_) => summary.fetched += 1,
+                Err(err) => summary.failed.push((country, year, err)),
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Number of requests [`HolidayAPI::prefetch`] would send with the same arguments, i.e. the
+    /// country and year combinations which are not cached yet.
+    ///
+    /// Nothing is sent without a cache, since prefetching fails.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .with_cache(Duration::from_secs(3600));
+    /// assert_eq!(api.estimate_requests(&["us", "jp"], 2020..=2021), 4);
+    /// ```
+    pub fn estimate_requests(
+        &self,
+        countries: &[&str],
+        years: impl IntoIterator<Item = i32>,
+    ) -> usize {
+        if self.cache.is_none() {
+            return 0;
+        }
+        self.prefetch_requests(countries, years)
+            .filter(|(_, _, request)| !request.is_cached())
+            .count()
+    }
+
+    /// Client whose retries stop once the retry policy's `max_elapsed` has passed since now.
+    fn batch(&self) -> HolidayAPI {
+        let mut api = self.clone();
+        api.retry = self.retry.as_ref().map(RetryPolicy::for_batch);
+        api
+    }
+
+    /// Holidays requests of every country and year combination, with their country and year.
+    fn prefetch_requests<'a>(
+        &'a self,
+        countries: &'a [&str],
+        years: impl IntoIterator<Item = i32>,
+    ) -> impl Iterator<Item = (String, i32, Request<HolidaysResponse>)> + 'a {
+        let years: Vec<i32> = years.into_iter().collect();
+        countries.iter().flat_map(move |country| {
+            years
+                .clone()
+                .into_iter()
+                .map(move |year| (country.to_string(), year, self.holidays(*country, year)))
+        })
+    }
+
+    /// Make a custom request.
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```
+    /// use holidayapi_rust::{ HolidayAPI };
+    /// use std::collections::HashMap;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    /// let _future = api.custom_request("countries", HashMap::new());
+    /// ```
+    pub async fn custom_request(
+        &self,
+        endpoint: impl Into<Endpoint>,
+        parameters: HashMap<String, String>,
+    ) -> Result<Response, HolidayAPIError> {
+        self.send_request(&endpoint.into(), parameters, HeaderMap::new())
+            .await
+    }
+
+    /// Sends a request and returns the response whatever its status.
+    async fn send_raw(
+        &self,
+        endpoint: &Endpoint,
+        parameters: HashMap<String, String>,
+        headers: HeaderMap,
+    ) -> Result<Response, HolidayAPIError> {
+        let _permit = match &self.concurrency {
+            Some(limit) => Some(limit.acquire().await),
+            None => None,
+        };
+        if let Some(tenant) = &self.tenant {
+            tenant.check(self.clock.now())?;
+        }
+        if let Some(budget) = &self.budget {
+            budget.spend(self.clock.now())?;
+        }
+        let mut last_error = None;
+        for base_url in self.base_urls.iter() {
+            if let Some(err) = &last_error {
+                log::warn!("Trying {} after: {}", base_url, err);
+            }
+            let url = self
+                .version
+                .request_url(base_url, endpoint, &self.key, &parameters)?;
+            // Other errors may come after the server received, and charged, the request.
+            match self.transport_get(endpoint, url, headers.clone()).await {
+                Err(HolidayAPIError::TransportError(err))
+                    if transport::is_connect(err.as_ref()) =>
+                {
+                    last_error = Some(HolidayAPIError::TransportError(err))
+                }
+                result => return result,
+            }
+        }
+        Err(last_error.expect("There is at least one base url"))
+    }
+
+    /// Sends a `GET` request with the transport, classifying timeouts and bodies over the size
+    /// limit. `endpoint` names the request in errors.
+    pub(crate) async fn transport_get(
+        &self,
+        endpoint: &Endpoint,
+        url: Url,
+        headers: HeaderMap,
+    ) -> Result<Response, HolidayAPIError> {
+        let request = TransportRequest {
+            url,
+            headers,
+            max_body_size: self.max_response_size,
+        };
+        #[cfg(feature = "prometheus")]
+        self.metrics.request(endpoint);
+        let sent = Instant::now();
+        match self.transport.get(request).await {
+            Ok(response) => match self.max_response_size {
+                Some(limit) if response.bytes().len() > limit => {
+                    Err(HolidayAPIError::ResponseTooLarge(limit))
+                }
+                _ => Ok(response),
+            },
+            Err(err) if transport::is_timeout(err.as_ref()) => Err(HolidayAPIError::Timeout {
+                elapsed: sent.elapsed(),
+                endpoint: endpoint.to_string().into(),
+            }),
+            Err(err) => match err.downcast::<BodyTooLarge>() {
+                Ok(too_large) => Err(HolidayAPIError::ResponseTooLarge(too_large.limit)),
+                Err(err) => Err(HolidayAPIError::TransportError(err)),
+            },
+        }
+    }
+
+    /// Checks the key against the API with a minimal `languages` request.
+    ///
+    /// The check counts as one request against the key's quota.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     match api.validate_key().await {
+    ///         KeyStatus::Valid(requests) => println!("{:?}", requests),
+    ///         KeyStatus::Unreachable(err) => println!("{}", err),
+    ///         status => println!("{:?}", status),
+    ///     }
+    /// }
+    /// ```
+    pub async fn validate_key(&self) -> KeyStatus {
+        let parameters = HashMap::from([
+            ("language".to_string(), "en".to_string()),
+            ("format".to_string(), "json".to_string()),
+        ]);
+        let response = match self
+            .send_raw(&Endpoint::Languages, parameters, HeaderMap::new())
+            .await
+        {
+            Ok(response) => response,
+            Err(err) => return KeyStatus::Unreachable(err),
+        };
+        let body = serde_json::from_slice::<Value>(response.bytes()).ok();
+        let message = body
+            .as_ref()
+            .and_then(|body| body.get("error"))
+            .and_then(|error| error.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        match response.status() {
+            StatusCode::UNAUTHORIZED => KeyStatus::Invalid(message),
+            StatusCode::FORBIDDEN => KeyStatus::Expired(message),
+            StatusCode::PAYMENT_REQUIRED | StatusCode::TOO_MANY_REQUESTS => {
+                KeyStatus::QuotaExceeded(message)
+            }
+            status if status.is_success() => KeyStatus::Valid(
+                body.and_then(|body| body.get("requests").cloned())
+                    .and_then(|requests| serde_json::from_value::<APIRequests>(requests).ok()),
+            ),
+            status => KeyStatus::Unreachable(HolidayAPIError::RequestError(status, message)),
+        }
+    }
+
+    /// Sends a request with extra headers, shared by [`HolidayAPI::custom_request`] and the
+    /// request builders.
+    ///
+    /// Failed requests are retried according to the [`RetryPolicy`], if any.
+    pub(crate) async fn send_request(
+        &self,
+        endpoint: &Endpoint,
+        parameters: HashMap<String, String>,
+        headers: HeaderMap,
+    ) -> Result<Response, HolidayAPIError> {
+        let started = Instant::now();
+        let mut previous = Duration::ZERO;
+        // Number of the attempt being made, from 1.
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let err = match self
+                .send_once(endpoint, parameters.clone(), headers.clone())
+                .await
+            {
+                Ok(response) => return Ok(response),
+                Err(err) => err,
+            };
+            let Some(policy) = &self.retry else {
+                #[cfg(feature = "prometheus")]
+                self.metrics.error(endpoint);
+                return Err(err);
+            };
+            let next = attempt + 1;
+            match policy.delay_before(next, started, previous, &err) {
+                Some(delay) => {
+                    self.hooks.retry(next, &err);
+                    policy.sleep(delay).await;
+                    previous = delay;
+                }
+                None => {
+                    #[cfg(feature = "prometheus")]
+                    self.metrics.error(endpoint);
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    /// Sends a request once and checks its status.
+    async fn send_once(
+        &self,
+        endpoint: &Endpoint,
+        parameters: HashMap<String, String>,
+        headers: HeaderMap,
+    ) -> Result<Response, HolidayAPIError> {
+        let response = self.send_raw(endpoint, parameters, headers).await?;
+
+        let status = response.status();
+        if let Some(content) = UnexpectedContent::detect(&response) {
+            return Err(HolidayAPIError::UnexpectedContentType(Box::new(content)));
+        }
+        // Bodies in other formats than JSON are not checked.
+        let envelope = serde_json::from_slice::<Envelope>(response.bytes()).ok();
+        if let Some(payload) = envelope.as_ref().and_then(|envelope| envelope.status) {
+            if payload != status.as_u16() {
+                return Err(HolidayAPIError::StatusMismatch(status, payload));
+            }
+        }
+        if !status.is_client_error() && !status.is_server_error() {
+            let requests = envelope
+                .and_then(|envelope| envelope.requests)
+                .and_then(|requests| serde_json::from_value::<APIRequests>(requests).ok());
+            if let Some(tenant) = &self.tenant {
+                tenant.succeeded(requests.as_ref());
+            }
+            if let Some(usage) = &self.usage {
+                usage.record(self, requests.as_ref(), response.quota());
+            }
+            if let Some(requests) = requests {
+                #[cfg(feature = "prometheus")]
+                self.metrics.quota_remaining(requests.available);
+                self.hooks.quota(&requests);
+            }
+            return Ok(response);
+        }
+        if let Some(tenant) = &self.tenant {
+            tenant.failed(status, self.clock.now());
+        }
+        if let Some(usage) = &self.usage {
+            usage.record(self, None, response.quota());
+        }
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        // Intermediaries may answer errors in plain text or truncate them, their body is kept.
+        let error = serde_json::from_slice::<Value>(response.bytes())
+            .ok()
+            .and_then(|body| body.get("error")?.as_str().map(str::to_string))
+            .unwrap_or_else(|| response.text());
+
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            self.hooks.rate_limited(retry_after);
+            return Err(HolidayAPIError::RateLimited(
+                retry_after,
+                error,
+                response.quota().map(Box::new),
+            ));
+        }
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return Err(HolidayAPIError::InvalidOrExpiredKey(status, error));
+        }
+        Err(HolidayAPIError::RequestError(status, error))
+    }
+
+    /// Generates a minimal `countries` request and returns it.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    /// let request = api.countries();
+    /// ```
+    ///
+    /// Adding optional parameters with builder pattern
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    /// let specific_request = api.countries().search("united states").public();
+    /// ```
+    pub fn countries(&self) -> Request<CountriesResponse> {
+        Request::<CountriesResponse>::new(self)
+    }
+
+    /// Generates a `holidays` request without a year, and without a country unless set with
+    /// [`HolidayAPI::default_country`], to be configured once and instantiated with
+    /// [`Request::apply`]. Sending it as is fails with [`HolidayAPIError::MissingParameter`].
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let template = api.holidays_template().language("fr").public();
+    ///     for country in ["fr", "be", "ch"] {
+    ///         let holidays = template.apply(country, 2025).get().await;
+    ///     }
+    /// }
+    /// ```
+    pub fn holidays_template(&self) -> Request<HolidaysResponse> {
+        Request::<HolidaysResponse>::template(self)
+    }
+
+    /// Generates a minimal `holidays` request and returns it.
+    ///
+    /// The year is checked against [`HolidayAPI::SUPPORTED_YEARS`] when the request is sent,
+    /// an out of range year fails with [`HolidayAPIError::InvalidYear`] without calling the API.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    /// let request = api.holidays("us", 2020);
+    /// ```
+    ///
+    /// Adding optional parameters with builder pattern
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    /// let specific_request = api.holidays("us", 2020).month(12).upcoming();
+    /// ```
+    pub fn holidays(
+        &self,
+        country: impl Into<CountryCode>,
+        year: i32,
+    ) -> Request<HolidaysResponse> {
+        Request::<HolidaysResponse>::new(self, country.into(), year)
+    }
+
+    /// Generates a minimal `holidays` request for the current year, in UTC.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    /// let request = api.holidays_current("us").public();
+    /// ```
+    pub fn holidays_current(&self, country: impl Into<CountryCode>) -> Request<HolidaysResponse> {
+        self.holidays(country, self.clock.current_year())
+    }
+
+    /// Generates a minimal `holidays` request for the previous year, in UTC.
+    ///
+    /// Free accounts only have access to last year's data, see [`HolidayAPI::free_plan`].
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .free_plan();
+    /// let request = api.holidays_last_year("us");
+    /// ```
+    pub fn holidays_last_year(&self, country: impl Into<CountryCode>) -> Request<HolidaysResponse> {
+        self.holidays(country, self.clock.current_year() - 1)
+    }
+
+    /// Returns a stream of the holidays of a year, month by month, along with the month.
+    ///
+    /// Each month is requested only when the stream is polled, keeping responses small on the
+    /// free plan and allowing to report progress, unless more months are fetched ahead with
+    /// [`HolidayAPI::stream_concurrency`]. A failed month yields its error and the stream
+    /// carries on with the next one.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```no_run
+    /// use futures_util::StreamExt;
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let mut months = api.holidays_by_month("us", 2021);
+    ///     while let Some(Ok((month, holidays))) = months.next().await {
+    ///         println!("{}/12: {} holidays", month, holidays.len());
+    ///     }
+    /// }
+    /// ```
+    pub fn holidays_by_month(
+        &self,
+        country: impl Into<CountryCode>,
+        year: i32,
+    ) -> BoxStream<'static, Result<(u32, Vec<Holiday>), HolidayAPIError>> {
+        let api = self.clone();
+        let country = country.into();
+        stream::iter(1..=12)
+            .map(move |month: u32| {
+                let request = api.holidays(&country, year).month(month as i32);
+                async move { request.get().await.map(|holidays| (month, holidays)) }
+            })
+            .buffered(self.streamed(1))
+            .boxed()
+    }
+
+    /// Returns the holidays of the next `days` days, today included, sorted by date.
+    ///
+    /// The holidays of the current year are fetched, along with the next year's when the window
+    /// crosses New Year, then filtered client-side. The `upcoming` parameter of the API is not
+    /// sent: it only returns the first day of holidays after a date, not a window of days.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     if let Ok(holidays) = api.upcoming_in("us", 30).await {
+    ///         for holiday in holidays {
+    ///             println!("{}: {}", holiday.date, holiday.name);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub async fn upcoming_in(
+        &self,
+        country: impl Into<CountryCode>,
+        days: u32,
+    ) -> Result<Vec<Holiday>, HolidayAPIError> {
+        let country = country.into();
+        let start = self.clock.today();
+        let mut holidays = Vec::new();
+        for year in Self::window_years(start, days) {
+            holidays.extend(self.holidays(&country, year).get().await?);
+        }
+        Ok(Self::within_window(holidays, start, days))
+    }
+
+    /// Last day of the `days` days starting at `start`, or `None` if the window is empty.
+    fn window_end(start: NaiveDate, days: u32) -> Option<NaiveDate> {
+        days.checked_sub(1)
+            .map(|days| start + chrono::Duration::days(i64::from(days)))
+    }
+
+    /// Years covered by the `days` days starting at `start`.
+    pub(crate) fn window_years(start: NaiveDate, days: u32) -> Range<i32> {
+        match Self::window_end(start, days) {
+            Some(end) => start.year()..end.year() + 1,
+            None => start.year()..start.year(),
+        }
+    }
+
+    /// Keeps the holidays of the `days` days starting at `start`, sorted by date.
+    pub(crate) fn within_window(
+        mut holidays: Vec<Holiday>,
+        start: NaiveDate,
+        days: u32,
+    ) -> Vec<Holiday> {
+        let Some(end) = Self::window_end(start, days) else {
+            return Vec::new();
+        };
+        holidays.retain(|holiday| {
+            holiday
+                .parse_date()
+                .is_some_and(|date| start <= date && date <= end)
+        });
+        holidays.sort_by(|a, b| a.date.cmp(&b.date));
+        holidays
+    }
+
+    /// Computes reminders firing `days` days before each holiday of the coming year, the
+    /// [`HolidayAPI::REMINDER_WINDOW`] days from today, see
+    /// [`reminders_before`](crate::prelude::reminders_before).
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     if let Ok(reminders) = api.reminders_before("us", 7).await {
+    ///         for reminder in reminders {
+    ///             println!("{} in {:?}", reminder.holiday.name, reminder.until);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub async fn reminders_before(
+        &self,
+        country: impl Into<CountryCode>,
+        days: u32,
+    ) -> Result<Vec<Reminder>, HolidayAPIError> {
+        let holidays = self.upcoming_in(country, Self::REMINDER_WINDOW).await?;
+        Ok(reminder::reminders_before(
+            &holidays,
+            days,
+            self.clock.now(),
+        ))
+    }
+
+    /// Compares the holidays of a country over two years, see [`YearOverYear`].
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     if let Ok(comparison) = api.compare_years("us", 2020, 2021).await {
+    ///         for shift in comparison.shifted {
+    ///             println!("{} moved by {} days", shift.current.name, shift.days);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub async fn compare_years(
+        &self,
+        country: impl Into<CountryCode>,
+        previous: i32,
+        current: i32,
+    ) -> Result<YearOverYear, HolidayAPIError> {
+        let country = country.into();
+        let before = self.holidays(&country, previous).get().await?;
+        let after = self.holidays(&country, current).get().await?;
+        Ok(YearOverYear::between(&before, &after))
+    }
+
+    /// Generates a minimal `holidays` request for the country set with
+    /// [`HolidayAPI::default_country`].
+    ///
+    /// Without a default country, the request fails with [`HolidayAPIError::MissingParameter`]
+    /// when sent.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .default_country("de")
+    ///     .default_language("de");
+    /// let request = api.holidays_default(2021).public();
+    /// ```
+    pub fn holidays_default(&self, year: i32) -> Request<HolidaysResponse> {
+        Request::<HolidaysResponse>::new(self, CountryCode::new(""), year)
+    }
+
+    /// Generates a minimal `workday` request and returns it.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    /// let request = api.workday("us","YYYY-MM-DD", 100);
+    /// ```
+    pub fn workday(
+        &self,
+        country: impl Into<CountryCode>,
+        start: &str,
+        days: i32,
+    ) -> Request<WorkdayResponse> {
+        Request::<WorkdayResponse>::new(self, country.into(), start, days)
+    }
+
+    /// Generates a minimal `workdays` request and returns it.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    /// let request = api.workdays("us", "YYYY-MM-DD", "YYYY-MM-DD");
+    /// ```
+    pub fn workdays(
+        &self,
+        country: impl Into<CountryCode>,
+        start: &str,
+        days: &str,
+    ) -> Request<WorkdaysResponse> {
+        Request::<WorkdaysResponse>::new(self, country.into(), start, days)
+    }
+
+    /// Generates a minimal `languages` request and returns it.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    /// let request = api.languages();
+    /// ```
+    ///
+    /// Adding optional parameters with builder pattern
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    /// let specific_request = api.languages().search("united states");
+    /// ```
+    pub fn languages(&self) -> Request<LanguagesResponse> {
+        Request::<LanguagesResponse>::new(self)
+    }
+
+    /// Generates a request of an endpoint defined with [`EndpointSpec`], parsed into its
+    /// response type.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// struct Countries;
+    ///
+    /// impl EndpointSpec for Countries {
+    ///     type Response = CountriesResponse;
+    ///     const PATH: &'static str = "countries";
+    /// }
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    /// let request = api.request::<Countries>().parameter("public", "true");
+    /// ```
+    pub fn request<E: EndpointSpec>(&self) -> Request<E::Response> {
+        Request::spec::<E>(self)
+    }
+
+    /// Looks up a language and the countries using it, joining the `languages` and `countries`
+    /// endpoints.
+    ///
+    /// Both responses go through the client's cache, see [`HolidayAPI::with_cache`], so looking
+    /// up several languages only sends two requests. Fails with
+    /// [`HolidayAPIError::UnknownLanguage`] when the language code is not listed.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///         .unwrap()
+    ///         .with_cache(std::time::Duration::from_secs(3600));
+    ///     if let Ok(spanish) = api.countries_for_language("es").await {
+    ///         for country in spanish.countries {
+    ///             println!("{} speaks {}", country.name, spanish.language.name);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub async fn countries_for_language(
+        &self,
+        language: &str,
+    ) -> Result<LanguageCountries, HolidayAPIError> {
+        let language = language.trim();
+        let languages = self.languages().get_full().await?;
+        let language = languages
+            .languages
+            .into_iter()
+            .find(|candidate| candidate.code.eq_ignore_ascii_case(language))
+            .ok_or_else(|| HolidayAPIError::UnknownLanguage(language.to_string()))?;
+        let countries = self
+            .countries()
+            .get_full()
+            .await?
+            .countries
+            .into_iter()
+            .filter(|country| {
+                country
+                    .languages
+                    .iter()
+                    .any(|code| code.eq_ignore_ascii_case(&language.code))
+            })
+            .collect();
+        Ok(LanguageCountries {
+            language,
+            countries,
+        })
+    }
+
+    /// Subdivisions of a country, such as the states of the United States, e.g. to pick one
+    /// before asking for its regional holidays with [`HolidayAPI::holidays`].
+    ///
+    /// Read from the `countries` endpoint, through the client's cache, see
+    /// [`HolidayAPI::with_cache`], so listing the subdivisions of several countries only sends
+    /// one request. Without a cache, only the country is requested. A subdivision code, such as
+    /// `"us-ca"`, lists the subdivisions of its country. Fails with
+    /// [`HolidayAPIError::UnknownCountry`] when the country is not listed.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///         .unwrap()
+    ///         .with_default_cache();
+    ///     let states = api.subdivisions("us").await.unwrap();
+    ///     if let Some(state) = states.first() {
+    ///         let holidays = api.holidays(&*state.code, 2021).get().await;
+    ///     }
+    /// }
+    /// ```
+    pub async fn subdivisions(
+        &self,
+        country: impl Into<CountryCode>,
+    ) -> Result<Vec<Subdivision>, HolidayAPIError> {
+        let code = country.into();
+        let mut request = self.countries();
+        if self.cache.is_none() {
+            request = request.country(code.country());
+        }
+        request
+            .get_full()
+            .await?
+            .countries
+            .into_iter()
+            .find(|candidate| candidate.code.eq_ignore_ascii_case(code.country()))
+            .map(|country| country.subdivisions)
+            .ok_or_else(|| HolidayAPIError::UnknownCountry(code.country().to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::mock::{MockResponse, MockTransport};
+    use crate::{clock::FixedClock, test_support::EXPIRED_KEY};
+    use futures_util::FutureExt;
+    use http::header::HeaderName;
+
+    static INVALID_KEY: &str = "invalid-key-format";
+
+    #[test]
+    fn test_valid_key() {
+        assert!(
+            HolidayAPI::new(EXPIRED_KEY).is_ok(),
+            "Should not return an error on valid key"
+        );
+        assert!(
+            HolidayAPI::new(INVALID_KEY).is_err(),
+            "Should return an error on invalid key"
+        );
+    }
+
+    #[test]
+    fn test_valid_year() {
+        assert!(HolidayAPI::is_valid_year(&2021).is_ok());
+        assert!(HolidayAPI::is_valid_year(&0).is_err());
+        assert!(HolidayAPI::is_valid_year(&99999).is_err());
+    }
+
+    #[test]
+    fn test_error_is_portable() {
+        fn assert_portable<T: Error + Send + Sync + 'static>() {}
+        assert_portable::<HolidayAPIError>();
+        assert!(std::mem::size_of::<HolidayAPIError>() <= 48);
+
+        fn fail() -> Result<(), Box<dyn Error + Send + Sync>> {
+            Err(HolidayAPIError::TransportError("connection reset".into()))?
+        }
+        let err = fail().unwrap_err();
+        assert_eq!(err.to_string(), "Transport error: connection reset");
+        let err = err.downcast::<HolidayAPIError>().unwrap();
+        assert_eq!(err.source().unwrap().to_string(), "connection reset");
+        assert!(HolidayAPIError::CacheDisabled.source().is_none());
+    }
+
+    #[test]
+    fn test_error_classification() {
+        let server_error = HolidayAPIError::RequestError(StatusCode::BAD_GATEWAY, "".into());
+        assert!(server_error.is_retryable());
+        assert!(!server_error.is_quota());
+        assert_eq!(server_error.status(), Some(StatusCode::BAD_GATEWAY));
+
+        let payment = HolidayAPIError::RequestError(StatusCode::PAYMENT_REQUIRED, "".into());
+        assert!(!payment.is_retryable());
+        assert!(payment.is_quota());
+
+        let rate_limited = HolidayAPIError::RateLimited(None, "".into(), None);
+        assert!(rate_limited.is_retryable() && rate_limited.is_quota());
+        assert!(!HolidayAPIError::InvalidYear("0".into()).is_retryable());
+    }
+
+    #[test]
+    fn test_error_serialize() {
+        let rate_limited =
+            HolidayAPIError::RateLimited(Some(Duration::from_secs(30)), "Slow down.".into(), None);
+        assert_eq!(
+            serde_json::to_value(&rate_limited).unwrap(),
+            serde_json::json!({
+                "status": 429,
+                "kind": "RateLimited",
+                "message": "Rate limited: Slow down.\nRetry after 30s",
+                "endpoint": null,
+                "retry_after": 30,
+            })
+        );
+        let timeout = HolidayAPIError::Timeout {
+            elapsed: Duration::from_millis(1500),
+            endpoint: "holidays".into(),
+        };
+        let value = serde_json::to_value(&timeout).unwrap();
+        assert_eq!(value["kind"], "Timeout");
+        assert_eq!(value["endpoint"], "holidays");
+        assert!(value["status"].is_null() && value["retry_after"].is_null());
+
+        let transport = HolidayAPIError::TransportError(
+            format!(
+                "error sending request for url (https://holidayapi.com/v1/holidays?key={}&country=US)",
+                EXPIRED_KEY
+            )
+            .into(),
+        );
+        let message = serde_json::to_value(&transport).unwrap()["message"].clone();
+        assert_eq!(
+            message,
+            "Transport error: error sending request for url \
+             (https://holidayapi.com/v1/holidays?key=REDACTED&country=US)"
+        );
+        let invalid = HolidayAPIError::InvalidKeyFormat(EXPIRED_KEY.into());
+        assert_eq!(
+            serde_json::to_value(&invalid).unwrap()["message"],
+            "Invalid key"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_key_error_keeps_status() {
+        let api = mock_api(
+            StatusCode::UNAUTHORIZED,
+            r#"{ "status": 401, "error": "Missing or invalid API key." }"#,
+        );
+        let err = api.countries().get().await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::UNAUTHORIZED));
+        assert_eq!(serde_json::to_value(&err).unwrap()["status"], 401);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_year_is_rejected_locally() {
+        let api = HolidayAPI::new(EXPIRED_KEY).unwrap();
+        match api.holidays("us", 0).get().await {
+            Err(HolidayAPIError::InvalidYear(message)) => assert!(message.contains("2010")),
+            other => unreachable!("Should reject year 0 before sending, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_last_year_passes_free_plan_guard() {
+        let api = mock_api(StatusCode::OK, HOLIDAYS_BODY).free_plan();
+        assert!(api.holidays_last_year("us").get().await.is_ok());
+        assert!(api.holidays_current("us").get().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_free_plan_guard() {
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .free_plan()
+            .clock(FixedClock::on(NaiveDate::from_ymd_opt(2022, 6, 1).unwrap()));
+        let this_year = 2022;
+        match api.holidays("us", this_year).get().await {
+            Err(HolidayAPIError::FreePlanLimitation(_)) => {}
+            other => unreachable!("Should reject the current year, got {:?}", other),
+        }
+        match api.workdays("us", "2021-01-01", "2021-02-01").get().await {
+            Err(HolidayAPIError::FreePlanLimitation(_)) => {}
+            other => unreachable!("Should reject premium endpoints, got {:?}", other),
+        }
+        assert!(api
+            .check_free_plan(
+                &Endpoint::Holidays,
+                &HashMap::from([("year".to_string(), (this_year - 1).to_string())])
+            )
+            .is_ok());
+    }
+
+    static HOLIDAYS_BODY: &str = r#"{
+        "status": 200,
+        "requests": { "used": 1, "available": 9999, "resets": "2021-01-01 00:00:00" },
+        "holidays": [{
+            "name": "New Year's Day",
+            "date": "2021-01-01",
+            "observed": "2021-01-01",
+            "public": true,
+            "country": "US",
+            "uuid": "82f78b8a-019e-479e-a19f-99040275f9bf",
+            "weekday": {
+                "date": { "name": "Friday", "numeric": "5" },
+                "observed": { "name": "Friday", "numeric": "5" }
+            }
+        }]
+    }"#;
+
+    #[tokio::test]
+    async fn test_cached_request_skips_api() {
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .with_cache(Duration::from_secs(60));
+        let request = api.holidays("us", 2021);
+        assert!(!request.is_cached());
+        let cache = api.cache().unwrap();
+        let key = request.cache_key();
+        let revalidation = cache.insert(
+            key.clone(),
+            Default::default(),
+            HOLIDAYS_BODY.into(),
+            Default::default(),
+        );
+        assert_eq!(revalidation, Revalidation::Fetched);
+        let revalidation = cache.insert(
+            key,
+            Default::default(),
+            HOLIDAYS_BODY.into(),
+            Default::default(),
+        );
+        assert_eq!(revalidation, Revalidation::Unchanged);
+        assert!(request.is_cached());
+
+        let holidays = request.clone().get().await.unwrap();
+        assert_eq!(holidays[0].name, "New Year's Day");
+
+        assert_eq!(api.estimate_requests(&["us", "jp"], 2020..=2021), 3);
+        assert_eq!(
+            HolidayAPI::new(EXPIRED_KEY)
+                .unwrap()
+                .estimate_requests(&["us"], [2021]),
+            0
+        );
+        assert!(matches!(
+            HolidayAPI::new(EXPIRED_KEY)
+                .unwrap()
+                .prefetch(&["us"], [2021])
+                .await,
+            Err(HolidayAPIError::CacheDisabled)
+        ));
+        let summary = api.prefetch(&["us"], [2021]).await.unwrap();
+        assert_eq!(summary.cached, 1);
+        assert_eq!(summary.fetched, 0);
+
+        assert_eq!(cache.invalidate_endpoint(Endpoint::Countries), 0);
+        assert_eq!(cache.invalidate("US", 2020), 0);
+        assert_eq!(cache.invalidate("US", 2021), 1);
+        assert!(!request.is_cached());
+    }
+
+    #[tokio::test]
+    async fn test_custom_transport() {
+        let mut api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(MockTransport::new(MockResponse::ok(HOLIDAYS_BODY)));
+        let holidays = api.holidays("us", 2021).get().await.unwrap();
+        assert_eq!(holidays[0].name, "New Year's Day");
+
+        let api = api.transport(MockTransport::new(MockResponse::new(
+            StatusCode::UNAUTHORIZED,
+            r#"{ "status": 401, "error": "Missing or invalid API key." }"#,
+        )));
+        match api.holidays("us", 2021).get().await {
+            Err(HolidayAPIError::InvalidOrExpiredKey(StatusCode::UNAUTHORIZED, message)) => {
+                assert_eq!(message, "Missing or invalid API key.")
+            }
+            other => unreachable!("Should fail with a key error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_request_endpoints() {
+        let transport = MockTransport::new(MockResponse::ok(HOLIDAYS_BODY));
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone());
+        assert_eq!(Endpoint::from("Holidays"), Endpoint::Holidays);
+        assert_eq!(
+            Endpoint::from("holidays/upcoming"),
+            Endpoint::Other("holidays/upcoming".into())
+        );
+
+        api.custom_request("Countries", HashMap::new())
+            .await
+            .unwrap();
+        api.custom_request(Endpoint::Other("holidays/upcoming".into()), HashMap::new())
+            .await
+            .unwrap();
+        let paths: Vec<String> = transport
+            .requests()
+            .iter()
+            .map(|url| url.path().to_string())
+            .collect();
+        assert_eq!(paths, ["/v1/countries", "/v1/holidays/upcoming"]);
+
+        for path in [
+            "//evil.example/v1",
+            "/v2/holidays",
+            "https://evil.example/",
+            "a:b",
+            "../v2/holidays",
+            "holidays/../../v2/holidays",
+            "holidays\\..",
+            "%2E%2e/v2/holidays",
+        ] {
+            match api.custom_request(path, HashMap::new()).await {
+                Err(HolidayAPIError::InvalidEndpoint(invalid)) => assert_eq!(invalid, path),
+                other => unreachable!("Should reject {}, got {:?}", path, other),
+            }
+        }
+        assert_eq!(transport.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_spec() {
+        struct Upcoming;
+
+        impl EndpointSpec for Upcoming {
+            type Response = HolidaysResponse;
+            const PATH: &'static str = "holidays/upcoming";
+            const REQUIRED_PARAMETERS: &'static [&'static str] = &["country"];
+        }
+
+        let transport = MockTransport::new(MockResponse::ok(HOLIDAYS_BODY));
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone());
+        assert!(matches!(
+            api.request::<Upcoming>().get_full().await,
+            Err(HolidayAPIError::MissingParameter(parameter)) if parameter == "country"
+        ));
+        let response = api
+            .request::<Upcoming>()
+            .parameter("country", "us")
+            .get_full()
+            .await
+            .unwrap();
+        assert_eq!(response.holidays[0].name, "New Year's Day");
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path(), "/v1/holidays/upcoming");
+    }
+
+    #[tokio::test]
+    async fn test_integrity_checks() {
+        let transport = MockTransport::new(MockResponse::ok(HOLIDAYS_BODY));
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+            .with_cache(Duration::from_secs(60));
+        assert!(api.holidays("us", 2020).get().await.is_ok());
+
+        let strict = api.clone().integrity_checks(Validation::Strict);
+        assert!(matches!(
+            strict.holidays("us", 2020).get().await,
+            Err(HolidayAPIError::IntegrityMismatch(mismatch)) if mismatch.contains("year 2020")
+        ));
+        assert!(!api.holidays("us", 2020).is_cached());
+        assert!(matches!(
+            strict.holidays("ca", 2021).get().await,
+            Err(HolidayAPIError::IntegrityMismatch(_))
+        ));
+        assert!(!api.holidays("ca", 2021).is_cached());
+        assert!(strict.holidays("us", 2021).get().await.is_ok());
+        assert!(api.holidays("us", 2021).is_cached());
+
+        let warn = api.clone().integrity_checks(Validation::Warn);
+        assert!(warn.holidays("us", 2020).get().await.is_ok());
+        assert_eq!(transport.calls(), 4);
+    }
+
+    #[test]
+    fn test_block_on_blocking_transport() {
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(MockTransport::new(MockResponse::ok(HOLIDAYS_BODY)));
+        let holidays = block_on(api.holidays("us", 2021).get()).unwrap();
+        assert_eq!(holidays.len(), 1);
+    }
+
+    fn mock_api(status: StatusCode, body: &'static str) -> HolidayAPI {
+        HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(MockTransport::new(MockResponse::new(status, body)))
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_upcoming_reads_clock() {
+        let api = mock_api(StatusCode::OK, HOLIDAYS_BODY).clock(FixedClock::on(
+            NaiveDate::from_ymd_opt(2020, 12, 25).unwrap(),
+        ));
+        let first = api
+            .watch_upcoming("us", Duration::from_millis(1))
+            .window(8)
+            .into_stream()
+            .next()
+            .await;
+        assert_eq!(first.unwrap().unwrap().len(), 1);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_watch_upcoming_keeps_polling_on_errors() {
+        let body = r#"{ "status": 503, "error": "Service unavailable." }"#;
+        let errors: Vec<_> = mock_api(StatusCode::SERVICE_UNAVAILABLE, body)
+            .watch_upcoming("us", Duration::from_millis(1))
+            .into_stream()
+            .take(2)
+            .collect()
+            .await;
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|result| result.is_err()));
+    }
+
+    #[tokio::test]
+    async fn test_validate_key() {
+        match mock_api(StatusCode::OK, HOLIDAYS_BODY).validate_key().await {
+            KeyStatus::Valid(Some(requests)) => assert_eq!(requests.available, 9999),
+            other => unreachable!("Should be valid, got {:?}", other),
+        }
+        let body = r#"{ "status": 401, "error": "Missing or invalid API key." }"#;
+        match mock_api(StatusCode::UNAUTHORIZED, body)
+            .validate_key()
+            .await
+        {
+            KeyStatus::Invalid(message) => assert_eq!(message, "Missing or invalid API key."),
+            other => unreachable!("Should be invalid, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stale_fallback() {
+        let body = r#"{ "status": 503, "error": "Service unavailable." }"#;
+        let api = mock_api(StatusCode::SERVICE_UNAVAILABLE, body).with_cache(Duration::ZERO);
+        let request = api.holidays("us", 2021);
+        assert!(request.clone().get_full_or_stale().await.is_err());
+
+        let cache = api.cache().unwrap();
+        cache.insert(
+            request.cache_key(),
+            Default::default(),
+            HOLIDAYS_BODY.into(),
+            Default::default(),
+        );
+        let response = request.get_full_or_stale().await.unwrap();
+        assert!(response.stale);
+        assert_eq!(response.data.holidays.len(), 1);
+    }
+
+    /// Transport failing for every host but one.
+    #[derive(Debug)]
+    struct SingleHostTransport(&'static str);
+
+    impl Transport for SingleHostTransport {
+        fn get(
+            &self,
+            request: TransportRequest,
+        ) -> transport::BoxFuture<'_, Result<Response, TransportError>> {
+            let response = match request.url.host_str() {
+                Some(host) if host == self.0 => Ok(Response::new(
+                    StatusCode::OK,
+                    HeaderMap::new(),
+                    HOLIDAYS_BODY.into(),
+                )),
+                _ => Err(std::io::Error::from(std::io::ErrorKind::ConnectionRefused).into()),
+            };
+            Box::pin(async move { response })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_base_url_failover() {
+        let mut api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(SingleHostTransport("mirror.internal"));
+        assert!(api.base_urls(&[]).is_err());
+
+        let api = api
+            .base_urls(&["https://holidayapi.com/v1", "https://mirror.internal/v1"])
+            .unwrap();
+        assert_eq!(api.holidays("us", 2021).get().await.unwrap().len(), 1);
+
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(SingleHostTransport("mirror.internal"));
+        match api.holidays("us", 2021).get().await {
+            Err(HolidayAPIError::TransportError(_)) => {}
+            other => unreachable!("Should fail to connect, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_base_url_failover_skips_timeouts() {
+        let transport =
+            MockTransport::new(MockResponse::ok(HOLIDAYS_BODY)).on_call(1, MockResponse::timeout());
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .base_urls(&["https://holidayapi.com/v1/", "https://mirror.internal/v1/"])
+            .unwrap()
+            .transport(transport.clone());
+        assert!(matches!(
+            api.holidays("us", 2021).get().await,
+            Err(HolidayAPIError::Timeout { .. })
+        ));
+        assert_eq!(transport.calls(), 1);
+        assert_eq!(transport.requests()[0].host_str(), Some("holidayapi.com"));
+    }
+
+    #[tokio::test]
+    async fn test_client_defaults() {
+        let mut api = mock_api(StatusCode::OK, HOLIDAYS_BODY);
+        match api.holidays_default(2021).get().await {
+            Err(HolidayAPIError::MissingParameter(parameter)) => assert_eq!(parameter, "country"),
+            other => unreachable!("Should require a country, got {:?}", other),
+        }
+
+        let api = api.default_country("de").default_language("de");
+        let request = api.holidays_default(2021);
+        assert_eq!(request.cache_key(), api.holidays("de", 2021).cache_key());
+        assert_ne!(
+            request.cache_key(),
+            api.holidays("de", 2021).language("en").cache_key()
+        );
+        assert!(request.get().await.is_ok());
+
+        assert_eq!(
+            api.holidays_template().apply("", 2021).cache_key(),
+            api.holidays("de", 2021).cache_key()
+        );
+        assert_eq!(
+            api.workday("", "2021-01-04", 5).cache_key(),
+            api.workday("de", "2021-01-04", 5).cache_key()
+        );
+        assert_eq!(
+            api.workdays("", "2021-01-04", "2021-01-29").cache_key(),
+            api.workdays("DE", "2021-01-04", "2021-01-29").cache_key()
+        );
+        assert_ne!(
+            api.workdays("at", "2021-01-04", "2021-01-29").cache_key(),
+            api.workdays("de", "2021-01-04", "2021-01-29").cache_key()
+        );
+        assert_eq!(
+            api.countries().cache_key(),
+            HolidayAPI::new(EXPIRED_KEY)
+                .unwrap()
+                .countries()
+                .cache_key()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upcoming_in_crosses_new_year() {
+        let transport = MockTransport::new(MockResponse::ok(HOLIDAYS_BODY));
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+            .clock(FixedClock::on(
+                NaiveDate::from_ymd_opt(2020, 12, 20).unwrap(),
+            ));
+        let holidays = api.upcoming_in("us", 30).await.unwrap();
+        assert!(!holidays.is_empty());
+        assert!(holidays.iter().all(|holiday| holiday.date == "2021-01-01"));
+        let years: Vec<String> = transport
+            .requests()
+            .iter()
+            .map(|url| {
+                assert!(!url.query_pairs().any(|(key, _)| key == "upcoming"));
+                url.query_pairs()
+                    .find(|(key, _)| key == "year")
+                    .unwrap()
+                    .1
+                    .into_owned()
+            })
+            .collect();
+        assert_eq!(years, ["2020", "2021"]);
+        assert!(api.upcoming_in("us", 5).await.unwrap().is_empty());
+
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+            .clock(FixedClock::on(
+                NaiveDate::from_ymd_opt(2020, 12, 27).unwrap(),
+            ));
+        assert!(api.upcoming_in("us", 5).await.unwrap().is_empty());
+        let holidays = api.upcoming_in("us", 6).await.unwrap();
+        assert!(!holidays.is_empty());
+        assert!(holidays.iter().all(|holiday| holiday.date == "2021-01-01"));
+        let requests = transport.requests().len();
+        assert!(api.upcoming_in("us", 0).await.unwrap().is_empty());
+        assert_eq!(transport.requests().len(), requests);
+    }
+
+    #[tokio::test]
+    async fn test_reminders_cover_a_leap_year() {
+        let api = mock_api(StatusCode::OK, HOLIDAYS_BODY)
+            .clock(FixedClock::on(NaiveDate::from_ymd_opt(2020, 1, 2).unwrap()));
+        let reminders = api.reminders_before("us", 7).await.unwrap();
+        assert!(!reminders.is_empty());
+        assert!(reminders
+            .iter()
+            .all(|reminder| reminder.holiday.date == "2021-01-01"));
+    }
+
+    #[tokio::test]
+    async fn test_countries_for_language() {
+        let transport = MockTransport::new(
+            crate::fixtures::Fixture::named("countries")
+                .unwrap()
+                .mock_response(),
+        )
+        .on_call(
+            1,
+            crate::fixtures::Fixture::named("languages")
+                .unwrap()
+                .mock_response(),
+        );
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+            .with_cache(Duration::from_secs(60));
+
+        let french = api.countries_for_language("FR").await.unwrap();
+        assert_eq!(&*french.language.name, "French");
+        let codes: Vec<&str> = french
+            .countries
+            .iter()
+            .map(|country| &*country.code)
+            .collect();
+        assert_eq!(codes, ["CA"]);
+        assert_eq!(
+            api.countries_for_language("en")
+                .await
+                .unwrap()
+                .countries
+                .len(),
+            2
+        );
+        assert!(matches!(
+            api.countries_for_language("es").await,
+            Err(HolidayAPIError::UnknownLanguage(language)) if language == "es"
+        ));
+        assert_eq!(transport.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_subdivisions() {
+        let transport = MockTransport::new(
+            crate::fixtures::Fixture::named("countries")
+                .unwrap()
+                .mock_response(),
+        );
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+            .with_cache(Duration::from_secs(60));
+
+        let states = api.subdivisions(" us ").await.unwrap();
+        let codes: Vec<&str> = states.iter().map(|state| &*state.code).collect();
+        assert_eq!(codes, ["US-CA", "US-NY"]);
+        assert_eq!(api.subdivisions("ca").await.unwrap().len(), 1);
+        assert_eq!(api.subdivisions("us-ca").await.unwrap().len(), 2);
+        assert!(matches!(
+            api.subdivisions("xx").await,
+            Err(HolidayAPIError::UnknownCountry(country)) if country == "XX"
+        ));
+        assert_eq!(transport.calls(), 1);
+
+        let uncached = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone());
+        assert_eq!(uncached.subdivisions("us-ny").await.unwrap().len(), 2);
+        assert!(transport.requests()[1]
+            .query_pairs()
+            .any(|(key, value)| key == "country" && value == "US"));
+    }
+
+    #[tokio::test]
+    async fn test_subdivision_country() {
+        let transport = MockTransport::new(MockResponse::ok(HOLIDAYS_BODY));
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone());
+        assert!(api.holidays("us-ca", 2021).get().await.is_ok());
+        assert!(transport.requests()[0]
+            .query_pairs()
+            .any(|(key, value)| key == "country" && value == "US-CA"));
+        match api.holidays("us-california", 2021).get().await {
+            Err(HolidayAPIError::InvalidCountry(country)) => assert_eq!(country, "US-CALIFORNIA"),
+            other => unreachable!("Should reject the country code, got {:?}", other),
+        }
+        assert_eq!(transport.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_codes_fail_locally() {
+        let transport = MockTransport::new(MockResponse::ok(HOLIDAYS_BODY));
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone());
+        match api.holidays("xx", 2021).get().await {
+            Err(HolidayAPIError::UnknownCountry(country)) => assert_eq!(country, "XX"),
+            other => unreachable!("Should reject the country, got {:?}", other),
+        }
+        match api.holidays("us", 2021).language("xx").get().await {
+            Err(HolidayAPIError::UnknownLanguage(language)) => assert_eq!(language, "xx"),
+            other => unreachable!("Should reject the language, got {:?}", other),
+        }
+        assert!(api
+            .holidays("usa", 2021)
+            .language("pt-BR")
+            .get()
+            .await
+            .is_ok());
+        assert_eq!(transport.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_validation_strictness() {
+        let transport = MockTransport::new(MockResponse::ok(HOLIDAYS_BODY));
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone());
+        match api.holidays("us", 2021).month(2).day(30).get().await {
+            Err(HolidayAPIError::InvalidDate(date)) => assert_eq!(date, "2021-02-30"),
+            other => unreachable!("Should reject the date, got {:?}", other),
+        }
+        assert!(api
+            .workdays("us", "2021-1-1", "2021-02-01")
+            .get()
+            .await
+            .is_err());
+        assert_eq!(transport.calls(), 0);
+
+        let warn = api.clone().validation(Validation::Warn);
+        assert!(warn.holidays("xx", 2021).get().await.is_ok());
+        let skip = api.clone().validation(Validation::Skip);
+        assert!(skip.holidays("us", 2021).month(13).get().await.is_ok());
+        assert!(matches!(
+            skip.holidays("", 2021).get().await,
+            Err(HolidayAPIError::MissingParameter(_))
+        ));
+        assert_eq!(transport.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_budget_fails_fast() {
+        let transport = MockTransport::new(MockResponse::ok(HOLIDAYS_BODY));
+        let budget = Budget::new().per_day(2);
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+            .clock(FixedClock::on(NaiveDate::from_ymd_opt(2021, 6, 1).unwrap()))
+            .budget(&budget);
+        assert!(api.holidays("us", 2021).get().await.is_ok());
+        assert!(api.clone().holidays("jp", 2021).get().await.is_ok());
+        match api.holidays("de", 2021).get().await {
+            Err(err @ HolidayAPIError::BudgetExceeded(_)) => assert!(err.is_quota()),
+            other => unreachable!("Should exceed the budget, got {:?}", other),
+        }
+        assert_eq!(transport.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_hooks() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let quota = |available: u32| {
+            format!(
+                r#"{{ "status": 200, "requests": {{ "used": 0, "available": {}, "resets": "" }},
+                    "holidays": [] }}"#,
+                available
+            )
+        };
+        let transport = MockTransport::new(MockResponse::ok(&quota(50)))
+            .on_call(1, MockResponse::ok(&quota(200)))
+            .on_call(2, MockResponse::rate_limited(Some(Duration::from_secs(3))))
+            .on_call(3, MockResponse::connection_refused())
+            .on_call(6, MockResponse::ok(&quota(200)));
+        let (retries, rate_limits, lows) = (events.clone(), events.clone(), events.clone());
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .base_urls(&["https://a.example/v1/", "https://b.example/v1/"])
+            .unwrap()
+            .transport(transport)
+            .on_retry(move |attempt, err| {
+                retries
+                    .lock()
+                    .unwrap()
+                    .push(format!("retry {} {}", attempt, err))
+            })
+            .on_rate_limited(move |retry_after| {
+                rate_limits
+                    .lock()
+                    .unwrap()
+                    .push(format!("429 {:?}", retry_after))
+            })
+            .on_quota_low(100, move |requests| {
+                lows.lock()
+                    .unwrap()
+                    .push(format!("low {}", requests.available))
+            });
+
+        for _ in 0..6 {
+            let _ = api.holidays("us", 2021).get().await;
+        }
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec!["429 Some(3s)", "low 50", "low 50",]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quota_low_percent() {
+        let quota = |used: u32, available: u32| {
+            format!(
+                r#"{{ "status": 200, "requests": {{ "used": {}, "available": {}, "resets": "" }},
+                    "holidays": [] }}"#,
+                used, available
+            )
+        };
+        let transport = MockTransport::new(MockResponse::ok(&quota(95, 5)))
+            .on_call(1, MockResponse::ok(&quota(80, 20)))
+            .on_call(2, MockResponse::ok(&quota(90, 10)))
+            .on_call(3, MockResponse::ok(&quota(91, 9)));
+        let lows = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = lows.clone();
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport)
+            .on_quota_low_percent(10, move |requests| {
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push((requests.used, requests.available))
+            });
+
+        for _ in 0..4 {
+            let _ = api.holidays("us", 2021).get().await;
+        }
+        assert_eq!(*lows.lock().unwrap(), [(91, 9)]);
+    }
+
+    #[tokio::test]
+    async fn test_max_concurrency() {
+        let latency = Duration::from_millis(30);
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(MockTransport::new(
+                MockResponse::ok(HOLIDAYS_BODY).latency(latency),
+            ))
+            .with_cache(Duration::from_secs(60))
+            .max_concurrency(1);
+        let start = std::time::Instant::now();
+        let summary = api.prefetch(&["us", "jp"], 2020..=2021).await.unwrap();
+        assert_eq!(summary.fetched, 4);
+        assert!(start.elapsed() >= latency * 4);
+
+        let limit = ConcurrencyLimit::new(2);
+        let first = limit.acquire().await;
+        let second = limit.acquire().await;
+        let mut cancelled = Box::pin(limit.acquire());
+        let mut waiting = Box::pin(limit.acquire());
+        assert!((&mut cancelled).now_or_never().is_none());
+        assert!((&mut waiting).now_or_never().is_none());
+        drop(cancelled);
+        drop(first);
+        let third = waiting.await;
+        drop((second, third));
+        let _permits = (limit.acquire().await, limit.acquire().await);
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn test_resolver_overrides_dns() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0; 1024];
+            let _ = socket.read(&mut request).await.unwrap();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                HOLIDAYS_BODY.len(),
+                HOLIDAYS_BODY
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let url = format!("http://holidayapi.invalid:{}/v1/", address.port());
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .base_urls(&[&url])
+            .unwrap()
+            .resolver(&Resolver::new().host("holidayapi.invalid", &[address]))
+            .unwrap();
+        let holidays = api.holidays("us", 2021).get().await.unwrap();
+        assert_eq!(holidays[0].name, "New Year's Day");
+
+        let custom = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(MockTransport::new(MockResponse::ok(HOLIDAYS_BODY)))
+            .resolver(&Resolver::new().host("holidayapi.invalid", &[address]));
+        assert!(matches!(custom, Err(HolidayAPIError::InvalidTransport(_))));
+    }
+
+    #[tokio::test]
+    async fn test_max_response_size() {
+        let transport = MockTransport::new(MockResponse::ok(HOLIDAYS_BODY));
+        let mut api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+            .base_urls(&["https://wrong.example/v1/", "https://holidayapi.com/v1/"])
+            .unwrap()
+            .max_response_size(Some(64));
+        match api.holidays("us", 2021).get().await {
+            Err(err @ HolidayAPIError::ResponseTooLarge(64)) => {
+                assert!(!err.is_retryable());
+                assert_eq!(
+                    err.to_string(),
+                    "Response longer than the limit of 64 bytes"
+                );
+            }
+            other => unreachable!("Should reject the body, got {:?}", other),
+        }
+        assert_eq!(transport.calls(), 1);
+        assert!(api
+            .max_response_size(None)
+            .holidays("us", 2021)
+            .get()
+            .await
+            .is_ok());
+        assert!(mock_api(StatusCode::OK, HOLIDAYS_BODY)
+            .holidays("us", 2021)
+            .get()
+            .await
+            .is_ok());
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn test_max_response_size_aborts_download() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0; 1024];
+            let _ = socket.read(&mut request).await.unwrap();
+            let head = "HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n";
+            socket.write_all(head.as_bytes()).await.unwrap();
+            // An endless body, only stopped by the client.
+            let chunk = format!("400\r\n{}\r\n", "x".repeat(1024));
+            while socket.write_all(chunk.as_bytes()).await.is_ok() {}
+        });
+
+        let url = format!("http://{}/v1/", address);
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .base_urls(&[&url])
+            .unwrap()
+            .max_response_size(Some(4096));
+        assert!(matches!(
+            api.holidays("us", 2021).get().await,
+            Err(HolidayAPIError::ResponseTooLarge(4096))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_holidays_by_month() {
+        let transport = MockTransport::new(MockResponse::ok(HOLIDAYS_BODY)).on_call(
+            2,
+            MockResponse::new(StatusCode::BAD_REQUEST, r#"{"status":400,"error":"Bad"}"#),
+        );
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone());
+
+        let mut months = api.holidays_by_month("us", 2021);
+        assert!(transport.requests().is_empty());
+        let (month, holidays) = months.next().await.unwrap().unwrap();
+        assert_eq!((month, holidays[0].name.as_str()), (1, "New Year's Day"));
+        assert!(months.next().await.unwrap().is_err());
+        assert_eq!(months.next().await.unwrap().unwrap().0, 3);
+        let requested: Vec<String> = transport
+            .requests()
+            .iter()
+            .filter_map(|url| {
+                url.query_pairs()
+                    .find(|(key, _)| key == "month")
+                    .map(|(_, value)| value.into_owned())
+            })
+            .collect();
+        assert_eq!(requested, ["1", "2", "3"]);
+        assert_eq!(months.count().await, 9);
+    }
+
+    #[tokio::test]
+    async fn test_status_mismatch() {
+        let proxied = mock_api(StatusCode::OK, r#"{"status":503,"error":"Unavailable"}"#);
+        match proxied.countries().get().await {
+            Err(err @ HolidayAPIError::StatusMismatch(StatusCode::OK, 503)) => {
+                assert_eq!(err.status(), Some(StatusCode::OK));
+                assert_eq!(
+                    err.to_string(),
+                    "HTTP status 200 does not match the response status 503"
+                );
+            }
+            other => unreachable!("Should report the mismatch, got {:?}", other),
+        }
+        let raw = mock_api(StatusCode::OK, "name,date\nNew Year's Day,2021-01-01");
+        assert!(raw
+            .holidays("us", 2021)
+            .format("csv")
+            .get_raw()
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_error_bodies() {
+        let gateway = mock_api(StatusCode::BAD_GATEWAY, "Bad Gateway");
+        match gateway.countries().get().await {
+            Err(HolidayAPIError::RequestError(StatusCode::BAD_GATEWAY, body)) => {
+                assert_eq!(body, "Bad Gateway")
+            }
+            other => unreachable!("Should keep the body, got {:?}", other),
+        }
+        let truncated = mock_api(StatusCode::UNAUTHORIZED, r#"{"status":401,"err"#);
+        assert!(matches!(
+            truncated.countries().get().await,
+            Err(HolidayAPIError::InvalidOrExpiredKey(_, body)) if body == r#"{"status":401,"err"#
+        ));
+        let malformed = mock_api(StatusCode::OK, r#"{ "status": 200, "holidays": ["#);
+        assert!(matches!(
+            malformed.holidays("us", 2021).get().await,
+            Err(HolidayAPIError::InvalidResponse(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_html_pages() {
+        let maintenance = format!(
+            "<!DOCTYPE html>\n<p>{}</p>",
+            "Down for maintenance. ".repeat(20)
+        );
+        let err = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(MockTransport::new(MockResponse::ok(&maintenance)))
+            .countries()
+            .get()
+            .await
+            .unwrap_err();
+        assert!(!err.is_retryable());
+        let HolidayAPIError::UnexpectedContentType(content) = err else {
+            unreachable!("Should detect the page, got {:?}", err)
+        };
+        assert_eq!(content.content_type, None);
+        assert_eq!(
+            content.snippet.chars().count(),
+            UnexpectedContent::SNIPPET_LENGTH
+        );
+        assert!(content.snippet.starts_with("<!DOCTYPE html>"));
+
+        let transport = MockTransport::new(
+            MockResponse::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "<h1>Service Unavailable</h1>",
+            )
+            .header(CONTENT_TYPE, "text/html; charset=utf-8"),
+        );
+        let err = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport)
+            .countries()
+            .get()
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(err.is_retryable());
+        assert_eq!(
+            err.to_string(),
+            "Unexpected text/html; charset=utf-8 response with status 503: \
+             <h1>Service Unavailable</h1>"
+        );
+
+        let xml = mock_api(StatusCode::OK, "<holidays></holidays>");
+        assert!(xml
+            .holidays("us", 2021)
+            .format("xml")
+            .get_raw()
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_error_classes() {
+        let transport = MockTransport::new(MockResponse::transport_error("dns error"))
+            .on_call(2, MockResponse::timeout())
+            .on_call(
+                3,
+                crate::fixtures::Fixture::named("error_400")
+                    .unwrap()
+                    .mock_response(),
+            )
+            .on_call(4, MockResponse::rate_limited(None))
+            .on_call(5, MockResponse::ok("<!DOCTYPE html><p>Maintenance</p>"));
+        let api = HolidayAPI::new(EXPIRED_KEY).unwrap().transport(transport);
+        let mut errors = Vec::new();
+        for _ in 0..5 {
+            errors.push(api.countries().get().await.unwrap_err());
+        }
+        let classes: Vec<(bool, bool)> = errors
+            .iter()
+            .map(|err| (err.is_network(), err.is_api()))
+            .collect();
+        assert_eq!(
+            classes,
+            [
+                (true, false),
+                (true, false),
+                (false, true),
+                (false, true),
+                (false, false)
+            ]
+        );
+        let local = api.holidays("", 2021).get().await.unwrap_err();
+        assert!(!local.is_network() && !local.is_api());
+    }
+
+    #[tokio::test]
+    async fn test_holidays_template() {
+        let transport = MockTransport::new(MockResponse::ok(HOLIDAYS_BODY));
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone());
+        let template = api.holidays_template().language("fr").public();
+        assert!(matches!(
+            template.clone().get().await,
+            Err(HolidayAPIError::MissingParameter(_))
+        ));
+
+        template.apply("de", 2025).get().await.unwrap();
+        template.apply("at", 2024).pretty().get().await.unwrap();
+        let queries: Vec<HashMap<String, String>> = transport
+            .requests()
+            .iter()
+            .map(|url| url.query_pairs().into_owned().collect())
+            .collect();
+        assert_eq!(queries.len(), 2);
+        assert_eq!(
+            (
+                &*queries[0]["country"],
+                &*queries[0]["year"],
+                &*queries[0]["language"]
+            ),
+            ("DE", "2025", "fr")
+        );
+        assert_eq!(
+            (&*queries[1]["country"], &*queries[1]["public"]),
+            ("AT", "true")
+        );
+        assert!(!queries[0].contains_key("pretty") && queries[1].contains_key("pretty"));
+    }
+
+    #[tokio::test]
+    async fn test_raw_response() {
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(MockTransport::new(
+                MockResponse::ok(HOLIDAYS_BODY)
+                    .header(http::header::CONTENT_TYPE, "application/json"),
+            ));
+        let response = api.holidays("us", 2021).get_response().await.unwrap();
+        assert_eq!(response.status_code(), 200);
+        assert_eq!(response.header("Content-Type"), Some("application/json"));
+        assert_eq!(response.header("etag"), None);
+        assert_eq!(response.text(), HOLIDAYS_BODY);
+    }
+
+    #[tokio::test]
+    async fn test_quota_headers() {
+        let quota = |mut response: MockResponse| {
+            response
+                .header(HeaderName::from_static("x-ratelimit-limit"), "100")
+                .header(HeaderName::from_static("x-ratelimit-remaining"), "0")
+                .header(HeaderName::from_static("x-ratelimit-reset"), " 42 ")
+        };
+        let transport = MockTransport::new(quota(MockResponse::ok(HOLIDAYS_BODY)))
+            .on_call(1, quota(MockResponse::rate_limited(None)))
+            .on_call(2, MockResponse::rate_limited(None));
+        let api = HolidayAPI::new(EXPIRED_KEY).unwrap().transport(transport);
+        let expected = QuotaInfo {
+            limit: Some(100),
+            remaining: Some(0),
+            reset: Some(42),
+        };
+
+        match api.holidays("us", 2021).get().await {
+            Err(HolidayAPIError::RateLimited(None, _, Some(quota))) => assert_eq!(*quota, expected),
+            other => unreachable!("Should carry the quota, got {:?}", other),
+        }
+        assert!(matches!(
+            api.holidays("us", 2021).get().await,
+            Err(HolidayAPIError::RateLimited(None, _, None))
+        ));
+        let response = api.holidays("us", 2021).get_response().await.unwrap();
+        assert_eq!(response.quota(), Some(expected));
+        assert_eq!(QuotaInfo::from_headers(&HeaderMap::new()), None);
+    }
+
+    #[tokio::test]
+    async fn test_raw_json_fields() {
+        let api = mock_api(StatusCode::OK, HOLIDAYS_BODY);
+        let response = api.holidays("us", 2021).get_response().await.unwrap();
+        assert_eq!(
+            response.json().unwrap()["holidays"][0]["name"],
+            "New Year's Day"
+        );
+        assert_eq!(
+            response.field::<String>("/holidays/0/name").unwrap(),
+            Some("New Year's Day".to_string())
+        );
+        assert_eq!(response.field::<u32>("/requests/used").unwrap(), Some(1));
+        assert_eq!(response.field::<bool>("/holidays/1/public").unwrap(), None);
+        assert!(matches!(
+            response.field::<u32>("/holidays/0/name"),
+            Err(HolidayAPIError::InvalidResponse(_))
+        ));
+        let invalid = Response::new(StatusCode::OK, HeaderMap::new(), b"not json".to_vec());
+        for _ in 0..2 {
+            assert!(matches!(
+                invalid.field::<u32>("/requests/used"),
+                Err(HolidayAPIError::InvalidResponse(_))
+            ));
+        }
+
+        let json = api.holidays("us", 2021).get_json().await.unwrap();
+        assert_eq!(json["holidays"][0]["public"], true);
+        assert!(json["missing"][3]["field"].is_null());
+    }
+
+    #[test]
+    fn test_requests_share_the_client() {
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .default_country("de")
+            .default_language("de");
+        let request = api.holidays_default(2021);
+        assert!(Arc::ptr_eq(&api.key, &request.api().key));
+        assert!(Arc::ptr_eq(&api.base_urls, &request.api().base_urls));
+        assert_eq!(request.cache_key(), api.holidays("DE", 2021).cache_key());
     }
 
     #[tokio::test]
@@ -0,0 +1,100 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use futures_channel::oneshot;
+
+#[derive(Debug)]
+struct State {
+    available: usize,
+    /// Waiting requests, in order, each receiving a permit when one is released.
+    waiters: VecDeque<oneshot::Sender<Permit>>,
+}
+
+/// Semaphore bounding the number of requests in flight, working with any executor.
+#[derive(Clone)]
+pub(crate) struct ConcurrencyLimit {
+    max: usize,
+    state: Arc<Mutex<State>>,
+}
+
+impl fmt::Debug for ConcurrencyLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConcurrencyLimit")
+            .field("max", &self.max)
+            .finish()
+    }
+}
+
+/// Permission to send a request, released when dropped.
+#[derive(Debug)]
+pub(crate) struct Permit {
+    state: Option<Arc<Mutex<State>>>,
+}
+
+impl ConcurrencyLimit {
+    pub(crate) fn new(max: usize) -> Self {
+        let max = max.max(1);
+        Self {
+            max,
+            state: Arc::new(Mutex::new(State {
+                available: max,
+                waiters: VecDeque::new(),
+            })),
+        }
+    }
+
+    pub(crate) fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Waits until fewer than `max` permits are held.
+    pub(crate) async fn acquire(&self) -> Permit {
+        let receiver = {
+            let mut state = self.state.lock().expect("Lock is not poisoned");
+            if state.available > 0 {
+                state.available -= 1;
+                return Permit {
+                    state: Some(self.state.clone()),
+                };
+            }
+            let (sender, receiver) = oneshot::channel();
+            state.waiters.push_back(sender);
+            receiver
+        };
+        receiver
+            .await
+            .expect("Waiters are only dropped after sending a permit")
+    }
+}
+
+impl Drop for Permit {
+    /// Hands the permit over to the first waiter still waiting, or makes it available again.
+    fn drop(&mut self) {
+        let Some(shared) = self.state.take() else {
+            return;
+        };
+        loop {
+            let waiter = {
+                let mut state = shared.lock().expect("Lock is not poisoned");
+                match state.waiters.pop_front() {
+                    Some(waiter) => waiter,
+                    None => {
+                        state.available += 1;
+                        return;
+                    }
+                }
+            };
+            let permit = Permit {
+                state: Some(shared.clone()),
+            };
+            match waiter.send(permit) {
+                Ok(()) => return,
+                // The waiter gave up, forget the permit instead of releasing it again.
+                Err(mut permit) => permit.state = None,
+            }
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use http::StatusCode;
+
+use crate::{
+    country::CountryCode,
+    iso,
+    responses::{Country, Holiday},
+    HolidayAPI, HolidayAPIError,
+};
+
+/// Language names fall back to when none of the preferred locales is supported.
+pub const FALLBACK_LANGUAGE: &str = "en";
+
+/// Display name along with the language it is written in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalizedName {
+    pub name: String,
+    /// ISO 639-1 code of the language of the name.
+    pub language: String,
+}
+
+impl LocalizedName {
+    /// Whether the name is written in the first preferred locale's language.
+    pub fn is_preferred(&self, preferred: &[&str]) -> bool {
+        preferred
+            .first()
+            .is_some_and(|locale| primary_language(locale) == self.language)
+    }
+}
+
+/// Language part of a locale, lowercased: `"pt"` for `"pt-BR"` or `"pt_BR"`.
+fn primary_language(locale: &str) -> String {
+    locale
+        .trim()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+/// Picks the language of the first preferred locale which is a known ISO 639-1 language,
+/// falling back to [`FALLBACK_LANGUAGE`].
+///
+/// # Examples
+/// ```
+/// use holidayapi_rust::prelude::*;
+///
+/// assert_eq!(negotiate_language(&["xx", "fr-CA", "de"]), "fr");
+/// assert_eq!(negotiate_language(&[]), "en");
+/// ```
+pub fn negotiate_language(preferred: &[&str]) -> String {
+    preferred
+        .iter()
+        .map(|locale| primary_language(locale))
+        .find(|language| iso::is_language(language))
+        .unwrap_or_else(|| FALLBACK_LANGUAGE.to_string())
+}
+
+impl Country {
+    /// Display name of the country. The API only names countries in English, so the name is
+    /// always in [`FALLBACK_LANGUAGE`].
+    pub fn localized_name(&self) -> LocalizedName {
+        LocalizedName {
+            name: self.name.to_string(),
+            language: FALLBACK_LANGUAGE.to_string(),
+        }
+    }
+}
+
+impl HolidayAPI {
+    /// Fetches holidays named in the best language for the preferred locales, see
+    /// [`negotiate_language`].
+    ///
+    /// When the API rejects the negotiated language, the holidays are requested again in
+    /// [`FALLBACK_LANGUAGE`].
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let holidays = api.localized_holidays("ca", 2021, &["fr-CA", "en-CA"]).await;
+    ///     for (holiday, name) in holidays.unwrap() {
+    ///         println!("{} ({}): {}", holiday.date, name.language, name.name);
+    ///     }
+    /// }
+    /// ```
+    pub async fn localized_holidays(
+        &self,
+        country: impl Into<CountryCode>,
+        year: i32,
+        preferred: &[&str],
+    ) -> Result<Vec<(Holiday, LocalizedName)>, HolidayAPIError> {
+        let country = country.into();
+        let mut language = negotiate_language(preferred);
+        let holidays = match self
+            .holidays(&country, year)
+            .language(&language)
+            .get()
+            .await
+        {
+            Err(HolidayAPIError::RequestError(StatusCode::BAD_REQUEST, _))
+                if language != FALLBACK_LANGUAGE =>
+            {
+                language = FALLBACK_LANGUAGE.to_string();
+                self.holidays(&country, year)
+                    .language(&language)
+                    .get()
+                    .await?
+            }
+            result => result?,
+        };
+        Ok(holidays
+            .into_iter()
+            .map(|holiday| {
+                let name = LocalizedName {
+                    name: holiday.name.clone(),
+                    language: language.clone(),
+                };
+                (holiday, name)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::Fixture, mock::MockTransport, test_support::EXPIRED_KEY};
+
+    #[tokio::test]
+    async fn test_localized_holidays_fall_back_to_english() {
+        let transport = MockTransport::new(Fixture::named("holidays").unwrap().mock_response())
+            .on_call(1, Fixture::named("error_400").unwrap().mock_response());
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone());
+
+        let holidays = api
+            .localized_holidays("us", 2021, &["zz", "pt_BR"])
+            .await
+            .unwrap();
+        let (holiday, name) = &holidays[0];
+        assert_eq!(name.name, holiday.name);
+        assert_eq!(name.language, "en");
+        assert!(!name.is_preferred(&["pt-BR"]));
+        let languages: Vec<String> = transport
+            .requests()
+            .iter()
+            .filter_map(|url| {
+                url.query_pairs()
+                    .find(|(key, _)| key == "language")
+                    .map(|(_, value)| value.into_owned())
+            })
+            .collect();
+        assert_eq!(languages, ["pt", "en"]);
+
+        let holidays = api
+            .localized_holidays("us", 2021, &["de-AT"])
+            .await
+            .unwrap();
+        assert!(holidays[0].1.is_preferred(&["de-AT"]));
+    }
+}
@@ -0,0 +1,173 @@
+use std::{collections::BTreeMap, fs::File, ops::Range, path::Path};
+
+use memmap2::Mmap;
+use serde::Deserialize;
+use serde_json::value::RawValue;
+
+use crate::{
+    country::CountryCode, responses::HolidaysResponse, snapshot::Snapshot, HolidayAPI,
+    HolidayAPIError,
+};
+
+/// Entry of a snapshot file, with its response left undecoded.
+#[derive(Deserialize)]
+struct RawEntry<'a> {
+    country: String,
+    year: i32,
+    #[serde(borrow)]
+    response: &'a RawValue,
+}
+
+#[derive(Deserialize)]
+struct RawSnapshot<'a> {
+    version: u32,
+    #[serde(borrow)]
+    holidays: Vec<RawEntry<'a>>,
+}
+
+/// [`Snapshot`] read through a memory map, returned by [`HolidayAPI::map_snapshot`].
+///
+/// Only the countries and years of the entries are kept in memory. Responses stay in the file,
+/// paged in by the operating system, and are decoded when asked for.
+#[derive(Debug)]
+pub struct MappedSnapshot {
+    version: u32,
+    map: Mmap,
+    entries: BTreeMap<(String, i32), Range<usize>>,
+}
+
+impl MappedSnapshot {
+    /// Format version of the snapshot, see [`Snapshot::VERSION`].
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Number of country and year entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Years of a country in the snapshot, sorted.
+    pub fn years(&self, country: impl Into<CountryCode>) -> Vec<i32> {
+        let country = country.into();
+        self.entries
+            .keys()
+            .filter(|(code, _)| code == country.as_str())
+            .map(|(_, year)| *year)
+            .collect()
+    }
+
+    /// Decodes the holidays of a country and year, if they are part of the snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`HolidayAPIError::SnapshotError`] if the response cannot be decoded.
+    pub fn holidays(
+        &self,
+        country: impl Into<CountryCode>,
+        year: i32,
+    ) -> Result<Option<HolidaysResponse>, HolidayAPIError> {
+        let country = country.into();
+        let Some(range) = self.entries.get(&(country.as_str().to_string(), year)) else {
+            return Ok(None);
+        };
+        serde_json::from_slice(&self.map[range.clone()])
+            .map(Some)
+            .map_err(|err| HolidayAPIError::SnapshotError(err.to_string()))
+    }
+}
+
+impl HolidayAPI {
+    /// Maps a snapshot written by [`HolidayAPI::export_snapshot`] into memory, decoding the
+    /// responses of a country and year only when they are read, see [`MappedSnapshot`].
+    ///
+    /// The file is scanned once to index its entries. It must not be modified while mapped.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`HolidayAPIError::SnapshotError`] if the file cannot be read, is not a
+    /// snapshot, or is of a newer version.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let snapshot = HolidayAPI::map_snapshot("holidays.json").unwrap();
+    /// if let Some(us) = snapshot.holidays("us", 2021).unwrap() {
+    ///     println!("{} holidays", us.holidays.len());
+    /// }
+    /// ```
+    pub fn map_snapshot(path: impl AsRef<Path>) -> Result<MappedSnapshot, HolidayAPIError> {
+        let error = |err: &dyn std::fmt::Display| HolidayAPIError::SnapshotError(err.to_string());
+        let file = File::open(path).map_err(|err| error(&err))?;
+        // SAFETY: the map is only read, and the file is documented as not to be modified while
+        // mapped.
+        let map = unsafe { Mmap::map(&file) }.map_err(|err| error(&err))?;
+
+        let raw: RawSnapshot = serde_json::from_slice(&map).map_err(|err| error(&err))?;
+        if raw.version > Snapshot::VERSION {
+            return Err(HolidayAPIError::SnapshotError(format!(
+                "unsupported version {}, expected at most {}",
+                raw.version,
+                Snapshot::VERSION
+            )));
+        }
+        let base = map.as_ptr() as usize;
+        let entries = raw
+            .holidays
+            .into_iter()
+            .map(|entry| {
+                let response = entry.response.get();
+                let start = response.as_ptr() as usize - base;
+                ((entry.country, entry.year), start..start + response.len())
+            })
+            .collect();
+        Ok(MappedSnapshot {
+            version: raw.version,
+            map,
+            entries,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::Fixture, mock::MockTransport, test_support::EXPIRED_KEY};
+
+    #[tokio::test]
+    async fn test_map_snapshot() {
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(MockTransport::new(
+                Fixture::named("holidays").unwrap().mock_response(),
+            ));
+        let path = std::env::temp_dir().join(format!("mapped-{}.json", std::process::id()));
+        api.export_snapshot(&path, &["jp", "us"], [2021, 2022])
+            .await
+            .unwrap();
+
+        let snapshot = HolidayAPI::map_snapshot(&path).unwrap();
+        assert_eq!(snapshot.version(), Snapshot::VERSION);
+        assert_eq!(snapshot.len(), 4);
+        assert_eq!(snapshot.years("US"), [2021, 2022]);
+        let us = snapshot.holidays("us", 2022).unwrap().unwrap();
+        assert_eq!(us.holidays.len(), 3);
+        assert!(snapshot.holidays("de", 2021).unwrap().is_none());
+        drop(snapshot);
+
+        std::fs::write(&path, r#"{ "version": 99, "holidays": [] }"#).unwrap();
+        assert!(matches!(
+            HolidayAPI::map_snapshot(&path),
+            Err(HolidayAPIError::SnapshotError(_))
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
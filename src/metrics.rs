@@ -0,0 +1,199 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{Arc, Mutex},
+};
+
+use crate::{requests::Endpoint, HolidayAPI};
+
+#[derive(Debug, Default)]
+struct Counts {
+    requests: BTreeMap<String, u64>,
+    errors: BTreeMap<String, u64>,
+    cache_hits: u64,
+    cache_misses: u64,
+    quota_remaining: Option<u32>,
+}
+
+/// Counters of a client and its clones, rendered by [`HolidayAPI::render_metrics`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Metrics(Arc<Mutex<Counts>>);
+
+impl Metrics {
+    fn counts(&self) -> std::sync::MutexGuard<'_, Counts> {
+        self.0.lock().expect("Lock is not poisoned")
+    }
+
+    /// Counts a request sent to the transport.
+    pub(crate) fn request(&self, endpoint: &Endpoint) {
+        *self
+            .counts()
+            .requests
+            .entry(endpoint.to_string())
+            .or_default() += 1;
+    }
+
+    /// Counts a request which failed, after its retries.
+    pub(crate) fn error(&self, endpoint: &Endpoint) {
+        *self
+            .counts()
+            .errors
+            .entry(endpoint.to_string())
+            .or_default() += 1;
+    }
+
+    /// Counts a lookup of the cache.
+    pub(crate) fn cache_lookup(&self, hit: bool) {
+        let mut counts = self.counts();
+        if hit {
+            counts.cache_hits += 1;
+        } else {
+            counts.cache_misses += 1;
+        }
+    }
+
+    pub(crate) fn quota_remaining(&self, available: u32) {
+        self.counts().quota_remaining = Some(available);
+    }
+}
+
+/// Writes the `# HELP` and `# TYPE` lines of a metric.
+fn header(text: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(text, "# HELP {} {}", name, help);
+    let _ = writeln!(text, "# TYPE {} {}", name, kind);
+}
+
+/// Escapes a label value of the Prometheus text format.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+impl HolidayAPI {
+    /// Renders the counters of the client in the Prometheus text exposition format, to be
+    /// served on a metrics endpoint.
+    ///
+    /// The counters are shared with the clones of the client:
+    /// - `holidayapi_requests_total`: requests sent, retries and failovers included, by
+    ///   endpoint.
+    /// - `holidayapi_errors_total`: requests which failed after their retries, by endpoint.
+    /// - `holidayapi_cache_hits_total` and `holidayapi_cache_misses_total`: lookups of the
+    ///   cache enabled with [`HolidayAPI::with_cache`].
+    /// - `holidayapi_quota_remaining`: requests available according to the last successful
+    ///   response, once known.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    /// assert!(api
+    ///     .render_metrics()
+    ///     .contains("# TYPE holidayapi_requests_total counter"));
+    /// ```
+    pub fn render_metrics(&self) -> String {
+        let counts = self.metrics.counts();
+        let mut text = String::new();
+        let labelled = [
+            (
+                "holidayapi_requests_total",
+                "Requests sent to HolidayAPI.",
+                &counts.requests,
+            ),
+            (
+                "holidayapi_errors_total",
+                "Requests to HolidayAPI which failed after their retries.",
+                &counts.errors,
+            ),
+        ];
+        for (name, help, values) in labelled {
+            header(&mut text, name, "counter", help);
+            for (endpoint, value) in values {
+                let _ = writeln!(
+                    text,
+                    "{}{{endpoint=\"{}\"}} {}",
+                    name,
+                    escape(endpoint),
+                    value
+                );
+            }
+        }
+        header(
+            &mut text,
+            "holidayapi_cache_hits_total",
+            "counter",
+            "Responses served from the cache.",
+        );
+        let _ = writeln!(text, "holidayapi_cache_hits_total {}", counts.cache_hits);
+        header(
+            &mut text,
+            "holidayapi_cache_misses_total",
+            "counter",
+            "Responses looked up in the cache and fetched from HolidayAPI.",
+        );
+        let _ = writeln!(
+            text,
+            "holidayapi_cache_misses_total {}",
+            counts.cache_misses
+        );
+        if let Some(remaining) = counts.quota_remaining {
+            header(
+                &mut text,
+                "holidayapi_quota_remaining",
+                "gauge",
+                "Requests available in the quota of the key.",
+            );
+            let _ = writeln!(text, "holidayapi_quota_remaining {}", remaining);
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        fixtures::Fixture,
+        mock::{MockResponse, MockTransport},
+        test_support::EXPIRED_KEY,
+    };
+
+    #[tokio::test]
+    async fn test_render_metrics() {
+        let transport = MockTransport::new(Fixture::named("holidays").unwrap().mock_response())
+            .on_call(2, Fixture::named("error_400").unwrap().mock_response())
+            .on_call(3, MockResponse::transport_error("reset"));
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport)
+            .with_cache(Duration::from_secs(60));
+        assert!(!api.render_metrics().contains("holidayapi_quota_remaining"));
+
+        api.holidays("us", 2021).get().await.unwrap();
+        api.clone().holidays("us", 2021).get().await.unwrap();
+        assert!(api.holidays("us", 2020).get().await.is_err());
+        assert!(api.countries().get().await.is_err());
+
+        let metrics = api.render_metrics();
+        for line in [
+            "# TYPE holidayapi_requests_total counter",
+            "holidayapi_requests_total{endpoint=\"holidays\"} 2",
+            "holidayapi_requests_total{endpoint=\"countries\"} 1",
+            "holidayapi_errors_total{endpoint=\"holidays\"} 1",
+            "holidayapi_errors_total{endpoint=\"countries\"} 1",
+            "holidayapi_cache_hits_total 1",
+            "holidayapi_cache_misses_total 3",
+            "# TYPE holidayapi_quota_remaining gauge",
+        ] {
+            assert!(metrics.contains(line), "{} missing from\n{}", line, metrics);
+        }
+        assert_eq!(escape("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
@@ -0,0 +1,284 @@
+use std::{
+    collections::HashMap,
+    io,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use futures_channel::oneshot;
+use http::{
+    header::{HeaderName, HeaderValue, RETRY_AFTER},
+    HeaderMap, StatusCode,
+};
+use url::Url;
+
+use crate::transport::{BoxFuture, Response, Transport, TransportError, TransportRequest};
+
+/// Scripted answer of a [`MockTransport`].
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    outcome: Result<Response, (io::ErrorKind, String)>,
+    latency: Duration,
+}
+
+impl MockResponse {
+    pub fn new(status: StatusCode, body: &str) -> Self {
+        Self {
+            outcome: Ok(Response::new(status, HeaderMap::new(), body.into())),
+            latency: Duration::ZERO,
+        }
+    }
+
+    /// `200 OK` with the given body.
+    pub fn ok(body: &str) -> Self {
+        Self::new(StatusCode::OK, body)
+    }
+
+    /// `429 Too Many Requests`, with a `Retry-After` header if `retry_after` is set.
+    pub fn rate_limited(retry_after: Option<Duration>) -> Self {
+        let mut response = Self::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            r#"{ "status": 429, "error": "Rate limit exceeded." }"#,
+        );
+        match retry_after {
+            Some(retry_after) => response.header(RETRY_AFTER, &retry_after.as_secs().to_string()),
+            None => response,
+        }
+    }
+
+    /// `200 OK` with a body which is not valid JSON.
+    pub fn malformed_json() -> Self {
+        Self::ok(r#"{ "status": 200, "holidays": ["#)
+    }
+
+    /// Fails without a response, as a reset connection would. The error is not a connection
+    /// failure, so the request is not sent to the next [base url](crate::HolidayAPI::base_urls).
+    pub fn transport_error(message: &str) -> Self {
+        Self {
+            outcome: Err((io::ErrorKind::Other, message.to_string())),
+            latency: Duration::ZERO,
+        }
+    }
+
+    /// Fails to connect, so the request is sent to the next
+    /// [base url](crate::HolidayAPI::base_urls) if any.
+    pub fn connection_refused() -> Self {
+        Self {
+            outcome: Err((
+                io::ErrorKind::ConnectionRefused,
+                "connection refused".to_string(),
+            )),
+            latency: Duration::ZERO,
+        }
+    }
+
+    /// Fails without a response because the request timed out, see
+    /// [`HolidayAPIError::Timeout`](crate::HolidayAPIError::Timeout).
+    pub fn timeout() -> Self {
+        Self {
+            outcome: Err((io::ErrorKind::TimedOut, "operation timed out".to_string())),
+            latency: Duration::ZERO,
+        }
+    }
+
+    /// Adds a header to the response.
+    pub fn header(&mut self, name: HeaderName, value: &str) -> Self {
+        if let Ok(response) = &mut self.outcome {
+            let mut headers = response.headers().clone();
+            if let Ok(value) = HeaderValue::from_str(value) {
+                headers.insert(name, value);
+            }
+            *response = Response::new(response.status(), headers, response.bytes().to_vec());
+        }
+        self.to_owned()
+    }
+
+    /// Delays the answer.
+    pub fn latency(&mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self.to_owned()
+    }
+}
+
+/// [`Transport`] answering with scripted responses, for tests running without network access.
+///
+/// Every request gets the default response, unless a response was scripted for its call number
+/// with [`on_call`](Self::on_call). Clones share their call counter and recorded requests, so
+/// a clone can be kept to inspect the traffic after handing the transport to a client.
+///
+/// Latency is simulated on a helper thread, so it works with any executor.
+///
+/// # Examples
+/// ```
+/// use holidayapi_rust::prelude::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let body = r#"{ "status": 200, "requests": { "used": 1, "available": 9, "resets": "" },
+///                     "holidays": [] }"#;
+///     let transport = MockTransport::new(MockResponse::ok(body))
+///         .on_call(1, MockResponse::rate_limited(None));
+///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+///         .unwrap()
+///         .transport(transport.clone());
+///
+///     assert!(api.holidays("us", 2021).get().await.is_err());
+///     assert!(api.holidays("us", 2021).get().await.is_ok());
+///     assert_eq!(transport.calls(), 2);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockTransport {
+    default: MockResponse,
+    scripted: HashMap<usize, MockResponse>,
+    calls: Arc<AtomicUsize>,
+    requests: Arc<Mutex<Vec<Url>>>,
+}
+
+impl MockTransport {
+    pub fn new(default: MockResponse) -> Self {
+        Self {
+            default,
+            scripted: HashMap::new(),
+            calls: Arc::new(AtomicUsize::new(0)),
+            requests: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Answers the `call`-th request, starting at 1, with the given response.
+    pub fn on_call(&mut self, call: usize, response: MockResponse) -> Self {
+        self.scripted.insert(call, response);
+        self.to_owned()
+    }
+
+    /// Number of requests received so far.
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    /// Urls of the requests received so far, in order.
+    pub fn requests(&self) -> Vec<Url> {
+        self.requests.lock().expect("Lock is not poisoned").clone()
+    }
+}
+
+impl Transport for MockTransport {
+    fn get(&self, request: TransportRequest) -> BoxFuture<'_, Result<Response, TransportError>> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        self.requests
+            .lock()
+            .expect("Lock is not poisoned")
+            .push(request.url);
+        let response = self.scripted.get(&call).unwrap_or(&self.default).clone();
+        Box::pin(async move {
+            if !response.latency.is_zero() {
+                let (sender, receiver) = oneshot::channel();
+                std::thread::spawn(move || {
+                    std::thread::sleep(response.latency);
+                    let _ = sender.send(());
+                });
+                let _ = receiver.await;
+            }
+            response
+                .outcome
+                .map_err(|(kind, message)| TransportError::from(io::Error::new(kind, message)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_support::EXPIRED_KEY, HolidayAPI, HolidayAPIError};
+    use std::time::Instant;
+
+    static BODY: &str = r#"{
+        "status": 200,
+        "requests": { "used": 1, "available": 9999, "resets": "2021-01-01 00:00:00" },
+        "holidays": []
+    }"#;
+
+    fn api(transport: &MockTransport) -> HolidayAPI {
+        HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+    }
+
+    #[tokio::test]
+    async fn test_scripted_faults() {
+        let transport = MockTransport::new(MockResponse::ok(BODY))
+            .on_call(2, MockResponse::transport_error("connection reset"))
+            .on_call(3, MockResponse::rate_limited(Some(Duration::from_secs(2))));
+        let api = api(&transport);
+        assert!(api.holidays("us", 2021).get().await.is_ok());
+        match api.holidays("us", 2021).get().await {
+            Err(HolidayAPIError::TransportError(err)) => {
+                assert_eq!(err.to_string(), "connection reset")
+            }
+            other => unreachable!("Should fail to connect, got {:?}", other),
+        }
+        match api.holidays("us", 2021).get().await {
+            Err(HolidayAPIError::RateLimited(retry_after, _, _)) => {
+                assert_eq!(retry_after, Some(Duration::from_secs(2)))
+            }
+            other => unreachable!("Should be rate limited, got {:?}", other),
+        }
+        assert!(api.holidays("jp", 2021).get().await.is_ok());
+        assert_eq!(transport.calls(), 4);
+        assert!(transport.requests()[3]
+            .query_pairs()
+            .any(|(key, value)| key == "country" && value == "JP"));
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let transport = MockTransport::new(MockResponse::ok(BODY))
+            .on_call(1, MockResponse::timeout())
+            .on_call(2, MockResponse::transport_error("connection reset"));
+        let api = api(&transport);
+        match api.holidays("us", 2021).get().await {
+            Err(err @ HolidayAPIError::Timeout { .. }) => {
+                assert!(err.is_retryable());
+                assert!(err
+                    .to_string()
+                    .starts_with("Request to holidays timed out after"));
+            }
+            other => unreachable!("Should time out, got {:?}", other),
+        }
+        assert!(matches!(
+            api.holidays("us", 2021).get().await,
+            Err(HolidayAPIError::TransportError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_latency() {
+        let transport =
+            MockTransport::new(MockResponse::ok(BODY).latency(Duration::from_millis(50)));
+        let start = Instant::now();
+        assert!(api(&transport).holidays("us", 2021).get().await.is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_json() {
+        let transport = MockTransport::new(MockResponse::malformed_json());
+        assert!(matches!(
+            api(&transport).holidays("us", 2021).get().await,
+            Err(HolidayAPIError::InvalidResponse(_))
+        ));
+
+        let response = transport
+            .get(TransportRequest {
+                url: Url::parse("https://holidayapi.com/v1/holidays").unwrap(),
+                headers: HeaderMap::new(),
+                max_body_size: None,
+            })
+            .await
+            .unwrap();
+        assert!(serde_json::from_slice::<serde_json::Value>(response.bytes()).is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+    sync::Arc,
+};
+
+use crate::HolidayAPI;
+
+type Processor = Arc<dyn Fn(&mut dyn Any) + Send + Sync>;
+
+/// Post-processors registered with [`HolidayAPI::post_process`], by response type.
+#[derive(Clone, Default)]
+pub(crate) struct PostProcessors(Arc<HashMap<TypeId, Vec<Processor>>>);
+
+impl fmt::Debug for PostProcessors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PostProcessors")
+            .field(&self.0.values().map(Vec::len).sum::<usize>())
+            .finish()
+    }
+}
+
+impl PostProcessors {
+    /// Runs the post-processors of `T` on a parsed response, in their registration order.
+    pub(crate) fn apply<T: 'static>(&self, response: &mut T) {
+        for processor in self.0.get(&TypeId::of::<T>()).into_iter().flatten() {
+            processor(response);
+        }
+    }
+}
+
+impl HolidayAPI {
+    /// Registers a closure modifying every parsed response of type `T`, such as
+    /// [`HolidaysResponse`](crate::responses::HolidaysResponse), fetched or cached, before it is
+    /// returned.
+    ///
+    /// Post-processors run in their registration order, on the responses of
+    /// [`Request::get_full`](crate::requests::Request::get_full) and the methods built on it.
+    /// Raw responses are left as sent by the API.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .post_process(|response: &mut HolidaysResponse| {
+    ///         response.holidays.retain(|holiday| holiday.public)
+    ///     })
+    ///     .post_process(|response: &mut HolidaysResponse| {
+    ///         for holiday in &mut response.holidays {
+    ///             holiday.name = format!("{} (office closed)", holiday.name);
+    ///         }
+    ///     });
+    /// ```
+    pub fn post_process<T: 'static>(
+        &mut self,
+        processor: impl Fn(&mut T) + Send + Sync + 'static,
+    ) -> Self {
+        let processor: Processor = Arc::new(move |response: &mut dyn Any| {
+            if let Some(response) = response.downcast_mut::<T>() {
+                processor(response);
+            }
+        });
+        Arc::make_mut(&mut self.post_processors.0)
+            .entry(TypeId::of::<T>())
+            .or_default()
+            .push(processor);
+        self.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        fixtures::Fixture,
+        mock::MockTransport,
+        responses::{CountriesResponse, HolidaysResponse},
+        test_support::EXPIRED_KEY,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_post_process() {
+        let transport = MockTransport::new(Fixture::named("holidays").unwrap().mock_response());
+        let plain = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+            .with_cache(Duration::from_secs(60));
+        let api = plain
+            .clone()
+            .post_process(|response: &mut HolidaysResponse| {
+                response.holidays.retain(|holiday| holiday.public)
+            })
+            .post_process(|response: &mut HolidaysResponse| {
+                for holiday in &mut response.holidays {
+                    holiday.name.insert_str(0, "ACME: ");
+                }
+            })
+            .post_process(|_: &mut CountriesResponse| unreachable!("Not a countries response"));
+
+        let all = plain.holidays("us", 2021).get().await.unwrap();
+        let public = api.holidays("us", 2021).get().await.unwrap();
+        assert_eq!(transport.calls(), 1);
+        assert!(public.len() < all.len());
+        assert!(public
+            .iter()
+            .all(|holiday| holiday.public && holiday.name.starts_with("ACME: ")));
+        let raw = api.holidays("us", 2021).get_json().await.unwrap();
+        assert_eq!(raw["holidays"].as_array().unwrap().len(), all.len());
+    }
+}
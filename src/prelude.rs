@@ -1,3 +1,39 @@
+pub use crate::batch::*;
+pub use crate::budget::*;
+pub use crate::calendar::*;
+pub use crate::clock::*;
+#[cfg(feature = "compact-strings")]
+pub use crate::compact::*;
+pub use crate::country::*;
+pub use crate::deferred::*;
+pub use crate::diff::*;
+pub use crate::dns::*;
+#[cfg(feature = "fake")]
+pub use crate::fake::*;
+#[cfg(feature = "feed")]
+pub use crate::feed::*;
+#[cfg(feature = "mock")]
+pub use crate::fixtures::*;
+pub use crate::health::*;
+pub use crate::locale::*;
+#[cfg(feature = "mmap")]
+pub use crate::mapped::*;
+#[cfg(feature = "mock")]
+pub use crate::mock::*;
+pub use crate::refresh::*;
+pub use crate::reminder::*;
 pub use crate::requests::*;
 pub use crate::responses::*;
+pub use crate::retry::*;
+pub use crate::runtime::*;
+pub use crate::scheduler::*;
+pub use crate::snapshot::*;
+#[cfg(feature = "sqlx")]
+pub use crate::sql::*;
+pub use crate::stats::*;
+pub use crate::tenant::*;
+pub use crate::transport::*;
+pub use crate::usage::*;
+pub use crate::version::*;
+pub use crate::watch::*;
 pub use crate::*;
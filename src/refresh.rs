@@ -0,0 +1,210 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use serde_json::Value;
+
+#[cfg(feature = "tokio")]
+use crate::runtime::TokioRuntime;
+use crate::{
+    cache::Cache,
+    requests::Request,
+    runtime::Runtime,
+    shutdown::{self, TaskGuard, TaskHandle},
+    HolidayAPI, HolidayAPIError,
+};
+
+/// Handle of the task started by [`HolidayAPI::spawn_cache_refresher`].
+///
+/// The task is aborted when the handle is dropped.
+#[derive(Debug)]
+#[must_use = "the refresher is aborted when its handle is dropped"]
+pub struct CacheRefresher {
+    task: TaskHandle,
+    refreshed: Arc<AtomicU64>,
+}
+
+impl CacheRefresher {
+    /// Stops the task right away, dropping the revalidation in flight, if any.
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+
+    /// Stops the task once the revalidation in flight, if any, is done, and waits for it to
+    /// end.
+    pub async fn shutdown(&self) {
+        self.task.shutdown().await;
+    }
+
+    /// Number of entries revalidated so far.
+    pub fn refreshed(&self) -> u64 {
+        self.refreshed.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for CacheRefresher {
+    fn drop(&mut self) {
+        self.abort();
+    }
+}
+
+/// Revalidates the entries expiring before the next round, one at a time. Returns how long to
+/// wait before the next round: `interval`, or longer when the API rate limits the client or its
+/// budget is spent, as the refresh would be failing until then.
+async fn refresh_round(
+    api: &HolidayAPI,
+    cache: &Cache,
+    interval: Duration,
+    guard: &TaskGuard,
+    refreshed: &AtomicU64,
+) -> Duration {
+    for (key, parameters) in cache.expiring(interval) {
+        if guard.is_stopping() {
+            break;
+        }
+        let request = Request::<Value>::for_cache_key(api, &key, parameters);
+        match guard.until_aborted(request.revalidate()).await {
+            None => break,
+            Some(Ok(_)) => {
+                refreshed.fetch_add(1, Ordering::Relaxed);
+            }
+            Some(Err(err)) if err.is_quota() => {
+                log::warn!("Pausing the cache refresh: {}", err);
+                return err.retry_after().unwrap_or(interval).max(interval);
+            }
+            Some(Err(err)) => log::warn!(
+                "Failed to refresh {} from the cache: {}",
+                key.endpoint(),
+                err
+            ),
+        }
+    }
+    interval
+}
+
+impl HolidayAPI {
+    /// Revalidates the cached responses about to expire every `interval` on the current tokio
+    /// runtime, so that requests keep being served from the cache, see [`CacheRefresher`].
+    ///
+    /// Entries expiring before the next round are revalidated one at a time, with the
+    /// conditional requests of [`Request::revalidate`]. The requests go through the budget and
+    /// retry policy of the client. When the API rate limits the client or its budget is spent,
+    /// the refresh pauses until the `Retry-After` delay or the reset of the budget, and the
+    /// entries expiring meanwhile are fetched again by the next request asking for them.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`HolidayAPIError::CacheDisabled`] if no cache is enabled.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///         .unwrap()
+    ///         .with_default_cache();
+    ///     let _refresher = api
+    ///         .spawn_cache_refresher(Duration::from_secs(60))
+    ///         .unwrap();
+    ///     let holidays = api.holidays("us", 2021).get().await;
+    /// }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn spawn_cache_refresher(
+        &self,
+        interval: Duration,
+    ) -> Result<CacheRefresher, HolidayAPIError> {
+        self.spawn_cache_refresher_with_runtime(interval, Arc::new(TokioRuntime))
+    }
+
+    /// Revalidates the cached responses about to expire every `interval`, on the given
+    /// runtime, see [`HolidayAPI::spawn_cache_refresher`].
+    pub fn spawn_cache_refresher_with_runtime(
+        &self,
+        interval: Duration,
+        runtime: Arc<dyn Runtime>,
+    ) -> Result<CacheRefresher, HolidayAPIError> {
+        let cache = self.cache.clone().ok_or(HolidayAPIError::CacheDisabled)?;
+        let api = self.clone();
+        let (task, guard) = shutdown::task();
+        let refreshed = Arc::new(AtomicU64::new(0));
+        let counter = refreshed.clone();
+        runtime.clone().spawn(Box::pin(async move {
+            let mut pause = interval;
+            while guard.until_stopping(runtime.sleep(pause)).await.is_some() {
+                pause = refresh_round(&api, &cache, interval, &guard, &counter).await;
+            }
+        }));
+        Ok(CacheRefresher { task, refreshed })
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{
+        fixtures::Fixture,
+        mock::{MockResponse, MockTransport},
+        test_support::EXPIRED_KEY,
+    };
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cache_refresher() {
+        let not_modified = MockResponse::new(StatusCode::NOT_MODIFIED, "");
+        let transport = MockTransport::new(not_modified)
+            .on_call(1, Fixture::named("holidays").unwrap().mock_response())
+            .on_call(
+                3,
+                MockResponse::new(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    r#"{ "status": 429, "error": "Rate limited." }"#,
+                )
+                .header(http::header::RETRY_AFTER, "600"),
+            );
+        // Time is paused and only advances when every task waits, while entries expire on the
+        // wall clock: with a TTL shorter than the interval, every round finds the entry
+        // expiring.
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+            .with_cache(Duration::from_secs(60));
+        let interval = Duration::from_secs(100);
+        assert!(matches!(
+            HolidayAPI::new(EXPIRED_KEY)
+                .unwrap()
+                .spawn_cache_refresher(interval),
+            Err(HolidayAPIError::CacheDisabled)
+        ));
+
+        api.holidays("us", 2021).get().await.unwrap();
+        let refresher = api.spawn_cache_refresher(interval).unwrap();
+        tokio::time::sleep(Duration::from_secs(150)).await;
+        assert_eq!(refresher.refreshed(), 1);
+        assert!(api.holidays("us", 2021).is_cached());
+
+        // The second round is rate limited, and the refresh pauses for the `Retry-After` delay.
+        tokio::time::sleep(Duration::from_secs(100)).await;
+        assert_eq!(transport.calls(), 3);
+        tokio::time::sleep(Duration::from_secs(500)).await;
+        assert_eq!(transport.calls(), 3);
+        assert_eq!(refresher.refreshed(), 1);
+        tokio::time::sleep(Duration::from_secs(100)).await;
+        assert_eq!(transport.calls(), 4);
+        assert_eq!(refresher.refreshed(), 2);
+        tokio::time::timeout(Duration::from_secs(1), refresher.shutdown())
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    clock::{Clock, SystemClock},
+    responses::{Holiday, HolidaysResponse},
+};
+
+/// Alert to trigger ahead of a holiday.
+#[derive(Debug, Clone)]
+pub struct Reminder {
+    pub holiday: Holiday,
+    /// When the alert should fire.
+    pub trigger_at: DateTime<Utc>,
+    /// Time left until `trigger_at`, zero if it already passed while the holiday did not start
+    /// yet.
+    pub until: Duration,
+}
+
+impl Holiday {
+    /// Start of the holiday, midnight UTC of its `date`.
+    pub fn starts_at(&self) -> Option<DateTime<Utc>> {
+        Some(self.parse_date()?.and_hms_opt(0, 0, 0)?.and_utc())
+    }
+
+    /// Time left until the holiday starts, `None` if it already started or its date cannot be
+    /// parsed.
+    pub fn time_until(&self, now: DateTime<Utc>) -> Option<Duration> {
+        (self.starts_at()? - now)
+            .to_std()
+            .ok()
+            .filter(|duration| !duration.is_zero())
+    }
+}
+
+/// Computes reminders firing `days` days before each holiday that did not start yet, sorted by
+/// trigger time.
+pub fn reminders_before<'a>(
+    holidays: impl IntoIterator<Item = &'a Holiday>,
+    days: u32,
+    now: DateTime<Utc>,
+) -> Vec<Reminder> {
+    let lead = chrono::Duration::days(i64::from(days));
+    let mut reminders: Vec<Reminder> = holidays
+        .into_iter()
+        .filter(|holiday| holiday.time_until(now).is_some())
+        .filter_map(|holiday| {
+            let trigger_at = holiday.starts_at()? - lead;
+            Some(Reminder {
+                holiday: holiday.clone(),
+                trigger_at,
+                until: (trigger_at - now).to_std().unwrap_or_default(),
+            })
+        })
+        .collect();
+    reminders.sort_by_key(|reminder| reminder.trigger_at);
+    reminders
+}
+
+impl HolidaysResponse {
+    /// Computes reminders firing `days` days before each upcoming holiday of this response,
+    /// according to the system clock, see [`reminders_before`].
+    pub fn reminders_before(&self, days: u32) -> Vec<Reminder> {
+        reminders_before(&self.holidays, days, SystemClock.now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::holiday;
+
+    #[test]
+    fn test_reminders_before() {
+        let now = holiday("2021-06-28").starts_at().unwrap() + chrono::Duration::hours(12);
+        let holidays = [
+            holiday("2021-12-25"),
+            holiday("2021-06-01"),
+            holiday("2021-07-06"),
+            holiday("2021-06-30"),
+        ];
+        let reminders = reminders_before(&holidays, 7, now);
+        assert_eq!(reminders.len(), 3);
+        assert_eq!(reminders[0].holiday.date, "2021-06-30");
+        assert_eq!(reminders[0].until, Duration::ZERO);
+        assert_eq!(reminders[1].until, Duration::from_secs(12 * 3600));
+        assert_eq!(reminders[2].holiday.date, "2021-12-25");
+        assert_eq!(
+            holidays[3].time_until(now),
+            Some(Duration::from_secs(36 * 3600))
+        );
+    }
+}
@@ -1,25 +1,198 @@
+use chrono::NaiveDate;
+use http::StatusCode;
 use serde::de::DeserializeOwned;
+use serde_json::Value;
 
 use crate::{
+    cache::{CacheKey, Revalidation, Validators},
+    country::CountryCode,
+    date, integrity, iso,
     responses::{
         CountriesResponse, Country, Date, Holiday, HolidaysResponse, Language, LanguagesResponse,
         WorkdayResponse, WorkdaysResponse,
     },
-    HolidayAPI, HolidayAPIError,
+    transport::Response,
+    version::Version,
+    HolidayAPI, HolidayAPIError, Validation,
 };
-use std::{collections::HashMap, marker::PhantomData};
+use std::{collections::HashMap, fmt, marker::PhantomData};
+
+/// HolidayAPI endpoints, those supported by [`Request`] and any other one for
+/// [`HolidayAPI::custom_request`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    Countries,
+    Holidays,
+    Languages,
+    Workday,
+    Workdays,
+    /// Endpoint without a request builder, named by its path relative to the base url.
+    ///
+    /// Requests fail with [`HolidayAPIError::InvalidEndpoint`] if the path starts with a `/` or
+    /// a scheme, or has `..` segments, which would send the key to another path or host.
+    Other(String),
+}
+
+impl Endpoint {
+    /// Parameters the endpoint cannot be queried without.
+    pub fn required_parameters(&self) -> &'static [&'static str] {
+        match self {
+            Endpoint::Countries | Endpoint::Languages | Endpoint::Other(_) => &[],
+            Endpoint::Holidays => &["country", "year"],
+            Endpoint::Workday => &["country", "start", "days"],
+            Endpoint::Workdays => &["country", "start", "end"],
+        }
+    }
+
+    /// Path of the endpoint, relative to the base url.
+    pub fn path(&self) -> &str {
+        match self {
+            Endpoint::Countries => "countries",
+            Endpoint::Holidays => "holidays",
+            Endpoint::Languages => "languages",
+            Endpoint::Workday => "workday",
+            Endpoint::Workdays => "workdays",
+            Endpoint::Other(path) => path,
+        }
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.path())
+    }
+}
+
+impl From<&str> for Endpoint {
+    /// The endpoint with this path, case insensitive, or [`Endpoint::Other`].
+    fn from(path: &str) -> Self {
+        match path.to_ascii_lowercase().as_str() {
+            "countries" => Endpoint::Countries,
+            "holidays" => Endpoint::Holidays,
+            "languages" => Endpoint::Languages,
+            "workday" => Endpoint::Workday,
+            "workdays" => Endpoint::Workdays,
+            _ => Endpoint::Other(path.to_string()),
+        }
+    }
+}
+
+/// Endpoint defined outside of this library, requested with [`HolidayAPI::request`] to get
+/// typed responses from endpoints without a request builder.
+///
+/// # Examples
+/// ```
+/// use holidayapi_rust::prelude::*;
+///
+/// struct Upcoming;
+///
+/// impl EndpointSpec for Upcoming {
+///     type Response = HolidaysResponse;
+///     const PATH: &'static str = "holidays/upcoming";
+///     const REQUIRED_PARAMETERS: &'static [&'static str] = &["country"];
+/// }
+///
+/// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+/// let request = api.request::<Upcoming>().parameter("country", "us");
+/// ```
+pub trait EndpointSpec {
+    /// Type the JSON response is parsed into.
+    type Response: Clone + DeserializeOwned;
+    /// Path of the endpoint, relative to the base url.
+    const PATH: &'static str;
+    /// Parameters the endpoint cannot be queried without.
+    const REQUIRED_PARAMETERS: &'static [&'static str] = &[];
+}
 
 #[derive(Debug, Clone)]
 pub struct Request<T: Clone> {
+    endpoint: Endpoint,
+    required: &'static [&'static str],
     parameters: HashMap<String, String>,
     api: HolidayAPI,
     _marker: PhantomData<T>,
 }
 
+/// Parsed response which may come from an expired cache entry.
+#[derive(Debug, Clone)]
+pub struct MaybeStale<T> {
+    pub data: T,
+    /// Whether `data` comes from an expired cache entry because the API could not be reached.
+    pub stale: bool,
+}
+
+impl<T> MaybeStale<T> {
+    fn fresh(data: T) -> Self {
+        Self { data, stale: false }
+    }
+}
+
 impl<T> Request<T>
 where
     T: Clone + DeserializeOwned,
 {
+    fn empty(api: &HolidayAPI, endpoint: Endpoint) -> Self {
+        Self::with_required(api, endpoint.required_parameters(), endpoint)
+    }
+
+    /// Request with the parameters inherited from the client: its default country when the
+    /// endpoint requires a country, and its default language for `holidays`.
+    fn with_required(
+        api: &HolidayAPI,
+        required: &'static [&'static str],
+        endpoint: Endpoint,
+    ) -> Self {
+        let mut parameters = HashMap::new();
+        if let Some(country) = api.default_country.as_deref() {
+            if required.contains(&"country") {
+                parameters.insert("country".into(), country.as_str().to_string());
+            }
+        }
+        if let Some(language) = &api.default_language {
+            if endpoint == Endpoint::Holidays {
+                parameters.insert("language".into(), language.to_string());
+            }
+        }
+        Self {
+            required,
+            endpoint,
+            parameters,
+            api: api.clone(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sets the country of the request, keeping the client's default country when `country`
+    /// is blank.
+    fn set_country(&mut self, country: CountryCode) {
+        if !country.as_str().trim().is_empty() || !self.parameters.contains_key("country") {
+            self.parameters.insert("country".into(), country.into());
+        }
+    }
+
+    /// Request fetching the response cached under `key` again, with the parameters it was
+    /// sent with.
+    pub(crate) fn for_cache_key(
+        api: &HolidayAPI,
+        key: &CacheKey,
+        parameters: HashMap<String, String>,
+    ) -> Self {
+        let mut request = Self::empty(&api.at_version(key.version()), key.endpoint().clone());
+        request.parameters = parameters;
+        request
+    }
+
+    /// Request of an endpoint defined with [`EndpointSpec`].
+    pub(crate) fn spec<E: EndpointSpec<Response = T>>(api: &HolidayAPI) -> Self {
+        Self::with_required(api, E::REQUIRED_PARAMETERS, Endpoint::from(E::PATH))
+    }
+
+    /// Sets any parameter of the request, such as those of an [`EndpointSpec`].
+    pub fn parameter(&mut self, name: &str, value: &str) -> Self {
+        self.parameters.insert(name.into(), value.into());
+        self.to_owned()
+    }
+
     /// Response format (csv, json, php, tsv, yaml and xml). Defaults to JSON.
     /// Only work with `request.get_raw()`
     pub fn format(&mut self, format: &str) -> Self {
@@ -33,41 +206,355 @@ where
         self.to_owned()
     }
 
+    /// Sends this request to another version of the API than the client's, to mix versions
+    /// during a migration.
+    ///
+    /// The version at the end of the path of the base urls, `/v1/`, is replaced; other base
+    /// urls are kept.
+    ///
+    /// # Examples
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    /// let request = api.holidays("us", 2021).version(Version::V1);
+    /// ```
+    pub fn version(&mut self, version: Version) -> Self {
+        self.api = self.api.at_version(version);
+        self.to_owned()
+    }
+
+    /// Checks the parameters that can be validated locally, so obviously invalid requests
+    /// fail before being sent.
+    ///
+    /// Missing parameters and the free plan guard always fail, the checks of the values follow
+    /// the client's [`Validation`].
+    fn validate(&self) -> Result<(), HolidayAPIError> {
+        for parameter in self.required {
+            if self
+                .parameters
+                .get(*parameter)
+                .is_none_or(|value| value.trim().is_empty())
+            {
+                return Err(HolidayAPIError::MissingParameter(parameter.to_string()));
+            }
+        }
+        match self.api.validation {
+            Validation::Strict => self.validate_values()?,
+            Validation::Warn => {
+                if let Err(err) = self.validate_values() {
+                    log::warn!("Sending {} request anyway: {}", self.endpoint, err);
+                }
+            }
+            Validation::Skip => {}
+        }
+        if self.api.free_plan {
+            self.api.check_free_plan(&self.endpoint, &self.parameters)?;
+        }
+        Ok(())
+    }
+
+    /// Checks the country and language codes, the year and the dates.
+    fn validate_values(&self) -> Result<(), HolidayAPIError> {
+        if let Some(country) = self.parameters.get("country") {
+            let country = CountryCode::new(country);
+            country.validate()?;
+            if !iso::is_country(country.country()) {
+                return Err(HolidayAPIError::UnknownCountry(country.into()));
+            }
+        }
+        if let Some(language) = self.parameters.get("language") {
+            if !iso::is_language(language) {
+                return Err(HolidayAPIError::UnknownLanguage(language.clone()));
+            }
+        }
+        let year = match self.parameters.get("year") {
+            Some(year) => {
+                let year = year
+                    .parse::<i32>()
+                    .map_err(|_| HolidayAPIError::InvalidYear(year.clone()))?;
+                HolidayAPI::is_valid_year(&year)?;
+                Some(year)
+            }
+            None => None,
+        };
+        let number = |parameter: &str| -> Result<Option<u32>, HolidayAPIError> {
+            self.parameters
+                .get(parameter)
+                .map(|value| {
+                    value.parse::<u32>().map_err(|_| {
+                        HolidayAPIError::InvalidDate(format!("{} {}", parameter, value))
+                    })
+                })
+                .transpose()
+        };
+        if let Some(month) = number("month")? {
+            if !(1..=12).contains(&month) {
+                return Err(HolidayAPIError::InvalidDate(format!("month {}", month)));
+            }
+            if let (Some(year), Some(day)) = (year, number("day")?) {
+                if NaiveDate::from_ymd_opt(year, month, day).is_none() {
+                    return Err(HolidayAPIError::InvalidDate(format!(
+                        "{}-{:02}-{:02}",
+                        year, month, day
+                    )));
+                }
+            }
+        }
+        for parameter in ["start", "end"] {
+            if let Some(date) = self.parameters.get(parameter) {
+                // chrono accepts missing leading zeros, the API does not.
+                if date.len() != 10 || NaiveDate::parse_from_str(date, date::FORMAT).is_err() {
+                    return Err(HolidayAPIError::InvalidDate(date.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn api(&self) -> &HolidayAPI {
+        &self.api
+    }
+
     /// Return the raw String of the response
     pub async fn get_raw(self) -> Result<String, HolidayAPIError> {
-        Ok(self
-            .api
-            .custom_request("countries", self.parameters)
-            .await?
-            .text()
+        Ok(self.get_response().await?.text())
+    }
+
+    /// Return the raw response, along with its status and headers.
+    pub async fn get_response(self) -> Result<Response, HolidayAPIError> {
+        self.validate()?;
+        self.api
+            .custom_request(self.endpoint, self.parameters)
             .await
-            .unwrap())
     }
 
-    /// Returns the parsed struct of the response if successful
-    pub async fn get_full(self) -> Result<T, HolidayAPIError> {
-        let mut param = self.parameters;
+    /// Parameters sent when the parsed response is requested.
+    fn json_parameters(&self) -> HashMap<String, String> {
+        let mut param = self.parameters.clone();
         param.insert("format".into(), "json".into());
-        Ok(serde_json::from_str(
-            self.api
-                .custom_request("countries", param)
-                .await?
-                .text()
-                .await
-                .unwrap()
-                .as_str(),
+        param
+    }
+
+    pub(crate) fn cache_key(&self) -> CacheKey {
+        CacheKey::new(
+            self.api.version,
+            self.endpoint.clone(),
+            &self.json_parameters(),
         )
-        .expect("JSON is valid"))
+    }
+
+    /// Whether the parsed response is already in the client's cache.
+    pub fn is_cached(&self) -> bool {
+        self.api
+            .cache
+            .as_ref()
+            .is_some_and(|cache| cache.contains(&self.cache_key()))
+    }
+
+    /// Returns the parsed struct of the response if successful
+    ///
+    /// Served from the client's cache when enabled with [`HolidayAPI::with_cache`]. Expired
+    /// entries are revalidated, see [`Request::revalidate`].
+    pub async fn get_full(self) -> Result<T, HolidayAPIError>
+    where
+        T: 'static,
+    {
+        let body = self.get_body().await?;
+        Ok(self.processed(self.parse(&body)?))
+    }
+
+    /// Fetches the response from the API even if it is cached, for data meant to outlive the
+    /// cache such as [snapshots](crate::HolidayAPI::update_snapshot).
+    pub(crate) async fn fetch_full(self) -> Result<T, HolidayAPIError>
+    where
+        T: 'static,
+    {
+        self.validate()?;
+        let (body, _) = self.fetch().await?;
+        Ok(self.processed(self.parse(&body)?))
+    }
+
+    /// Returns the response as untyped JSON, to read a few fields without parsing the whole
+    /// response, see [`Response::json`].
+    ///
+    /// Served from the client's cache like [`Request::get_full`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let json = api.holidays("us", 2021).get_json().await.unwrap();
+    ///     println!("{}", json["holidays"][0]["name"].as_str().unwrap_or_default());
+    ///     let used = json.pointer("/requests/used").and_then(|used| used.as_u64());
+    /// }
+    /// ```
+    pub async fn get_json(self) -> Result<Value, HolidayAPIError> {
+        self.parse(&self.get_body().await?)
+    }
+
+    /// Response after the client's post-processors, see [`HolidayAPI::post_process`].
+    /// Parses a body of the API as answered by the version of the request, see
+    /// [`Version::parse`].
+    pub(crate) fn parse<R: DeserializeOwned>(&self, body: &str) -> Result<R, HolidayAPIError> {
+        self.api.version.parse(body)
+    }
+
+    fn processed(&self, mut response: T) -> T
+    where
+        T: 'static,
+    {
+        self.api.post_processors.apply(&mut response);
+        response
+    }
+
+    /// Body of the JSON response, from the cache if possible.
+    pub(crate) async fn get_body(&self) -> Result<String, HolidayAPIError> {
+        self.validate()?;
+        match self.cached(&self.cache_key())? {
+            Some(body) => Ok(body),
+            None => Ok(self.fetch().await?.0),
+        }
+    }
+
+    /// Body of the fresh cache entry, if any. Entries failing the integrity checks are
+    /// removed.
+    fn cached(&self, key: &CacheKey) -> Result<Option<String>, HolidayAPIError> {
+        let Some(cache) = &self.api.cache else {
+            return Ok(None);
+        };
+        let cached = cache.get(key);
+        #[cfg(feature = "prometheus")]
+        self.api.metrics.cache_lookup(cached.is_some());
+        match cached {
+            Some(body) => match self.verify(&body) {
+                Ok(()) => Ok(Some(body)),
+                Err(err) => {
+                    cache.remove(key);
+                    Err(err)
+                }
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Checks that the holidays of a response fall within the requested country and dates,
+    /// following the client's [`HolidayAPI::integrity_checks`].
+    fn verify(&self, body: &str) -> Result<(), HolidayAPIError> {
+        if self.endpoint != Endpoint::Holidays || self.api.integrity == Validation::Skip {
+            return Ok(());
+        }
+        // Bodies which cannot be parsed fail when they are parsed into the response.
+        let Ok(response) = self.parse::<HolidaysResponse>(body) else {
+            return Ok(());
+        };
+        match integrity::holidays_mismatch(&self.parameters, &response) {
+            Some(mismatch) if self.api.integrity == Validation::Strict => {
+                Err(HolidayAPIError::IntegrityMismatch(mismatch))
+            }
+            Some(mismatch) => {
+                log::warn!("Using {} response anyway: {}", self.endpoint, mismatch);
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Like [`Request::get_full`], but falls back to an expired cached response when the API
+    /// cannot be reached or fails with a server error.
+    ///
+    /// # Examples
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///         .unwrap()
+    ///         .with_cache(Duration::from_secs(3600));
+    ///     if let Ok(response) = api.holidays("us", 2021).get_full_or_stale().await {
+    ///         if response.stale {
+    ///             println!("HolidayAPI is down, showing cached holidays");
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub async fn get_full_or_stale(self) -> Result<MaybeStale<T>, HolidayAPIError>
+    where
+        T: 'static,
+    {
+        self.validate()?;
+        let key = self.cache_key();
+        let cache = self.api.cache.as_ref();
+        if let Some(body) = self.cached(&key)? {
+            return Ok(MaybeStale::fresh(self.processed(self.parse(&body)?)));
+        }
+        match self.fetch().await {
+            Ok((body, _)) => Ok(MaybeStale::fresh(self.processed(self.parse(&body)?))),
+            Err(err) if err.is_upstream_failure() => {
+                match cache.and_then(|cache| cache.entry(&key)) {
+                    Some(entry) => {
+                        self.verify(&entry.body)?;
+                        Ok(MaybeStale {
+                            data: self.processed(self.parse(&entry.body)?),
+                            stale: true,
+                        })
+                    }
+                    None => Err(err),
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Fetches the response again, even if it is cached, and tells whether it changed.
+    ///
+    /// Cached responses are revalidated with a conditional request using the `ETag` and
+    /// `Last-Modified` headers sent by the API. When the API sends the whole response back, it
+    /// is compared to the cached one to detect changes.
+    pub async fn revalidate(self) -> Result<Revalidation, HolidayAPIError> {
+        self.validate()?;
+        Ok(self.fetch().await?.1)
+    }
+
+    async fn fetch(&self) -> Result<(String, Revalidation), HolidayAPIError> {
+        let key = self.cache_key();
+        let cache = self.api.cache.as_ref();
+        let previous = cache.and_then(|cache| cache.entry(&key));
+        let headers = previous
+            .as_ref()
+            .map(|entry| entry.validators.conditional_headers())
+            .unwrap_or_default();
+
+        let response = self
+            .api
+            .send_request(&self.endpoint, self.json_parameters(), headers)
+            .await?;
+        if let (Some(cache), Some(previous)) = (cache, &previous) {
+            if response.status() == StatusCode::NOT_MODIFIED {
+                cache.refresh(&key);
+                return Ok((previous.body.clone(), Revalidation::NotModified));
+            }
+        }
+
+        let validators = Validators::from_headers(response.headers());
+        let body = response.text();
+        self.verify(&body)?;
+        let revalidation = match cache {
+            Some(cache) => cache.insert(key, self.json_parameters(), body.clone(), validators),
+            None => Revalidation::Fetched,
+        };
+        Ok((body, revalidation))
     }
 }
 
 impl Request<CountriesResponse> {
     pub(crate) fn new(api: &HolidayAPI) -> Self {
-        Self {
-            parameters: HashMap::new(),
-            api: api.clone(),
-            _marker: PhantomData,
-        }
+        Self::empty(api, Endpoint::Countries)
     }
 
     /// Return only the country with the specified code.
@@ -119,15 +606,31 @@ impl Request<CountriesResponse> {
 }
 
 impl Request<HolidaysResponse> {
-    pub(crate) fn new(api: &HolidayAPI, country: String, year: i32) -> Self {
-        let mut holiday = Self {
-            parameters: HashMap::new(),
-            api: api.clone(),
-            _marker: PhantomData,
-        };
-        holiday.parameters.insert("country".into(), country);
+    pub(crate) fn new(api: &HolidayAPI, country: CountryCode, year: i32) -> Self {
+        Self::template(api).apply(country, year)
+    }
+
+    /// Request without a year, see [`HolidayAPI::holidays_template`].
+    pub(crate) fn template(api: &HolidayAPI) -> Self {
+        Self::empty(api, Endpoint::Holidays)
+    }
+
+    /// Copy of this request for another country and year, keeping the other parameters.
+    ///
+    /// # Examples
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///
+    /// let template = api.holidays_template().language("fr").public().pretty();
+    /// let germany = template.apply("de", 2025);
+    /// let austria = template.apply("at", 2025);
+    /// ```
+    pub fn apply(&self, country: impl Into<CountryCode>, year: i32) -> Self {
+        let mut holiday = self.clone();
+        holiday.set_country(country.into());
         holiday.parameters.insert("year".into(), year.to_string());
-        return holiday;
+        holiday
     }
 
     /// 1 or 2 digit month (1-12).
@@ -222,18 +725,12 @@ impl Request<HolidaysResponse> {
 }
 
 impl Request<WorkdayResponse> {
-    pub(crate) fn new(api: &HolidayAPI, country: &str, start: &str, days: i32) -> Self {
-        let mut workday = Self {
-            parameters: HashMap::new(),
-            api: api.clone(),
-            _marker: PhantomData,
-        };
-        workday
-            .parameters
-            .insert("country".into(), country.to_string());
+    pub(crate) fn new(api: &HolidayAPI, country: CountryCode, start: &str, days: i32) -> Self {
+        let mut workday = Self::empty(api, Endpoint::Workday);
+        workday.set_country(country);
         workday.parameters.insert("start".into(), start.to_string());
         workday.parameters.insert("days".into(), days.to_string());
-        return workday;
+        workday
     }
 
     /// Returns only the important `("YYYY-MM-DD", Weekday)` tuple.
@@ -244,20 +741,14 @@ impl Request<WorkdayResponse> {
 }
 
 impl Request<WorkdaysResponse> {
-    pub fn new(api: &HolidayAPI, country: &str, start: &str, days: &str) -> Self {
-        let mut workdays = Self {
-            parameters: HashMap::new(),
-            api: api.clone(),
-            _marker: PhantomData,
-        };
-        workdays
-            .parameters
-            .insert("country".into(), country.to_string());
+    pub fn new(api: &HolidayAPI, country: impl Into<CountryCode>, start: &str, days: &str) -> Self {
+        let mut workdays = Self::empty(api, Endpoint::Workdays);
+        workdays.set_country(country.into());
         workdays
             .parameters
             .insert("start".into(), start.to_string());
         workdays.parameters.insert("end".into(), days.to_string());
-        return workdays;
+        workdays
     }
 
     /// Returns the number of working / business days between the specified start and end dates.
@@ -269,11 +760,7 @@ impl Request<WorkdaysResponse> {
 
 impl Request<LanguagesResponse> {
     pub fn new(api: &HolidayAPI) -> Self {
-        Self {
-            parameters: HashMap::new(),
-            api: api.clone(),
-            _marker: PhantomData,
-        }
+        Self::empty(api, Endpoint::Languages)
     }
 
     /// Return only the language with the specified code.
@@ -282,7 +769,7 @@ impl Request<LanguagesResponse> {
     /// use holidayapi_rust::prelude::*;
     ///
     /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
-    /// let request = api.languages().language("us");
+    /// let request = api.languages().language("ja");
     /// ```
     pub fn language(&mut self, language: &str) -> Self {
         self.parameters.insert("language".into(), language.into());
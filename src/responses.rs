@@ -1,13 +1,18 @@
-use serde::Deserialize;
+use std::collections::BTreeMap;
 
-#[derive(Debug, Deserialize, Clone)]
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{date, search};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct APIRequests {
     pub available: u32,
     pub used: u32,
     pub resets: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CountriesResponse {
     pub requests: APIRequests,
     pub status: u32,
@@ -16,7 +21,41 @@ pub struct CountriesResponse {
     pub countries: Vec<Country>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl CountriesResponse {
+    /// Returns the country with the given code, case-insensitively.
+    pub fn country(&self, code: &str) -> Option<&Country> {
+        self.countries
+            .iter()
+            .find(|country| country.code.eq_ignore_ascii_case(code.trim()))
+    }
+
+    /// Returns the subdivision with the given ISO 3166-2 code (e.g. `"US-CA"`), looking it up
+    /// in its parent country.
+    pub fn subdivision(&self, code: &str) -> Option<&Subdivision> {
+        let country = code.trim().split('-').next()?;
+        self.country(country)?.subdivision(code)
+    }
+
+    /// Indexes the countries by the language codes they use, lowercased, e.g. every country
+    /// using `"fr"`. Countries keep the order of the response.
+    pub fn countries_by_language(&self) -> BTreeMap<String, Vec<&Country>> {
+        let mut index: BTreeMap<String, Vec<&Country>> = BTreeMap::new();
+        for country in &self.countries {
+            for language in &country.languages {
+                let countries = index.entry(language.to_ascii_lowercase()).or_default();
+                if !countries
+                    .last()
+                    .is_some_and(|last| std::ptr::eq(*last, country))
+                {
+                    countries.push(country);
+                }
+            }
+        }
+        index
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Country {
     pub code: String,
     pub name: String,
@@ -26,7 +65,33 @@ pub struct Country {
     pub subdivisions: Vec<Subdivision>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl Country {
+    /// Returns the flag emoji of the country, computed from its alpha-2 code.
+    ///
+    /// Returns `None` if the alpha-2 code is not made of exactly two ASCII letters.
+    pub fn flag_emoji(&self) -> Option<String> {
+        self.codes.flag_emoji()
+    }
+
+    /// Returns the subdivision with the given ISO 3166-2 code (e.g. `"US-CA"`), case-insensitively.
+    pub fn subdivision(&self, code: &str) -> Option<&Subdivision> {
+        self.subdivisions
+            .iter()
+            .find(|subdivision| subdivision.code.eq_ignore_ascii_case(code.trim()))
+    }
+
+    /// Returns the subdivisions using the given language code.
+    pub fn subdivisions_with_language<'a>(
+        &'a self,
+        language: &'a str,
+    ) -> impl Iterator<Item = &'a Subdivision> {
+        self.subdivisions
+            .iter()
+            .filter(move |subdivision| subdivision.has_language(language))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Codes {
     #[serde(rename = "alpha-2")]
     pub alpha_2: String,
@@ -35,14 +100,49 @@ pub struct Codes {
     pub numeric: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl Codes {
+    /// Returns the flag emoji matching the alpha-2 code, made of two regional indicator symbols.
+    pub fn flag_emoji(&self) -> Option<String> {
+        let code = self.alpha_2.trim();
+        if code.len() != 2 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        code.to_ascii_uppercase()
+            .chars()
+            .map(|c| char::from_u32(0x1F1E6 + (c as u32 - 'A' as u32)))
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Subdivision {
     pub code: String,
     pub name: String,
     pub languages: Vec<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl Subdivision {
+    /// Returns the alpha-2 code of the parent country, e.g. `"US"` for `"US-CA"`.
+    pub fn country_code(&self) -> &str {
+        self.code.split('-').next().unwrap_or(&self.code)
+    }
+
+    /// Returns the part of the code identifying the subdivision, e.g. `"CA"` for `"US-CA"`.
+    pub fn local_code(&self) -> &str {
+        self.code
+            .split_once('-')
+            .map_or(&*self.code, |(_, local)| local)
+    }
+
+    /// Whether the subdivision uses the given language code.
+    pub fn has_language(&self, language: &str) -> bool {
+        self.languages
+            .iter()
+            .any(|code| code.eq_ignore_ascii_case(language))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct HolidaysResponse {
     pub requests: APIRequests,
     pub status: u32,
@@ -51,30 +151,134 @@ pub struct HolidaysResponse {
     pub warning: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl HolidaysResponse {
+    /// Returns the public holidays of the response.
+    pub fn public(&self) -> impl Iterator<Item = &Holiday> {
+        self.holidays.iter().filter(|holiday| holiday.public)
+    }
+
+    /// Returns the observances of the response which are not public holidays.
+    pub fn non_public(&self) -> impl Iterator<Item = &Holiday> {
+        self.holidays.iter().filter(|holiday| !holiday.public)
+    }
+
+    /// Number of public holidays in the response.
+    pub fn count_public(&self) -> usize {
+        self.public().count()
+    }
+
+    /// Number of observances in the response which are not public holidays.
+    pub fn count_non_public(&self) -> usize {
+        self.non_public().count()
+    }
+
+    /// Groups the holidays of the response by date, in chronological order.
+    ///
+    /// The map is keyed by the holidays' `date`, not `observed`, holidays whose date cannot be
+    /// parsed are left out.
+    pub fn to_map_by_date(&self) -> BTreeMap<NaiveDate, Vec<Holiday>> {
+        let mut map: BTreeMap<NaiveDate, Vec<Holiday>> = BTreeMap::new();
+        for holiday in &self.holidays {
+            if let Some(date) = holiday.parse_date() {
+                map.entry(date).or_default().push(holiday.clone());
+            }
+        }
+        map
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Holiday {
     pub name: String,
     pub date: String,
     pub observed: String,
     pub public: bool,
     pub country: String,
+    /// Identifier given by HolidayAPI, the same for every language of the holiday.
     pub uuid: String,
     pub weekday: Weekday,
+    /// ISO 3166-2 codes of the subdivisions observing the holiday, empty when it is observed
+    /// nationwide.
+    #[serde(default)]
+    pub subdivisions: Vec<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl Holiday {
+    /// Parses the `uuid` field as a [`uuid::Uuid`], `None` for identifiers which are not
+    /// UUIDs.
+    #[cfg(feature = "uuid")]
+    pub fn uuid(&self) -> Option<uuid::Uuid> {
+        uuid::Uuid::try_parse(&self.uuid).ok()
+    }
+
+    /// Parses the `date` field, see [`FlexibleDate`](crate::FlexibleDate) for the accepted
+    /// formats. The raw string is kept in `date`, so an unexpected format never fails the
+    /// whole payload.
+    pub fn parse_date(&self) -> Option<NaiveDate> {
+        date::parse(&self.date)
+    }
+
+    /// Parses the `observed` field, see [`Holiday::parse_date`].
+    pub fn parse_observed(&self) -> Option<NaiveDate> {
+        date::parse(&self.observed)
+    }
+
+    /// Name in lowercase, without accents nor punctuation: `"Día de la Raza"` becomes
+    /// `"dia de la raza"`.
+    pub fn normalized_name(&self) -> String {
+        search::normalize(&self.name)
+    }
+
+    /// Stable ASCII kebab-case identifier made of the name and the date, such as
+    /// `"new-years-day-2021-01-01"`, for keys chosen independently from HolidayAPI's `uuid`.
+    ///
+    /// Characters outside ASCII which are not accented letters are dropped. Names left empty,
+    /// such as those written in other scripts, are replaced by a hash of the name, stable
+    /// across releases: `"Новый год"` becomes `"92ed44f29f0013d8-2021-01-01"`.
+    pub fn slug(&self) -> String {
+        let date = self
+            .parse_date()
+            .map(|date| date.format(date::FORMAT).to_string())
+            .unwrap_or_else(|| self.date.clone());
+        let words = |text: &str| -> Vec<String> {
+            search::normalize(text)
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric() || *c == ' ')
+                .collect::<String>()
+                .split_whitespace()
+                .map(str::to_string)
+                .collect()
+        };
+        let mut slug = words(&self.name);
+        if slug.is_empty() && !self.name.trim().is_empty() {
+            slug.push(format!("{:016x}", fnv1a(self.name.trim())));
+        }
+        slug.extend(words(&date));
+        slug.join("-")
+    }
+}
+
+/// 64-bit FNV-1a hash, whose value does not depend on the Rust release unlike
+/// [`DefaultHasher`](std::collections::hash_map::DefaultHasher).
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Weekday {
     pub date: Date,
     pub observed: Date,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Date {
     pub name: String,
     pub numeric: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WorkdayResponse {
     pub requests: APIRequests,
     pub status: u32,
@@ -84,7 +288,14 @@ pub struct WorkdayResponse {
     pub warning: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl WorkdayResponse {
+    /// Parses the `date` field, see [`Holiday::parse_date`].
+    pub fn parse_date(&self) -> Option<NaiveDate> {
+        date::parse(&self.date)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 
 pub struct WorkdaysResponse {
     pub requests: APIRequests,
@@ -94,7 +305,7 @@ pub struct WorkdaysResponse {
     pub warning: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 
 pub struct LanguagesResponse {
     pub requests: APIRequests,
@@ -103,8 +314,159 @@ pub struct LanguagesResponse {
     pub error: Option<String>,
     pub warning: Option<String>,
 }
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Language {
     pub code: String,
     pub name: String,
 }
+
+/// A language and the countries using it, see
+/// [`HolidayAPI::countries_for_language`](crate::HolidayAPI::countries_for_language).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LanguageCountries {
+    pub language: Language,
+    pub countries: Vec<Country>,
+}
+
+/// Body of an error response.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ErrorResponse {
+    pub status: u32,
+    pub error: String,
+    pub requests: Option<APIRequests>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(alpha_2: &str) -> Codes {
+        Codes {
+            alpha_2: alpha_2.into(),
+            alpha_3: "".into(),
+            numeric: "".into(),
+        }
+    }
+
+    #[test]
+    fn test_subdivision_lookup() {
+        let country = Country {
+            code: "US".into(),
+            name: "United States".into(),
+            languages: vec!["en".into()],
+            codes: codes("US"),
+            flag: "".into(),
+            subdivisions: vec![Subdivision {
+                code: "US-CA".into(),
+                name: "California".into(),
+                languages: vec!["en".into()],
+            }],
+        };
+        let subdivision = country.subdivision("us-ca").unwrap();
+        assert_eq!(&*subdivision.name, "California");
+        assert_eq!(subdivision.country_code(), "US");
+        assert_eq!(subdivision.local_code(), "CA");
+        assert!(country.subdivision("US-NY").is_none());
+        assert_eq!(country.subdivisions_with_language("EN").count(), 1);
+    }
+
+    #[test]
+    fn test_countries_by_language() {
+        let countries: CountriesResponse = crate::fixtures::Fixture::named("countries")
+            .unwrap()
+            .parse()
+            .unwrap();
+        let index = countries.countries_by_language();
+        let codes = |language: &str| -> Vec<&str> {
+            index[language]
+                .iter()
+                .map(|country| &*country.code)
+                .collect()
+        };
+        assert_eq!(codes("en"), ["US", "CA"]);
+        assert_eq!(codes("fr"), ["CA"]);
+        assert_eq!(index.len(), 2);
+    }
+
+    #[test]
+    fn test_flag_emoji() {
+        assert_eq!(codes("US").flag_emoji().as_deref(), Some("🇺🇸"));
+        assert_eq!(codes("jp").flag_emoji().as_deref(), Some("🇯🇵"));
+        assert_eq!(codes("U1").flag_emoji(), None);
+        assert_eq!(codes("USA").flag_emoji(), None);
+    }
+
+    #[test]
+    fn test_holiday_slug() {
+        let mut holiday = crate::fixtures::Fixture::named("holidays")
+            .unwrap()
+            .parse::<HolidaysResponse>()
+            .unwrap()
+            .holidays
+            .remove(0);
+        holiday.name = "New Year's Day".into();
+        holiday.date = "2021-01-01".into();
+        assert_eq!(holiday.slug(), "new-years-day-2021-01-01");
+        holiday.name = " Día de la  Raza / Columbus Day ".into();
+        assert_eq!(holiday.normalized_name(), "dia de la raza columbus day");
+        assert_eq!(holiday.slug(), "dia-de-la-raza-columbus-day-2021-01-01");
+        holiday.name = "Новый год".into();
+        holiday.date = "2021-1-1".into();
+        let slug = holiday.slug();
+        assert_eq!(slug, "92ed44f29f0013d8-2021-01-01");
+        holiday.name = "Рождество".into();
+        assert_ne!(holiday.slug(), slug);
+        holiday.name = " ".into();
+        assert_eq!(holiday.slug(), "2021-01-01");
+    }
+
+    #[test]
+    fn test_public_filters() {
+        let response: HolidaysResponse = crate::fixtures::Fixture::named("holidays")
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(response.public().all(|holiday| holiday.public));
+        assert!(response.non_public().all(|holiday| !holiday.public));
+        assert_eq!(
+            response.count_public() + response.count_non_public(),
+            response.holidays.len()
+        );
+        assert!(response.count_public() > 0 && response.count_non_public() > 0);
+    }
+
+    #[test]
+    fn test_map_by_date() {
+        let mut response: HolidaysResponse = crate::fixtures::Fixture::named("holidays")
+            .unwrap()
+            .parse()
+            .unwrap();
+        response.holidays.reverse();
+        response.holidays[0].date = "invalid".into();
+        let map = response.to_map_by_date();
+        assert!(map.keys().zip(map.keys().skip(1)).all(|(a, b)| a < b));
+        assert_eq!(
+            map.values().map(Vec::len).sum::<usize>(),
+            response.holidays.len() - 1
+        );
+        let first = map.iter().next().unwrap();
+        assert!(first
+            .1
+            .iter()
+            .all(|holiday| holiday.parse_date() == Some(*first.0)));
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_holiday_uuids() {
+        let mut response: HolidaysResponse = crate::fixtures::Fixture::named("holidays")
+            .unwrap()
+            .parse()
+            .unwrap();
+        let uuid = response.holidays[0].uuid().unwrap();
+        assert_eq!(uuid.to_string(), response.holidays[0].uuid);
+
+        response.holidays[0].uuid = "new-years-day".into();
+        assert_eq!(response.holidays[0].uuid(), None);
+    }
+}
@@ -0,0 +1,440 @@
+use std::{
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hasher},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "tokio")]
+use crate::runtime::TokioRuntime;
+use crate::{runtime::Runtime, HolidayAPIError};
+
+type Classifier = Arc<dyn Fn(&HolidayAPIError) -> bool + Send + Sync>;
+
+/// How the delay between two attempts grows, set with [`RetryPolicy::backoff`].
+///
+/// Delays start from [`RetryPolicy::delay`] and never exceed [`RetryPolicy::max_delay`]. Jittered
+/// strategies spread the retries of many clients failing at once, at the cost of less
+/// predictable delays.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backoff {
+    /// The same delay before every retry.
+    Constant,
+    /// A delay doubling after every retry.
+    #[default]
+    Exponential,
+    /// A random delay between zero and the exponential delay.
+    ExponentialFullJitter,
+    /// A random delay between the initial delay and three times the previous delay.
+    DecorrelatedJitter,
+}
+
+/// Retries of requests failing with a [retryable](HolidayAPIError::is_retryable) error, set with
+/// [`HolidayAPI::retry`](crate::HolidayAPI::retry).
+///
+/// By default only network errors, timeouts, rate limiting and server errors are retried, never
+/// rejected requests such as invalid parameters, which would fail again and spend quota. The
+/// classification can be replaced or extended with [`retry_if`](Self::retry_if).
+///
+/// The delay before each retry grows from [`delay`](Self::delay) according to the [`Backoff`]
+/// strategy, and is at least the `Retry-After` delay of rate limited requests. Retrying stops
+/// after [`max_attempts`](Self::max_attempts) attempts, or when waiting for the next attempt
+/// would exceed [`max_elapsed`](Self::max_elapsed) since the first one. For batch helpers such
+/// as [`HolidayAPI::prefetch`](crate::HolidayAPI::prefetch), `max_elapsed` is counted from the
+/// start of the whole batch.
+///
+/// # Examples
+/// ```
+/// use holidayapi_rust::prelude::*;
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+///         .unwrap()
+///         .retry(
+///             &RetryPolicy::new()
+///                 .max_attempts(5)
+///                 .max_elapsed(Duration::from_secs(60))
+///                 .backoff(Backoff::ExponentialFullJitter),
+///         );
+/// }
+/// ```
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    max_elapsed: Option<Duration>,
+    delay: Duration,
+    max_delay: Duration,
+    backoff: Backoff,
+    classifier: Option<Classifier>,
+    runtime: Arc<dyn Runtime>,
+    /// State of the generator of the jittered delays.
+    jitter: Arc<AtomicU64>,
+    /// End of the batch operation the policy is used for.
+    deadline: Option<Instant>,
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("max_elapsed", &self.max_elapsed)
+            .field("delay", &self.delay)
+            .field("max_delay", &self.max_delay)
+            .field("backoff", &self.backoff)
+            .field("classifier", &self.classifier.is_some())
+            .field("runtime", &self.runtime)
+            .field("deadline", &self.deadline)
+            .finish()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RetryPolicy {
+    /// Number of attempts made by default, the first one included.
+    pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+    /// Delay before the first retry by default.
+    pub const DEFAULT_DELAY: Duration = Duration::from_millis(500);
+    /// Longest delay between two attempts by default.
+    pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+    /// Policy waiting between attempts on the current tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn new() -> Self {
+        Self::with_runtime(Arc::new(TokioRuntime))
+    }
+
+    /// Policy waiting between attempts with the given runtime.
+    pub fn with_runtime(runtime: Arc<dyn Runtime>) -> Self {
+        Self {
+            max_attempts: Self::DEFAULT_MAX_ATTEMPTS,
+            max_elapsed: None,
+            delay: Self::DEFAULT_DELAY,
+            max_delay: Self::DEFAULT_MAX_DELAY,
+            backoff: Backoff::default(),
+            classifier: None,
+            runtime,
+            jitter: Arc::new(AtomicU64::new(RandomState::new().build_hasher().finish())),
+            deadline: None,
+        }
+    }
+
+    /// Sets the maximum number of attempts, the first one included.
+    pub fn max_attempts(&mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self.to_owned()
+    }
+
+    /// Sets the maximum time spent on a request or batch, waiting between attempts included.
+    pub fn max_elapsed(&mut self, elapsed: Duration) -> Self {
+        self.max_elapsed = Some(elapsed);
+        self.to_owned()
+    }
+
+    /// Sets the delay before the first retry.
+    pub fn delay(&mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self.to_owned()
+    }
+
+    /// Sets the longest delay between two attempts, `Retry-After` delays excepted.
+    pub fn max_delay(&mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self.to_owned()
+    }
+
+    pub fn backoff(&mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self.to_owned()
+    }
+
+    /// Retries the errors for which `classify` returns `true`, instead of the
+    /// [retryable](HolidayAPIError::is_retryable) ones.
+    ///
+    /// # Examples
+    /// ```
+    /// use holidayapi_rust::prelude::*;
+    /// use http::StatusCode;
+    ///
+    /// // Also retry the 404 answered while a new year is being published.
+    /// let policy = RetryPolicy::new().retry_if(|err| {
+    ///     err.is_retryable() || err.status() == Some(StatusCode::NOT_FOUND)
+    /// });
+    /// ```
+    pub fn retry_if(
+        &mut self,
+        classify: impl Fn(&HolidayAPIError) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.classifier = Some(Arc::new(classify));
+        self.to_owned()
+    }
+
+    /// Whether the policy retries requests failing with `error`.
+    pub fn retries(&self, error: &HolidayAPIError) -> bool {
+        match &self.classifier {
+            Some(classify) => classify(error),
+            None => error.is_retryable(),
+        }
+    }
+
+    /// Pseudo-random number in `0..=bound`, using SplitMix64.
+    fn random_up_to(&self, bound: u64) -> u64 {
+        let state = self
+            .jitter
+            .fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)
+            .wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        (z ^ (z >> 31)) % bound.saturating_add(1)
+    }
+
+    /// Random delay between `low` and `high`.
+    fn random_between(&self, low: Duration, high: Duration) -> Duration {
+        let span = high.saturating_sub(low).as_millis().min(u64::MAX as u128) as u64;
+        low + Duration::from_millis(self.random_up_to(span))
+    }
+
+    /// Delay of the backoff strategy before the `attempt`-th attempt, `previous` being the
+    /// delay before the previous attempt.
+    fn backoff_delay(&self, attempt: u32, previous: Duration) -> Duration {
+        let exponential = self.delay.saturating_mul(1 << (attempt - 2).min(16));
+        let delay = match self.backoff {
+            Backoff::Constant => self.delay,
+            Backoff::Exponential => exponential,
+            Backoff::ExponentialFullJitter => {
+                self.random_between(Duration::ZERO, exponential.min(self.max_delay))
+            }
+            Backoff::DecorrelatedJitter => self.random_between(
+                self.delay,
+                previous
+                    .max(self.delay)
+                    .saturating_mul(3)
+                    .min(self.max_delay),
+            ),
+        };
+        delay.min(self.max_delay)
+    }
+
+    /// Policy for a batch starting now, whose requests share its `max_elapsed`.
+    pub(crate) fn for_batch(&self) -> Self {
+        let mut policy = self.clone();
+        policy.deadline = self.max_elapsed.map(|elapsed| Instant::now() + elapsed);
+        policy
+    }
+
+    /// Delay before the `attempt`-th attempt of a request started at `started`, which failed
+    /// with `error` after waiting `previous`, or `None` when it should not be retried.
+    pub(crate) fn delay_before(
+        &self,
+        attempt: u32,
+        started: Instant,
+        previous: Duration,
+        error: &HolidayAPIError,
+    ) -> Option<Duration> {
+        if attempt > self.max_attempts || !self.retries(error) {
+            return None;
+        }
+        let mut delay = self.backoff_delay(attempt, previous);
+        if let HolidayAPIError::RateLimited(Some(retry_after), _, _) = error {
+            delay = delay.max(*retry_after);
+        }
+        let deadline = match (
+            self.max_elapsed.map(|elapsed| started + elapsed),
+            self.deadline,
+        ) {
+            (Some(request), Some(batch)) => Some(request.min(batch)),
+            (request, batch) => request.or(batch),
+        };
+        match deadline {
+            Some(deadline) if Instant::now() + delay > deadline => None,
+            _ => Some(delay),
+        }
+    }
+
+    pub(crate) async fn sleep(&self, delay: Duration) {
+        self.runtime.sleep(delay).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{
+        fixtures::Fixture,
+        mock::{MockResponse, MockTransport},
+        test_support::EXPIRED_KEY,
+        transport::BoxFuture,
+        HolidayAPI,
+    };
+
+    /// Runtime recording the delays slept, without waiting.
+    #[derive(Debug, Default)]
+    struct Recording(Mutex<Vec<Duration>>);
+
+    impl Runtime for Recording {
+        fn spawn(&self, _future: BoxFuture<'static, ()>) {}
+
+        fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+            self.0.lock().unwrap().push(duration);
+            Box::pin(async {})
+        }
+    }
+
+    fn retrying_api(
+        transport: &MockTransport,
+        policy: impl Fn(RetryPolicy) -> RetryPolicy,
+    ) -> (HolidayAPI, Arc<Recording>) {
+        let runtime = Arc::new(Recording::default());
+        let policy =
+            policy(RetryPolicy::with_runtime(runtime.clone()).delay(Duration::from_millis(100)));
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+            .retry(&policy);
+        (api, runtime)
+    }
+
+    #[tokio::test]
+    async fn test_retry_policy() {
+        let holidays = Fixture::named("holidays").unwrap().mock_response();
+        let transport = MockTransport::new(holidays.clone())
+            .on_call(1, Fixture::named("error_500").unwrap().mock_response())
+            .on_call(2, MockResponse::rate_limited(Some(Duration::from_secs(5))));
+        let (api, runtime) = retrying_api(&transport, |policy| policy);
+        assert!(api.holidays("us", 2021).get().await.is_ok());
+        assert_eq!(transport.calls(), 3);
+        assert_eq!(
+            *runtime.0.lock().unwrap(),
+            [Duration::from_millis(100), Duration::from_secs(5)]
+        );
+
+        let transport = MockTransport::new(MockResponse::transport_error("connection reset"));
+        let (api, _) = retrying_api(&transport, |mut policy| policy.max_attempts(2));
+        assert!(api.holidays("us", 2021).get().await.is_err());
+        assert_eq!(transport.calls(), 2);
+
+        let transport = MockTransport::new(Fixture::named("error_400").unwrap().mock_response());
+        let (api, runtime) = retrying_api(&transport, |policy| policy);
+        assert!(api.holidays("us", 2021).get().await.is_err());
+        assert_eq!(transport.calls(), 1);
+        assert!(runtime.0.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_retry_classification() {
+        let transport = MockTransport::new(Fixture::named("holidays").unwrap().mock_response())
+            .on_call(1, MockResponse::timeout())
+            .on_call(2, Fixture::named("error_400").unwrap().mock_response());
+        let (api, _) = retrying_api(&transport, |policy| policy);
+        assert!(api.holidays("us", 2021).get().await.is_err());
+        assert_eq!(transport.calls(), 2);
+
+        let transport = MockTransport::new(Fixture::named("holidays").unwrap().mock_response())
+            .on_call(1, Fixture::named("error_400").unwrap().mock_response());
+        let (api, _) = retrying_api(&transport, |mut policy| {
+            policy
+                .retry_if(|err| err.is_retryable() || err.status() == Some(StatusCode::BAD_REQUEST))
+        });
+        assert!(api.holidays("us", 2021).get().await.is_ok());
+        assert_eq!(transport.calls(), 2);
+
+        let mut policy = RetryPolicy::with_runtime(Arc::new(Recording::default()));
+        assert!(policy.retries(&HolidayAPIError::RequestError(
+            StatusCode::SERVICE_UNAVAILABLE,
+            String::new()
+        )));
+        assert!(!policy.retries(&HolidayAPIError::RequestError(
+            StatusCode::NOT_FOUND,
+            String::new()
+        )));
+        assert!(!policy
+            .retry_if(|_| false)
+            .retries(&HolidayAPIError::TransportError("connection reset".into())));
+    }
+
+    #[test]
+    fn test_backoff_strategies() {
+        let policy = |backoff| {
+            RetryPolicy::with_runtime(Arc::new(Recording::default()))
+                .delay(Duration::from_millis(100))
+                .max_delay(Duration::from_secs(1))
+                .backoff(backoff)
+        };
+        let delays = |policy: &RetryPolicy| {
+            let mut previous = Duration::ZERO;
+            (2..10)
+                .map(|attempt| {
+                    previous = policy.backoff_delay(attempt, previous);
+                    previous
+                })
+                .collect::<Vec<_>>()
+        };
+        let ms = Duration::from_millis;
+
+        assert!(delays(&policy(Backoff::Constant))
+            .iter()
+            .all(|delay| *delay == ms(100)));
+        assert_eq!(
+            delays(&policy(Backoff::Exponential))[..5],
+            [ms(100), ms(200), ms(400), ms(800), ms(1000)]
+        );
+        let jittered = policy(Backoff::ExponentialFullJitter);
+        for _ in 0..20 {
+            let delays = delays(&jittered);
+            assert!(delays[0] <= ms(100) && delays[2] <= ms(400));
+            assert!(delays.iter().all(|delay| *delay <= ms(1000)));
+        }
+        let decorrelated = policy(Backoff::DecorrelatedJitter);
+        for _ in 0..20 {
+            let delays = delays(&decorrelated);
+            assert!(delays[0] >= ms(100) && delays[0] <= ms(300));
+            assert!(delays
+                .windows(2)
+                .all(|pair| pair[1] >= ms(100) && pair[1] <= (pair[0] * 3).min(ms(1000))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_max_elapsed() {
+        let transport = MockTransport::new(MockResponse::transport_error("connection reset"));
+        let (api, runtime) = retrying_api(&transport, |mut policy| {
+            policy
+                .max_attempts(10)
+                .max_elapsed(Duration::from_millis(150))
+        });
+        assert!(api.holidays("us", 2021).get().await.is_err());
+        // The runtime does not wait, but waiting 200ms would exceed the 150ms allowed.
+        assert_eq!(transport.calls(), 2);
+        assert_eq!(*runtime.0.lock().unwrap(), [Duration::from_millis(100)]);
+
+        let policy = RetryPolicy::with_runtime(runtime.clone())
+            .max_elapsed(Duration::from_millis(250))
+            .for_batch();
+        let err = HolidayAPIError::TransportError("connection reset".into());
+        let long_ago = Instant::now() - Duration::from_secs(1);
+        assert!(policy
+            .delay_before(2, long_ago, Duration::ZERO, &err)
+            .is_none());
+        let mut policy = policy.clone();
+        policy.deadline = Some(Instant::now() - Duration::from_millis(1));
+        assert!(policy
+            .delay_before(2, Instant::now(), Duration::ZERO, &err)
+            .is_none());
+    }
+}
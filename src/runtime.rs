@@ -0,0 +1,36 @@
+use std::{fmt::Debug, time::Duration};
+
+use crate::transport::BoxFuture;
+
+/// Async executor used by background components such as the
+/// [`Scheduler`](crate::prelude::Scheduler).
+///
+/// The `tokio` feature, enabled by default, provides [`TokioRuntime`]. Other executors
+/// (async-std, smol, ...) only need to spawn futures and provide a timer.
+pub trait Runtime: Debug + Send + Sync {
+    /// Runs the future in the background.
+    fn spawn(&self, future: BoxFuture<'static, ()>);
+
+    /// Returns a future completing after `duration`.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+}
+
+/// [`Runtime`] spawning on the current tokio runtime.
+///
+/// # Panics
+///
+/// Spawning panics if called outside of a tokio runtime.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioRuntime;
+
+#[cfg(feature = "tokio")]
+impl Runtime for TokioRuntime {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        tokio::spawn(future);
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
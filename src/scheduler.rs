@@ -0,0 +1,378 @@
+use futures_channel::{mpsc, oneshot};
+use futures_util::{
+    future::{self, Either},
+    stream::FuturesUnordered,
+    FutureExt, StreamExt,
+};
+use serde::de::DeserializeOwned;
+use std::{
+    collections::VecDeque,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "tokio")]
+use crate::runtime::TokioRuntime;
+use crate::{
+    requests::Request,
+    runtime::Runtime,
+    shutdown::{self, TaskGuard, TaskHandle},
+    transport::BoxFuture,
+    HolidayAPIError,
+};
+
+/// Maximum number of times a request is dispatched when the API keeps answering with `429`,
+/// for clients without a [`RetryPolicy`](crate::prelude::RetryPolicy).
+const MAX_RATE_LIMITED_ATTEMPTS: u32 = 3;
+
+/// Number of requests allowed per time window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub per: Duration,
+}
+
+impl RateLimit {
+    pub fn new(requests: u32, per: Duration) -> Self {
+        Self {
+            requests: requests.max(1),
+            per,
+        }
+    }
+
+    pub fn per_second(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(1))
+    }
+
+    pub fn per_minute(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(60))
+    }
+
+    /// Minimum delay between two dispatched requests.
+    pub fn interval(&self) -> Duration {
+        self.per / self.requests
+    }
+}
+
+/// Outcome of a single dispatch: `Some(pause)` asks the scheduler to pause and dispatch the job
+/// again later.
+type Attempt = BoxFuture<'static, Option<Option<Duration>>>;
+
+struct Job {
+    attempts: u32,
+    run: Box<dyn FnMut(u32) -> Attempt + Send>,
+}
+
+/// What the dispatching loop woke up for.
+enum Event {
+    /// The rate limit allows dispatching the pending job.
+    Slot,
+    /// A job was enqueued, or `None` once the scheduler stopped accepting jobs.
+    Enqueued(Option<Job>),
+    /// A dispatched job completed, with the outcome of its attempt.
+    Completed(Job, Option<Option<Duration>>),
+}
+
+/// Queue dispatching requests while respecting a [`RateLimit`].
+///
+/// A request is dispatched on each slot of the rate limit, without waiting for the previous
+/// ones to complete, so slow responses do not hold back the queue.
+///
+/// When the API answers with `429 Too Many Requests`, the scheduler pauses every queued request
+/// until the `Retry-After` delay (or one full rate limit window) has elapsed, then dispatches the
+/// limited request again, up to three times. Requests exceeding the client's
+/// [`Budget`](crate::prelude::Budget) likewise wait until the budget resets. Requests of clients
+/// with a [`RetryPolicy`](crate::prelude::RetryPolicy) are dispatched once, the policy retrying
+/// them, see [`HolidayAPI::retry`](crate::HolidayAPI::retry).
+///
+/// # Examples
+/// ```
+/// use holidayapi_rust::prelude::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+///     let scheduler = Scheduler::new(RateLimit::per_second(5));
+///
+///     let us = scheduler.enqueue(api.holidays("us", 2021));
+///     let jp = scheduler.enqueue(api.holidays("jp", 2021));
+///     let (_us, _jp) = (us.await, jp.await);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    rate_limit: RateLimit,
+    sender: mpsc::UnboundedSender<Job>,
+    task: TaskHandle,
+}
+
+impl Scheduler {
+    /// Spawns the dispatching task on the current tokio runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn new(rate_limit: RateLimit) -> Self {
+        Self::with_runtime(rate_limit, Arc::new(TokioRuntime))
+    }
+
+    /// Spawns the dispatching task on the given runtime.
+    pub fn with_runtime(rate_limit: RateLimit, runtime: Arc<dyn Runtime>) -> Self {
+        let (sender, receiver) = mpsc::unbounded();
+        let (task, guard) = shutdown::task();
+        runtime.spawn(Box::pin(Self::dispatch(
+            rate_limit,
+            runtime.clone(),
+            receiver,
+            guard,
+        )));
+        Self {
+            rate_limit,
+            sender,
+            task,
+        }
+    }
+
+    pub fn rate_limit(&self) -> RateLimit {
+        self.rate_limit
+    }
+
+    /// Stops accepting requests, dispatches the queued ones while respecting the rate limit,
+    /// and waits for the dispatching task to end.
+    ///
+    /// Applies to every clone of the scheduler. Requests enqueued afterwards fail with
+    /// [`HolidayAPIError::SchedulerStopped`].
+    pub async fn shutdown(&self) {
+        self.sender.close_channel();
+        self.task.shutdown().await;
+    }
+
+    /// Stops the dispatching task right away: the request in flight and the queued ones fail
+    /// with [`HolidayAPIError::SchedulerStopped`], as do requests enqueued afterwards.
+    ///
+    /// Applies to every clone of the scheduler.
+    pub fn abort(&self) {
+        self.sender.close_channel();
+        self.task.abort();
+    }
+
+    /// Enqueues a request and returns a future resolving to its parsed response.
+    ///
+    /// The request is queued immediately, the returned future only needs to be awaited to get
+    /// the result.
+    pub fn enqueue<T>(
+        &self,
+        request: Request<T>,
+    ) -> impl Future<Output = Result<T, HolidayAPIError>>
+    where
+        T: Clone + DeserializeOwned + Send + Sync + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let sender = Arc::new(Mutex::new(Some(sender)));
+        let run = move |attempt: u32| -> Attempt {
+            let request = request.clone();
+            let sender = sender.clone();
+            Box::pin(async move {
+                let result = request.clone().get_full().await;
+                let pause = match &result {
+                    Err(HolidayAPIError::RateLimited(retry_after, _, _)) => Some(*retry_after),
+                    Err(HolidayAPIError::BudgetExceeded(resets_in)) => Some(Some(*resets_in)),
+                    _ => None,
+                };
+                if let (Some(pause), Err(err)) = (pause, &result) {
+                    if attempt < MAX_RATE_LIMITED_ATTEMPTS && request.api().retry.is_none() {
+                        request.api().hooks.retry(attempt + 1, err);
+                        return Some(pause);
+                    }
+                }
+                if let Some(sender) = sender.lock().expect("Lock is not poisoned").take() {
+                    let _ = sender.send(result);
+                }
+                None
+            })
+        };
+        let _ = self.sender.unbounded_send(Job {
+            attempts: 0,
+            run: Box::new(run),
+        });
+
+        async move {
+            receiver
+                .await
+                .unwrap_or(Err(HolidayAPIError::SchedulerStopped))
+        }
+    }
+
+    async fn dispatch(
+        rate_limit: RateLimit,
+        runtime: Arc<dyn Runtime>,
+        receiver: mpsc::UnboundedReceiver<Job>,
+        guard: TaskGuard,
+    ) {
+        // Aborting drops the jobs in flight, failing them with `SchedulerStopped`.
+        let _ = guard
+            .until_aborted(Self::run(rate_limit, runtime, receiver))
+            .await;
+    }
+
+    /// Dispatches the jobs until the scheduler stopped accepting them and every job completed.
+    async fn run(
+        rate_limit: RateLimit,
+        runtime: Arc<dyn Runtime>,
+        mut receiver: mpsc::UnboundedReceiver<Job>,
+    ) {
+        let mut retries = VecDeque::new();
+        let mut in_flight = FuturesUnordered::new();
+        let mut pending: Option<Job> = None;
+        let mut open = true;
+        let mut next_slot = Instant::now();
+        loop {
+            if pending.is_none() {
+                pending = retries.pop_front();
+            }
+            if pending.is_none() && !open && in_flight.is_empty() {
+                return;
+            }
+            let event = {
+                let next: BoxFuture<'_, Event> = match (&pending, open) {
+                    (Some(_), _) => {
+                        let wait = next_slot.saturating_duration_since(Instant::now());
+                        Box::pin(runtime.sleep(wait).map(|_| Event::Slot))
+                    }
+                    (None, true) => Box::pin(receiver.next().map(Event::Enqueued)),
+                    (None, false) => Box::pin(future::pending()),
+                };
+                let completed = async {
+                    match in_flight.next().await {
+                        Some((job, retry)) => Event::Completed(job, retry),
+                        None => future::pending().await,
+                    }
+                };
+                match future::select(next, Box::pin(completed)).await {
+                    Either::Left((event, _)) | Either::Right((event, _)) => event,
+                }
+            };
+            match event {
+                Event::Slot => {
+                    let mut job = pending.take().expect("A job is pending");
+                    next_slot = Instant::now() + rate_limit.interval();
+                    job.attempts += 1;
+                    let attempt = (job.run)(job.attempts);
+                    in_flight.push(async move { (job, attempt.await) }.boxed());
+                }
+                Event::Enqueued(Some(job)) => pending = Some(job),
+                Event::Enqueued(None) => open = false,
+                Event::Completed(job, Some(retry_after)) => {
+                    // Pause every queued job, then dispatch the limited one first.
+                    let resume = Instant::now() + retry_after.unwrap_or(rate_limit.per);
+                    next_slot = next_slot.max(resume);
+                    retries.push_front(job);
+                    if let Some(job) = pending.take() {
+                        retries.push_back(job);
+                    }
+                }
+                Event::Completed(_, None) => {}
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use super::*;
+    use crate::{fixtures::Fixture, mock::MockTransport, test_support::EXPIRED_KEY, HolidayAPI};
+
+    #[tokio::test]
+    async fn test_shutdown() {
+        let transport = MockTransport::new(
+            Fixture::named("holidays")
+                .unwrap()
+                .mock_response()
+                .latency(Duration::from_millis(20)),
+        );
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone());
+
+        let scheduler = Scheduler::new(RateLimit::per_second(50));
+        let queued = [
+            scheduler.enqueue(api.holidays("us", 2021)),
+            scheduler.enqueue(api.holidays("jp", 2021)),
+        ];
+        scheduler.shutdown().await;
+        for response in queued {
+            assert!(response.await.is_ok());
+        }
+        assert!(matches!(
+            scheduler.enqueue(api.holidays("us", 2021)).await,
+            Err(HolidayAPIError::SchedulerStopped)
+        ));
+
+        let scheduler = Scheduler::new(RateLimit::per_second(1));
+        let first = scheduler.enqueue(api.holidays("us", 2021));
+        let second = scheduler.enqueue(api.holidays("jp", 2021));
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        scheduler.abort();
+        assert!(matches!(
+            first.await,
+            Err(HolidayAPIError::SchedulerStopped)
+        ));
+        assert!(matches!(
+            second.await,
+            Err(HolidayAPIError::SchedulerStopped)
+        ));
+        assert_eq!(transport.calls(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_slow_responses_do_not_hold_back_the_queue() {
+        let latency = Duration::from_millis(500);
+        let transport = MockTransport::new(
+            Fixture::named("holidays")
+                .unwrap()
+                .mock_response()
+                .latency(latency),
+        );
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone());
+        let scheduler = Scheduler::new(RateLimit::per_second(10));
+
+        let start = tokio::time::Instant::now();
+        let responses: Vec<_> = ["us", "jp", "de"]
+            .into_iter()
+            .map(|country| scheduler.enqueue(api.holidays(country, 2021)))
+            .collect();
+        for response in responses {
+            assert!(response.await.is_ok());
+        }
+        assert!(start.elapsed() < latency * 2);
+        assert_eq!(transport.calls(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_policy_owns_retries() {
+        use crate::{mock::MockResponse, retry::RetryPolicy};
+
+        let transport =
+            MockTransport::new(MockResponse::rate_limited(Some(Duration::from_secs(1))));
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone());
+        let scheduler = Scheduler::new(RateLimit::per_second(10));
+        assert!(matches!(
+            scheduler.enqueue(api.holidays("us", 2021)).await,
+            Err(HolidayAPIError::RateLimited(_, _, _))
+        ));
+        assert_eq!(transport.calls(), MAX_RATE_LIMITED_ATTEMPTS as usize);
+
+        let api = api.clone().retry(&RetryPolicy::new().max_attempts(2));
+        assert!(matches!(
+            scheduler.enqueue(api.holidays("us", 2021)).await,
+            Err(HolidayAPIError::RateLimited(_, _, _))
+        ));
+        assert_eq!(transport.calls(), MAX_RATE_LIMITED_ATTEMPTS as usize + 2);
+    }
+}
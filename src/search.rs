@@ -0,0 +1,191 @@
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+use crate::responses::{
+    CountriesResponse, Country, Holiday, HolidaysResponse, Language, LanguagesResponse,
+};
+
+/// Lowercases and strips accents and punctuation, turning hyphens and underscores into spaces:
+/// `"Côte d'Ivoire"` becomes `"cote divoire"`.
+pub(crate) fn normalize(text: &str) -> String {
+    let folded: String = text
+        .nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .map(|c| if c == '-' || c == '_' { ' ' } else { c })
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect();
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Number of typos tolerated in a normalized query.
+fn tolerance(query: &str) -> usize {
+    match query.chars().count() {
+        0..=2 => 0,
+        length => (length / 4).clamp(1, 3),
+    }
+}
+
+/// Edit distance between two strings, counting insertions, deletions, substitutions and
+/// transpositions of adjacent characters (optimal string alignment).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut rows = vec![(0..=b.len()).collect::<Vec<usize>>()];
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (rows[i - 1][j - 1] + cost)
+                .min(rows[i - 1][j] + 1)
+                .min(row[j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+        rows.push(row);
+    }
+    rows[a.len()][b.len()]
+}
+
+/// How a text matches a normalized query, from the closest match to the furthest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Rank {
+    Exact,
+    Prefix,
+    Substring,
+    /// Number of typos.
+    Typos(usize),
+}
+
+/// Rank of a text for a normalized query, or `None` when it does not match.
+///
+/// Texts equal to, starting with or containing the query rank first, in that order. Otherwise
+/// the query is compared to the whole text and to each run of as many consecutive words as
+/// the query has.
+fn rank(query: &str, text: &str) -> Option<Rank> {
+    let text = normalize(text);
+    if text == query {
+        return Some(Rank::Exact);
+    }
+    if text.starts_with(query) {
+        return Some(Rank::Prefix);
+    }
+    if text.contains(query) {
+        return Some(Rank::Substring);
+    }
+    let words: Vec<&str> = text.split(' ').collect();
+    let length = query.split(' ').count().min(words.len());
+    std::iter::once(text.clone())
+        .chain(words.windows(length).map(|run| run.join(" ")))
+        .map(|candidate| edit_distance(query, &candidate))
+        .min()
+        .filter(|distance| *distance <= tolerance(query))
+        .map(Rank::Typos)
+}
+
+/// Items matching the query on any of their searched fields, closest first.
+fn search<'a, T>(items: &'a [T], query: &str, fields: impl Fn(&T) -> Vec<&str>) -> Vec<&'a T> {
+    let query = normalize(query);
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut matches: Vec<(Rank, &T)> = items
+        .iter()
+        .filter_map(|item| {
+            fields(item)
+                .into_iter()
+                .filter_map(|field| rank(&query, field))
+                .min()
+                .map(|rank| (rank, item))
+        })
+        .collect();
+    matches.sort_by_key(|(rank, _)| *rank);
+    matches.into_iter().map(|(_, item)| item).collect()
+}
+
+impl CountriesResponse {
+    /// Searches countries by name and code, ignoring case and accents and tolerating typos,
+    /// closest matches first. Unlike [`Request::search`](crate::prelude::Request::search), it
+    /// runs locally without a request.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let countries = api.countries().get_full().await.unwrap();
+    ///     let ivory_coast = countries.search_fuzzy("cote divoire");
+    /// }
+    /// ```
+    pub fn search_fuzzy(&self, query: &str) -> Vec<&Country> {
+        search(&self.countries, query, |country: &Country| {
+            vec![&country.name, &country.code, &country.codes.alpha_3]
+        })
+    }
+}
+
+impl LanguagesResponse {
+    /// Searches languages by name and code, see [`CountriesResponse::search_fuzzy`].
+    pub fn search_fuzzy(&self, query: &str) -> Vec<&Language> {
+        search(&self.languages, query, |language: &Language| {
+            vec![&language.name, &language.code]
+        })
+    }
+}
+
+impl HolidaysResponse {
+    /// Searches holidays by name, see [`CountriesResponse::search_fuzzy`].
+    pub fn search_fuzzy(&self, query: &str) -> Vec<&Holiday> {
+        search(&self.holidays, query, |holiday: &Holiday| {
+            vec![&holiday.name]
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::Fixture;
+
+    #[test]
+    fn test_search_fuzzy() {
+        assert_eq!(normalize("  Côte d'Ivoire "), "cote divoire");
+        assert_eq!(normalize("Guinea-Bissau"), "guinea bissau");
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("yaer", "year"), 1);
+
+        let mut countries: CountriesResponse =
+            Fixture::named("countries").unwrap().parse().unwrap();
+        countries.countries[0].name = "Côte d'Ivoire".into();
+        assert_eq!(
+            &*countries.search_fuzzy("cote divoire")[0].name,
+            "Côte d'Ivoire"
+        );
+        assert_eq!(countries.search_fuzzy("COTE DIVIORE").len(), 1);
+        assert!(countries.search_fuzzy("").is_empty());
+
+        let mut russia = countries.countries[0].clone();
+        russia.name = "Russia".into();
+        russia.code = "RU".into();
+        let mut united_states = russia.clone();
+        united_states.name = "United States".into();
+        united_states.code = "US".into();
+        countries.countries = vec![russia, united_states];
+        let ranked = countries.search_fuzzy("us");
+        assert_eq!(&*ranked[0].code, "US");
+        assert!(ranked.iter().any(|country| &*country.name == "Russia"));
+        assert_eq!(rank("united", "United States"), Some(Rank::Prefix));
+        assert_eq!(rank("states", "United States"), Some(Rank::Substring));
+        assert!(rank("united", "Untied States") > rank("states", "United States"));
+
+        let holidays: HolidaysResponse = Fixture::named("holidays").unwrap().parse().unwrap();
+        assert_eq!(holidays.search_fuzzy("new yaer")[0].name, "New Year's Day");
+        assert_eq!(holidays.search_fuzzy("haloween")[0].name, "Halloween");
+        assert!(holidays.search_fuzzy("easter").is_empty());
+
+        let languages: LanguagesResponse = Fixture::named("languages").unwrap().parse().unwrap();
+        let first = &languages.languages[0];
+        assert_eq!(languages.search_fuzzy(&first.name)[0].code, first.code);
+    }
+}
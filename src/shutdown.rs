@@ -0,0 +1,129 @@
+use std::{
+    fmt,
+    future::Future,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc,
+    },
+    task::Poll,
+};
+
+use futures_channel::oneshot;
+use futures_util::{
+    future::{self, Either, FutureExt, Shared},
+    task::AtomicWaker,
+};
+
+/// States of a task after running, which is `0`.
+const SHUTTING_DOWN: u8 = 1;
+const ABORTED: u8 = 2;
+
+/// State of a background task, set by its handles and watched by the task.
+#[derive(Debug, Default)]
+struct Signal {
+    state: AtomicU8,
+    waker: AtomicWaker,
+}
+
+impl Signal {
+    fn raise(&self, state: u8) {
+        self.state.fetch_max(state, Ordering::SeqCst);
+        self.waker.wake();
+    }
+
+    fn is_at_least(&self, state: u8) -> bool {
+        self.state.load(Ordering::SeqCst) >= state
+    }
+}
+
+/// Handle stopping a background task spawned by the crate, shared by the handles of the
+/// component running it, such as the [`Scheduler`](crate::Scheduler).
+#[derive(Clone)]
+pub(crate) struct TaskHandle {
+    signal: Arc<Signal>,
+    done: Shared<oneshot::Receiver<()>>,
+}
+
+impl fmt::Debug for TaskHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskHandle")
+            .field("state", &self.signal.state)
+            .finish()
+    }
+}
+
+/// Side of the task of a [`TaskHandle`], dropped when the task ends.
+#[derive(Debug)]
+pub(crate) struct TaskGuard {
+    signal: Arc<Signal>,
+    _done: oneshot::Sender<()>,
+}
+
+/// Handle and guard of a new background task.
+pub(crate) fn task() -> (TaskHandle, TaskGuard) {
+    let signal = Arc::new(Signal::default());
+    let (sender, receiver) = oneshot::channel();
+    let handle = TaskHandle {
+        signal: signal.clone(),
+        done: receiver.shared(),
+    };
+    (
+        handle,
+        TaskGuard {
+            signal,
+            _done: sender,
+        },
+    )
+}
+
+impl TaskHandle {
+    /// Stops the task at its next await point, dropping the work in flight.
+    pub(crate) fn abort(&self) {
+        self.signal.raise(ABORTED);
+    }
+
+    /// Asks the task to stop once the work in flight is done, and waits for it to end.
+    pub(crate) async fn shutdown(&self) {
+        self.signal.raise(SHUTTING_DOWN);
+        self.finished().await;
+    }
+
+    /// Waits for the task to end.
+    pub(crate) async fn finished(&self) {
+        let _ = self.done.clone().await;
+    }
+}
+
+impl TaskGuard {
+    /// Whether the task was asked to shut down or aborted.
+    pub(crate) fn is_stopping(&self) -> bool {
+        self.signal.is_at_least(SHUTTING_DOWN)
+    }
+
+    /// Runs `future` unless the task is aborted first.
+    pub(crate) async fn until_aborted<T>(&self, future: impl Future<Output = T>) -> Option<T> {
+        self.until(ABORTED, future).await
+    }
+
+    /// Runs `future` unless the task is asked to shut down or aborted first, for waits which
+    /// are not part of the work in flight.
+    pub(crate) async fn until_stopping<T>(&self, future: impl Future<Output = T>) -> Option<T> {
+        self.until(SHUTTING_DOWN, future).await
+    }
+
+    async fn until<T>(&self, state: u8, future: impl Future<Output = T>) -> Option<T> {
+        let signal = &self.signal;
+        let stopped = future::poll_fn(|cx| {
+            signal.waker.register(cx.waker());
+            if signal.is_at_least(state) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        });
+        match future::select(Box::pin(future), Box::pin(stopped)).await {
+            Either::Left((value, _)) => Some(value),
+            Either::Right(_) => None,
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    country::CountryCode, diff::HolidaysDiff, responses::HolidaysResponse, usage, HolidayAPI,
+    HolidayAPIError,
+};
+
+/// Holidays response of a country and year stored in a [`Snapshot`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub country: String,
+    pub year: i32,
+    pub response: HolidaysResponse,
+}
+
+/// Versioned bundle of holidays responses, written with [`HolidayAPI::export_snapshot`] and
+/// read with [`HolidayAPI::load_snapshot`], e.g. to check a reproducible dataset into a
+/// repository.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    /// Revision of the dataset, `1` when exported and incremented by
+    /// [`HolidayAPI::update_snapshot`] whenever its holidays change.
+    #[serde(default)]
+    pub revision: u64,
+    /// Entries sorted by country and year.
+    pub holidays: Vec<SnapshotEntry>,
+}
+
+impl Snapshot {
+    /// Format version written by this library. Snapshots of newer versions cannot be loaded.
+    pub const VERSION: u32 = 1;
+
+    /// Holidays of a country and year, if they are part of the snapshot.
+    pub fn holidays(
+        &self,
+        country: impl Into<CountryCode>,
+        year: i32,
+    ) -> Option<&HolidaysResponse> {
+        let country = country.into();
+        self.holidays
+            .iter()
+            .find(|entry| entry.country == country.as_str() && entry.year == year)
+            .map(|entry| &entry.response)
+    }
+
+    /// Writes the snapshot as JSON through a temporary file renamed over `path`, so that
+    /// readers never see a partially written dataset, see [`usage::write_atomically`].
+    fn write(&self, path: &Path) -> Result<(), HolidayAPIError> {
+        let error = |err: &dyn std::fmt::Display| HolidayAPIError::SnapshotError(err.to_string());
+        let json = serde_json::to_vec_pretty(self).map_err(|err| error(&err))?;
+        usage::write_atomically(path, &json).map_err(|err| error(&err))
+    }
+}
+
+/// Outcome of [`HolidayAPI::update_snapshot`].
+#[derive(Debug, Clone)]
+pub struct SnapshotUpdate {
+    /// Snapshot after the update, as written to the file.
+    pub snapshot: Snapshot,
+    /// Countries and years which were not part of the snapshot.
+    pub added: Vec<(String, i32)>,
+    /// Countries and years whose holidays changed, with the holidays added, removed or moved.
+    ///
+    /// Any change of the holidays counts, such as a new name or `public` flag, even when the
+    /// diff, which only compares dates, is empty.
+    pub changed: Vec<(String, i32, HolidaysDiff)>,
+}
+
+impl SnapshotUpdate {
+    /// Whether the update added or changed holidays, and thus wrote a new revision.
+    pub fn is_changed(&self) -> bool {
+        !self.added.is_empty() || !self.changed.is_empty()
+    }
+}
+
+impl HolidayAPI {
+    /// Fetches the holidays of every country and year combination, through the cache when
+    /// enabled, and writes them to `path` as a JSON [`Snapshot`].
+    ///
+    /// Fails on the first failed request, without writing the file.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     api.export_snapshot("holidays.json", &["us", "jp"], 2021..=2022)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let snapshot = HolidayAPI::load_snapshot("holidays.json").unwrap();
+    ///     let us = snapshot.holidays("us", 2021).unwrap();
+    /// }
+    /// ```
+    pub async fn export_snapshot(
+        &self,
+        path: impl AsRef<Path>,
+        countries: &[&str],
+        years: impl IntoIterator<Item = i32>,
+    ) -> Result<Snapshot, HolidayAPIError> {
+        let mut holidays = Vec::new();
+        for (country, year, request) in self.prefetch_requests(countries, years) {
+            holidays.push(SnapshotEntry {
+                country: CountryCode::new(&country).into(),
+                year,
+                response: request.get_full().await?,
+            });
+        }
+        holidays.sort_by(|a, b| (&a.country, a.year).cmp(&(&b.country, b.year)));
+        let snapshot = Snapshot {
+            version: Snapshot::VERSION,
+            revision: 1,
+            holidays,
+        };
+        snapshot.write(path.as_ref())?;
+        Ok(snapshot)
+    }
+
+    /// Refreshes the snapshot at `path` from the API, for every country and year combination,
+    /// e.g. from a scheduled job keeping an offline dataset current.
+    ///
+    /// The holidays are fetched even if they are cached. Entries of other countries and years
+    /// are kept as is. When holidays were added or changed, the snapshot is written with the
+    /// next [revision](Snapshot::revision), replacing the file at once. Otherwise the file is
+    /// left untouched. A missing file is created.
+    ///
+    /// Fails on the first failed request, without writing the file.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let update = api
+    ///         .update_snapshot("holidays.json", &["us", "jp"], 2021..=2022)
+    ///         .await
+    ///         .unwrap();
+    ///     if update.is_changed() {
+    ///         println!("Wrote revision {}", update.snapshot.revision);
+    ///     }
+    /// }
+    /// ```
+    pub async fn update_snapshot(
+        &self,
+        path: impl AsRef<Path>,
+        countries: &[&str],
+        years: impl IntoIterator<Item = i32>,
+    ) -> Result<SnapshotUpdate, HolidayAPIError> {
+        let path = path.as_ref();
+        let mut snapshot = if path.exists() {
+            HolidayAPI::load_snapshot(path)?
+        } else {
+            Snapshot {
+                version: Snapshot::VERSION,
+                revision: 0,
+                holidays: Vec::new(),
+            }
+        };
+
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+        for (country, year, request) in self.prefetch_requests(countries, years) {
+            let country: String = CountryCode::new(&country).into();
+            let response = request.fetch_full().await?;
+            match snapshot
+                .holidays
+                .iter_mut()
+                .find(|entry| entry.country == country && entry.year == year)
+            {
+                Some(entry) => {
+                    if !same_holidays(&entry.response, &response) {
+                        changed.push((country, year, entry.response.diff(&response)));
+                        entry.response = response;
+                    }
+                }
+                None => {
+                    snapshot.holidays.push(SnapshotEntry {
+                        country: country.clone(),
+                        year,
+                        response,
+                    });
+                    added.push((country, year));
+                }
+            }
+        }
+
+        let mut update = SnapshotUpdate {
+            snapshot,
+            added,
+            changed,
+        };
+        if update.is_changed() {
+            let snapshot = &mut update.snapshot;
+            snapshot
+                .holidays
+                .sort_by(|a, b| (&a.country, a.year).cmp(&(&b.country, b.year)));
+            snapshot.version = Snapshot::VERSION;
+            snapshot.revision += 1;
+            snapshot.write(path)?;
+        }
+        Ok(update)
+    }
+
+    /// Reads a snapshot written by [`HolidayAPI::export_snapshot`].
+    pub fn load_snapshot(path: impl AsRef<Path>) -> Result<Snapshot, HolidayAPIError> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|err| HolidayAPIError::SnapshotError(err.to_string()))?;
+        let snapshot: Snapshot = serde_json::from_str(&json)
+            .map_err(|err| HolidayAPIError::SnapshotError(err.to_string()))?;
+        if snapshot.version > Snapshot::VERSION {
+            return Err(HolidayAPIError::SnapshotError(format!(
+                "unsupported version {}, expected at most {}",
+                snapshot.version,
+                Snapshot::VERSION
+            )));
+        }
+        Ok(snapshot)
+    }
+}
+
+/// Whether two responses hold the same holidays, comparing every field of the holidays but
+/// not the quota usage of the responses.
+fn same_holidays(a: &HolidaysResponse, b: &HolidaysResponse) -> bool {
+    serde_json::to_value(&a.holidays).ok() == serde_json::to_value(&b.holidays).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{
+        fixtures::Fixture,
+        mock::{MockResponse, MockTransport},
+        test_support::EXPIRED_KEY,
+    };
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip() {
+        let fixture = Fixture::named("holidays").unwrap();
+        let transport = MockTransport::new(fixture.mock_response());
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone());
+        let path = std::env::temp_dir().join(format!("holidays-{}.json", std::process::id()));
+
+        let exported = api
+            .export_snapshot(&path, &["jp", "us"], [2022, 2021])
+            .await
+            .unwrap();
+        assert_eq!(transport.calls(), 4);
+        assert_eq!(exported.holidays[0].country, "JP");
+        assert_eq!(exported.holidays[0].year, 2021);
+
+        let snapshot = HolidayAPI::load_snapshot(&path).unwrap();
+        assert_eq!(snapshot.version, Snapshot::VERSION);
+        assert_eq!(snapshot.holidays.len(), 4);
+        assert_eq!(snapshot.holidays("us", 2022).unwrap().holidays.len(), 3);
+        assert!(snapshot.holidays("de", 2021).is_none());
+
+        std::fs::write(&path, r#"{ "version": 99, "holidays": [] }"#).unwrap();
+        assert!(matches!(
+            HolidayAPI::load_snapshot(&path),
+            Err(HolidayAPIError::SnapshotError(_))
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_update_snapshot() {
+        let fixture = Fixture::named("holidays").unwrap();
+        let mut moved: serde_json::Value = fixture.parse().unwrap();
+        moved["holidays"][0]["observed"] = "2021-01-04".into();
+        let mut renamed = moved.clone();
+        renamed["holidays"][0]["name"] = "New Year".into();
+        let transport = MockTransport::new(fixture.mock_response())
+            .on_call(5, MockResponse::new(StatusCode::OK, &moved.to_string()))
+            .on_call(6, MockResponse::new(StatusCode::OK, &renamed.to_string()))
+            .on_call(7, MockResponse::new(StatusCode::OK, &renamed.to_string()));
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+            .with_cache(std::time::Duration::from_secs(60));
+        let path = std::env::temp_dir().join(format!("update-{}.json", std::process::id()));
+
+        let created = api.update_snapshot(&path, &["us"], [2021]).await.unwrap();
+        assert_eq!(created.added, [("US".to_string(), 2021)]);
+        assert_eq!(created.snapshot.revision, 1);
+
+        let unchanged = api.update_snapshot(&path, &["us"], [2021]).await.unwrap();
+        assert!(!unchanged.is_changed());
+        assert_eq!(transport.calls(), 2);
+
+        let extended = api
+            .update_snapshot(&path, &["jp", "us"], [2021])
+            .await
+            .unwrap();
+        assert_eq!(extended.added, [("JP".to_string(), 2021)]);
+        assert_eq!(extended.snapshot.holidays[0].country, "JP");
+
+        let moved = api.update_snapshot(&path, &["us"], [2021]).await.unwrap();
+        assert_eq!(moved.changed.len(), 1);
+        assert_eq!(moved.changed[0].2.modified.len(), 1);
+
+        // A rename is not part of the diff of dates, but is written all the same.
+        let renamed = api.update_snapshot(&path, &["us"], [2021]).await.unwrap();
+        assert_eq!(renamed.changed.len(), 1);
+        assert!(renamed.changed[0].2.is_empty());
+        assert!(!api
+            .update_snapshot(&path, &["us"], [2021])
+            .await
+            .unwrap()
+            .is_changed());
+
+        let snapshot = HolidayAPI::load_snapshot(&path).unwrap();
+        assert_eq!(snapshot.revision, 4);
+        assert_eq!(
+            snapshot.holidays("us", 2021).unwrap().holidays[0].name,
+            "New Year"
+        );
+        assert_eq!(snapshot.holidays.len(), 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
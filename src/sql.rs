@@ -0,0 +1,227 @@
+use sqlx::{Pool, Postgres, Sqlite};
+
+use crate::{
+    responses::{CountriesResponse, Country, Holiday, HolidaysResponse},
+    transport::BoxFuture,
+};
+
+/// Tables storing holidays, countries and subdivisions, valid for both Postgres and SQLite.
+///
+/// Dates are stored as `YYYY-MM-DD` text and languages as comma separated codes.
+pub const SQL_SCHEMA: &str = "\
+CREATE TABLE IF NOT EXISTS holidays (
+    uuid TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    date TEXT NOT NULL,
+    observed TEXT NOT NULL,
+    public BOOLEAN NOT NULL,
+    country TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS countries (
+    code TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    alpha_3 TEXT NOT NULL,
+    numeric TEXT NOT NULL,
+    languages TEXT NOT NULL,
+    flag TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS subdivisions (
+    code TEXT PRIMARY KEY,
+    country TEXT NOT NULL,
+    name TEXT NOT NULL,
+    languages TEXT NOT NULL
+);
+";
+
+const UPSERT_HOLIDAY: &str = "\
+INSERT INTO holidays (uuid, name, date, observed, public, country)
+VALUES ($1, $2, $3, $4, $5, $6)
+ON CONFLICT (uuid) DO UPDATE SET
+    name = excluded.name,
+    date = excluded.date,
+    observed = excluded.observed,
+    public = excluded.public,
+    country = excluded.country";
+
+const UPSERT_COUNTRY: &str = "\
+INSERT INTO countries (code, name, alpha_3, numeric, languages, flag)
+VALUES ($1, $2, $3, $4, $5, $6)
+ON CONFLICT (code) DO UPDATE SET
+    name = excluded.name,
+    alpha_3 = excluded.alpha_3,
+    numeric = excluded.numeric,
+    languages = excluded.languages,
+    flag = excluded.flag";
+
+const UPSERT_SUBDIVISION: &str = "\
+INSERT INTO subdivisions (code, country, name, languages)
+VALUES ($1, $2, $3, $4)
+ON CONFLICT (code) DO UPDATE SET
+    country = excluded.country,
+    name = excluded.name,
+    languages = excluded.languages";
+
+/// Connection pool holidays and countries can be persisted to, implemented for Postgres and
+/// SQLite pools.
+pub trait Store: Send + Sync {
+    /// Creates the tables of [`SQL_SCHEMA`] if they do not exist yet.
+    fn create_schema(&self) -> BoxFuture<'_, Result<(), sqlx::Error>>;
+
+    /// Inserts holidays, replacing those with the same `uuid`, returning the number of rows
+    /// written.
+    fn upsert_holidays<'a>(
+        &'a self,
+        holidays: &'a [Holiday],
+    ) -> BoxFuture<'a, Result<u64, sqlx::Error>>;
+
+    /// Inserts countries and their subdivisions, replacing those with the same code, returning
+    /// the number of rows written.
+    fn upsert_countries<'a>(
+        &'a self,
+        countries: &'a [Country],
+    ) -> BoxFuture<'a, Result<u64, sqlx::Error>>;
+}
+
+macro_rules! impl_store {
+    ($database:ty) => {
+        impl Store for Pool<$database> {
+            fn create_schema(&self) -> BoxFuture<'_, Result<(), sqlx::Error>> {
+                Box::pin(async move {
+                    let mut transaction = self.begin().await?;
+                    for statement in SQL_SCHEMA.split(';').filter(|s| !s.trim().is_empty()) {
+                        sqlx::query(statement).execute(&mut *transaction).await?;
+                    }
+                    transaction.commit().await
+                })
+            }
+
+            fn upsert_holidays<'a>(
+                &'a self,
+                holidays: &'a [Holiday],
+            ) -> BoxFuture<'a, Result<u64, sqlx::Error>> {
+                Box::pin(async move {
+                    let mut transaction = self.begin().await?;
+                    let mut written = 0;
+                    for holiday in holidays {
+                        written += sqlx::query(UPSERT_HOLIDAY)
+                            .bind(&holiday.uuid)
+                            .bind(&holiday.name)
+                            .bind(&holiday.date)
+                            .bind(&holiday.observed)
+                            .bind(holiday.public)
+                            .bind(&holiday.country)
+                            .execute(&mut *transaction)
+                            .await?
+                            .rows_affected();
+                    }
+                    transaction.commit().await?;
+                    Ok(written)
+                })
+            }
+
+            fn upsert_countries<'a>(
+                &'a self,
+                countries: &'a [Country],
+            ) -> BoxFuture<'a, Result<u64, sqlx::Error>> {
+                Box::pin(async move {
+                    let mut transaction = self.begin().await?;
+                    let mut written = 0;
+                    for country in countries {
+                        written += sqlx::query(UPSERT_COUNTRY)
+                            .bind(&*country.code)
+                            .bind(&*country.name)
+                            .bind(&*country.codes.alpha_3)
+                            .bind(&*country.codes.numeric)
+                            .bind(country.languages.join(","))
+                            .bind(&*country.flag)
+                            .execute(&mut *transaction)
+                            .await?
+                            .rows_affected();
+                        for subdivision in &country.subdivisions {
+                            written += sqlx::query(UPSERT_SUBDIVISION)
+                                .bind(&*subdivision.code)
+                                .bind(&*country.code)
+                                .bind(&*subdivision.name)
+                                .bind(subdivision.languages.join(","))
+                                .execute(&mut *transaction)
+                                .await?
+                                .rows_affected();
+                        }
+                    }
+                    transaction.commit().await?;
+                    Ok(written)
+                })
+            }
+        }
+    };
+}
+
+impl_store!(Postgres);
+impl_store!(Sqlite);
+
+impl HolidaysResponse {
+    /// Persists the holidays, upserting them by `uuid`, see [`Store::upsert_holidays`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let pool = sqlx::SqlitePool::connect("sqlite:holidays.db").await.unwrap();
+    ///     pool.create_schema().await.unwrap();
+    ///
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let response = api.holidays("us", 2021).get_full().await.unwrap();
+    ///     response.insert_into(&pool).await.unwrap();
+    /// }
+    /// ```
+    pub async fn insert_into(&self, store: &impl Store) -> Result<u64, sqlx::Error> {
+        store.upsert_holidays(&self.holidays).await
+    }
+}
+
+impl CountriesResponse {
+    /// Persists the countries and their subdivisions, upserting them by code, see
+    /// [`Store::upsert_countries`].
+    pub async fn insert_into(&self, store: &impl Store) -> Result<u64, sqlx::Error> {
+        store.upsert_countries(&self.countries).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::Fixture;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_sqlite_upsert() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        pool.create_schema().await.unwrap();
+
+        let mut holidays: HolidaysResponse = Fixture::named("holidays").unwrap().parse().unwrap();
+        assert_eq!(holidays.insert_into(&pool).await.unwrap(), 3);
+        holidays.holidays[0].observed = "2021-01-04".into();
+        holidays.insert_into(&pool).await.unwrap();
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM holidays")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+        let (observed,): (String,) =
+            sqlx::query_as("SELECT observed FROM holidays WHERE uuid = $1")
+                .bind(&holidays.holidays[0].uuid)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(observed, "2021-01-04");
+
+        let countries: CountriesResponse = Fixture::named("countries").unwrap().parse().unwrap();
+        assert_eq!(countries.insert_into(&pool).await.unwrap(), 5);
+    }
+}
@@ -0,0 +1,112 @@
+use chrono::{Datelike, Month, Weekday};
+
+use crate::{
+    calendar::Weekend,
+    responses::{Holiday, HolidaysResponse},
+};
+
+/// Summary of a set of holidays, for reporting.
+///
+/// Months and weekdays are those of the holidays' `date`, holidays whose date cannot be parsed
+/// are only counted in [`total`](Self::total), [`public`](Self::public) and
+/// [`non_public`](Self::non_public).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HolidayStats {
+    pub total: u32,
+    pub public: u32,
+    pub non_public: u32,
+    /// Holidays per month, January first.
+    pub per_month: [u32; 12],
+    /// Holidays per weekday, Monday first.
+    pub per_weekday: [u32; 7],
+    /// Holidays falling on the weekend.
+    pub on_weekend: u32,
+}
+
+impl HolidayStats {
+    pub fn from_holidays<'a>(
+        holidays: impl IntoIterator<Item = &'a Holiday>,
+        weekend: Weekend,
+    ) -> Self {
+        let mut stats = Self::default();
+        for holiday in holidays {
+            stats.total += 1;
+            if holiday.public {
+                stats.public += 1;
+            } else {
+                stats.non_public += 1;
+            }
+            if let Some(date) = holiday.parse_date() {
+                stats.per_month[date.month0() as usize] += 1;
+                stats.per_weekday[date.weekday().num_days_from_monday() as usize] += 1;
+                if weekend.contains(date.weekday()) {
+                    stats.on_weekend += 1;
+                }
+            }
+        }
+        stats
+    }
+
+    /// Aggregates the holidays of several responses, e.g. several years or countries.
+    pub fn from_responses<'a>(
+        responses: impl IntoIterator<Item = &'a HolidaysResponse>,
+        weekend: Weekend,
+    ) -> Self {
+        Self::from_holidays(
+            responses
+                .into_iter()
+                .flat_map(|response| response.holidays.iter()),
+            weekend,
+        )
+    }
+
+    pub fn month(&self, month: Month) -> u32 {
+        self.per_month[month.number_from_month() as usize - 1]
+    }
+
+    pub fn weekday(&self, weekday: Weekday) -> u32 {
+        self.per_weekday[weekday.num_days_from_monday() as usize]
+    }
+}
+
+impl HolidaysResponse {
+    /// Computes the statistics of the holidays of this response.
+    pub fn stats(&self, weekend: Weekend) -> HolidayStats {
+        HolidayStats::from_holidays(&self.holidays, weekend)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    fn holiday(date: &str, public: bool) -> Holiday {
+        Holiday {
+            public,
+            ..test_support::holiday(date)
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let holidays = [
+            holiday("2021-01-01", true),
+            holiday("2021-01-18", true),
+            holiday("2021-02-14", false),
+            holiday("2021-07-04", true),
+            holiday("invalid", false),
+        ];
+        let stats = HolidayStats::from_holidays(&holidays, Weekend::default());
+        assert_eq!(stats.total, 5);
+        assert_eq!((stats.public, stats.non_public), (3, 2));
+        assert_eq!(stats.month(Month::January), 2);
+        assert_eq!(stats.per_month.iter().sum::<u32>(), 4);
+        assert_eq!(stats.weekday(Weekday::Sun), 2);
+        assert_eq!(stats.on_weekend, 2);
+        assert_eq!(
+            HolidayStats::from_holidays(&holidays, Weekend::friday_saturday()).on_weekend,
+            1
+        );
+    }
+}
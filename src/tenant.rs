@@ -0,0 +1,428 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use http::StatusCode;
+
+use crate::{responses::APIRequests, HolidayAPI, HolidayAPIError};
+
+/// Quota and health of the key of a tenant.
+#[derive(Debug, Clone, Default)]
+struct TenantState {
+    quota: Option<APIRequests>,
+    failures: u32,
+    last_failure: Option<StatusCode>,
+    parked_until: Option<DateTime<Utc>>,
+    /// Deadline of the request probing the key once its parking ended, if one is in flight.
+    probing_until: Option<DateTime<Utc>>,
+}
+
+impl TenantState {
+    fn health(&self, tenant: &str) -> KeyHealth {
+        KeyHealth {
+            tenant: tenant.to_string(),
+            failures: self.failures,
+            last_failure: self.last_failure,
+            parked_until: self.parked_until,
+            quota: self.quota.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Tenant {
+    key: Arc<str>,
+    state: Arc<Mutex<TenantState>>,
+}
+
+/// Health of the key of a tenant, see [`KeyRegistry::health`].
+#[derive(Debug, Clone)]
+pub struct KeyHealth {
+    pub tenant: String,
+    /// Consecutive responses rejecting the key.
+    pub failures: u32,
+    /// Status of the last response rejecting the key: `401` or `403` for an invalid or expired
+    /// key, `402` for an exhausted plan.
+    pub last_failure: Option<StatusCode>,
+    /// End of the parking of the key, if it is parked.
+    pub parked_until: Option<DateTime<Utc>>,
+    /// Quota reported by the last successful response.
+    pub quota: Option<APIRequests>,
+}
+
+impl KeyHealth {
+    /// Whether requests of the tenant fail without being sent at `now`.
+    pub fn is_parked(&self, now: DateTime<Utc>) -> bool {
+        self.parked_until.is_some_and(|until| now < until)
+    }
+}
+
+/// Tenant of a client returned by [`HolidayAPI::for_tenant`], recording the outcome of its
+/// requests.
+#[derive(Debug, Clone)]
+pub(crate) struct TenantHandle {
+    name: Arc<str>,
+    state: Arc<Mutex<TenantState>>,
+    reprobe_after: Duration,
+}
+
+impl TenantHandle {
+    /// Current health of the key of the tenant.
+    pub(crate) fn health(&self) -> KeyHealth {
+        self.state
+            .lock()
+            .expect("Lock is not poisoned")
+            .health(&self.name)
+    }
+
+    /// Fails while the key is parked. Once the parking ends, a single request probes the key,
+    /// and the others keep failing until it succeeds, or until
+    /// [`KeyRegistry::PROBE_TIMEOUT`] passed without an answer.
+    pub(crate) fn check(&self, now: DateTime<Utc>) -> Result<(), HolidayAPIError> {
+        let mut state = self.state.lock().expect("Lock is not poisoned");
+        let parked = |until| Err(HolidayAPIError::KeyParked(self.name.to_string(), until));
+        match (state.parked_until, state.probing_until) {
+            (Some(until), _) if now < until => parked(until),
+            (Some(_), Some(deadline)) if now < deadline => parked(deadline),
+            (Some(_), _) => {
+                state.probing_until = chrono::Duration::from_std(KeyRegistry::PROBE_TIMEOUT)
+                    .ok()
+                    .and_then(|timeout| now.checked_add_signed(timeout));
+                Ok(())
+            }
+            (None, _) => Ok(()),
+        }
+    }
+
+    /// Records a successful response, with the quota it reported if any.
+    pub(crate) fn succeeded(&self, quota: Option<&APIRequests>) {
+        let mut state = self.state.lock().expect("Lock is not poisoned");
+        state.failures = 0;
+        state.parked_until = None;
+        state.probing_until = None;
+        if let Some(quota) = quota {
+            state.quota = Some(quota.clone());
+        }
+    }
+
+    /// Records an error response, parking the key if the response rejected it. Other errors
+    /// end the probe of a parked key without unparking it, so the next request probes again.
+    pub(crate) fn failed(&self, status: StatusCode, now: DateTime<Utc>) {
+        let mut state = self.state.lock().expect("Lock is not poisoned");
+        state.probing_until = None;
+        if !matches!(
+            status,
+            StatusCode::UNAUTHORIZED | StatusCode::PAYMENT_REQUIRED | StatusCode::FORBIDDEN
+        ) {
+            return;
+        }
+        state.failures += 1;
+        state.last_failure = Some(status);
+        state.parked_until = chrono::Duration::from_std(self.reprobe_after)
+            .ok()
+            .and_then(|parking| now.checked_add_signed(parking));
+    }
+}
+
+/// API keys of the tenants of a multi-tenant application, set with
+/// [`HolidayAPI::key_registry`].
+///
+/// [`HolidayAPI::for_tenant`] returns a client sending the requests of a tenant with its own
+/// key and sharing everything else, transport, cache and callbacks included, with the client it
+/// comes from. The quota reported by the API is tracked per tenant, see
+/// [`quota`](Self::quota).
+///
+/// Keys rejected by the API as invalid, expired (`401`, `403`) or exhausted (`402`) are parked:
+/// requests of their tenant fail with [`HolidayAPIError::KeyParked`] without being sent, until
+/// the [`reprobe_after`](Self::reprobe_after) delay elapsed and the next request probes the key
+/// again. Only that request is sent: the others stay parked until the probe succeeds.
+/// [`health`](Self::health) shows which keys are parked.
+///
+/// Clones share their tenants, so tenants can be added or removed while the clients are in use.
+///
+/// # Examples
+/// ```no_run
+/// use holidayapi_rust::prelude::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let registry = KeyRegistry::new()
+///         .insert("acme", "00000000-0000-0000-0000-000000000000")
+///         .unwrap()
+///         .insert("globex", "11111111-1111-1111-1111-111111111111")
+///         .unwrap();
+///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+///         .unwrap()
+///         .key_registry(&registry);
+///
+///     let holidays = api.for_tenant("acme").unwrap().holidays("us", 2021).get().await;
+///     if let Some(requests) = registry.quota("acme") {
+///         println!("acme has {} requests left", requests.available);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeyRegistry {
+    tenants: Arc<RwLock<HashMap<String, Tenant>>>,
+    reprobe_after: Duration,
+}
+
+impl Default for KeyRegistry {
+    fn default() -> Self {
+        Self {
+            tenants: Arc::default(),
+            reprobe_after: Self::DEFAULT_REPROBE_AFTER,
+        }
+    }
+}
+
+impl KeyRegistry {
+    /// Time a rejected key stays parked by default.
+    pub const DEFAULT_REPROBE_AFTER: Duration = Duration::from_secs(3600);
+
+    /// Time the request probing a key whose parking ended has to get an answer, before another
+    /// request probes it instead.
+    pub const PROBE_TIMEOUT: Duration = Duration::from_secs(60);
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the time a rejected key stays parked before being probed again, for the clients
+    /// returned by [`HolidayAPI::for_tenant`] afterwards.
+    pub fn reprobe_after(&mut self, delay: Duration) -> Self {
+        self.reprobe_after = delay;
+        self.to_owned()
+    }
+
+    /// Registers the key of `tenant`, replacing its previous key and resetting its quota and
+    /// health.
+    ///
+    /// # Errors
+    ///
+    /// Will return an `Err` if the key is not plausibly a valid one.
+    pub fn insert(&mut self, tenant: &str, key: &str) -> Result<Self, HolidayAPIError> {
+        HolidayAPI::is_valid_key(key)?;
+        self.tenants.write().expect("Lock is not poisoned").insert(
+            tenant.to_string(),
+            Tenant {
+                key: key.into(),
+                state: Arc::default(),
+            },
+        );
+        Ok(self.to_owned())
+    }
+
+    /// Removes `tenant`, returning whether it was registered. Clients already returned by
+    /// [`HolidayAPI::for_tenant`] keep its key.
+    pub fn remove(&self, tenant: &str) -> bool {
+        self.tenants
+            .write()
+            .expect("Lock is not poisoned")
+            .remove(tenant)
+            .is_some()
+    }
+
+    /// Identifiers of the registered tenants, sorted.
+    pub fn tenants(&self) -> Vec<String> {
+        let mut tenants: Vec<String> = self
+            .tenants
+            .read()
+            .expect("Lock is not poisoned")
+            .keys()
+            .cloned()
+            .collect();
+        tenants.sort();
+        tenants
+    }
+
+    /// Quota of the key of `tenant` reported by the last successful response, if any.
+    pub fn quota(&self, tenant: &str) -> Option<APIRequests> {
+        let tenants = self.tenants.read().expect("Lock is not poisoned");
+        let state = tenants
+            .get(tenant)?
+            .state
+            .lock()
+            .expect("Lock is not poisoned");
+        state.quota.clone()
+    }
+
+    /// Health of the keys of every tenant, sorted by tenant.
+    pub fn health(&self) -> Vec<KeyHealth> {
+        let tenants = self.tenants.read().expect("Lock is not poisoned");
+        let mut health: Vec<KeyHealth> = tenants
+            .iter()
+            .map(|(name, tenant)| {
+                tenant
+                    .state
+                    .lock()
+                    .expect("Lock is not poisoned")
+                    .health(name)
+            })
+            .collect();
+        health.sort_by(|a, b| a.tenant.cmp(&b.tenant));
+        health
+    }
+
+    /// Key and client side state of `tenant`.
+    fn get(&self, tenant: &str) -> Option<(Arc<str>, TenantHandle)> {
+        let tenants = self.tenants.read().expect("Lock is not poisoned");
+        let (name, registered) = tenants.get_key_value(tenant)?;
+        let handle = TenantHandle {
+            name: name.as_str().into(),
+            state: registered.state.clone(),
+            reprobe_after: self.reprobe_after,
+        };
+        Some((registered.key.clone(), handle))
+    }
+}
+
+impl HolidayAPI {
+    /// Sets the registry of the tenant keys used by [`HolidayAPI::for_tenant`].
+    pub fn key_registry(&mut self, registry: &KeyRegistry) -> Self {
+        self.keys = Some(registry.clone());
+        self.to_owned()
+    }
+
+    /// Returns a client sending requests with the key of `tenant`, see [`KeyRegistry`].
+    ///
+    /// # Errors
+    ///
+    /// Will return [`HolidayAPIError::UnknownTenant`] if the tenant is not registered, or if no
+    /// registry was set.
+    pub fn for_tenant(&self, tenant: &str) -> Result<HolidayAPI, HolidayAPIError> {
+        let (key, handle) = self
+            .keys
+            .as_ref()
+            .and_then(|keys| keys.get(tenant))
+            .ok_or_else(|| HolidayAPIError::UnknownTenant(tenant.to_string()))?;
+        let mut api = self.clone();
+        api.key = key;
+        api.tenant = Some(handle);
+        Ok(api)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::{
+        clock::{Clock, FixedClock},
+        mock::{MockResponse, MockTransport},
+        test_support::EXPIRED_KEY,
+    };
+
+    static ACME: &str = "aaaaaaaa-aaaa-aaaa-aaaa-aaaaaaaaaaaa";
+    static GLOBEX: &str = "bbbbbbbb-bbbb-bbbb-bbbb-bbbbbbbbbbbb";
+
+    #[tokio::test]
+    async fn test_key_registry() {
+        let body = r#"{ "status": 200, "requests": { "used": 3, "available": 7, "resets": "" },
+                        "holidays": [] }"#;
+        let transport = MockTransport::new(MockResponse::ok(body));
+        let mut registry = KeyRegistry::new().insert("acme", ACME).unwrap();
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+            .key_registry(&registry);
+        registry.insert("globex", GLOBEX).unwrap();
+        assert!(registry.insert("initech", "invalid").is_err());
+        assert_eq!(registry.tenants(), ["acme", "globex"]);
+
+        assert!(api
+            .for_tenant("acme")
+            .unwrap()
+            .holidays("us", 2021)
+            .get()
+            .await
+            .is_ok());
+        assert!(api
+            .for_tenant("globex")
+            .unwrap()
+            .holidays("jp", 2021)
+            .get()
+            .await
+            .is_ok());
+        let keys: Vec<String> = transport
+            .requests()
+            .iter()
+            .filter_map(|url| {
+                url.query_pairs()
+                    .find(|(name, _)| name == "key")
+                    .map(|(_, key)| key.into_owned())
+            })
+            .collect();
+        assert_eq!(keys, [ACME, GLOBEX]);
+        assert_eq!(registry.quota("acme").unwrap().available, 7);
+
+        assert!(registry.remove("globex"));
+        assert!(registry.quota("globex").is_none());
+        assert!(matches!(
+            api.for_tenant("globex"),
+            Err(HolidayAPIError::UnknownTenant(tenant)) if tenant == "globex"
+        ));
+        assert!(HolidayAPI::new(ACME).unwrap().for_tenant("acme").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_key_health() {
+        let body = r#"{ "status": 200, "requests": { "used": 3, "available": 7, "resets": "" },
+                        "holidays": [] }"#;
+        let transport = MockTransport::new(MockResponse::ok(body)).on_call(
+            1,
+            MockResponse::new(
+                StatusCode::PAYMENT_REQUIRED,
+                r#"{ "status": 402, "error": "Payment required." }"#,
+            ),
+        );
+        let clock = FixedClock(Utc.with_ymd_and_hms(2021, 6, 1, 12, 0, 0).unwrap());
+        let registry = KeyRegistry::new()
+            .reprobe_after(Duration::from_secs(60))
+            .insert("acme", ACME)
+            .unwrap();
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(transport.clone())
+            .clock(clock)
+            .key_registry(&registry);
+        let acme = api.for_tenant("acme").unwrap();
+
+        assert!(acme.holidays("us", 2021).get().await.is_err());
+        let health = &registry.health()[0];
+        assert_eq!(health.tenant, "acme");
+        assert_eq!(health.failures, 1);
+        assert_eq!(health.last_failure, Some(StatusCode::PAYMENT_REQUIRED));
+        assert!(health.is_parked(clock.now()));
+        assert!(matches!(
+            acme.holidays("us", 2021).get().await,
+            Err(HolidayAPIError::KeyParked(tenant, _)) if tenant == "acme"
+        ));
+        assert_eq!(transport.calls(), 1);
+
+        let unparked = FixedClock(clock.now() + chrono::Duration::seconds(60));
+        let handle = acme.tenant.clone().unwrap();
+        handle.check(unparked.now()).unwrap();
+        let err = handle.check(unparked.now()).unwrap_err();
+        assert!(matches!(err, HolidayAPIError::KeyParked(_, until) if until > unparked.now()));
+        assert!(err.is_api() && err.is_quota() && !err.is_network());
+        let timed_out =
+            unparked.now() + chrono::Duration::from_std(KeyRegistry::PROBE_TIMEOUT).unwrap();
+        handle.check(timed_out).unwrap();
+        handle.failed(StatusCode::INTERNAL_SERVER_ERROR, timed_out);
+        handle.check(timed_out).unwrap();
+        handle.failed(StatusCode::INTERNAL_SERVER_ERROR, timed_out);
+        assert!(registry.health()[0].parked_until.is_some());
+
+        let acme = acme.clone().clock(unparked);
+        assert!(acme.holidays("us", 2021).get().await.is_ok());
+        assert!(acme.holidays("us", 2021).get().await.is_ok());
+        let health = &registry.health()[0];
+        assert_eq!((health.failures, health.parked_until), (0, None));
+        assert_eq!(health.quota.as_ref().unwrap().available, 7);
+    }
+}
@@ -0,0 +1,25 @@
+use crate::responses::{Date, Holiday, Weekday};
+
+/// Key of a valid format, rejected by the API as expired.
+pub(crate) const EXPIRED_KEY: &str = "daaaaaab-aaaa-aaaa-aaaa-2aaaada37e14";
+
+/// Public holiday of the US observed on its date, with the date as name and uuid.
+pub(crate) fn holiday(date: &str) -> Holiday {
+    let weekday = Date {
+        name: "".into(),
+        numeric: "".into(),
+    };
+    Holiday {
+        name: date.into(),
+        date: date.into(),
+        observed: date.into(),
+        public: true,
+        country: "US".into(),
+        uuid: date.into(),
+        weekday: Weekday {
+            date: weekday.clone(),
+            observed: weekday,
+        },
+        subdivisions: Vec::new(),
+    }
+}
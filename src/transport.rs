@@ -0,0 +1,586 @@
+use http::{HeaderMap, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    error::Error,
+    fmt::{self, Debug},
+    future::Future,
+    io,
+    pin::Pin,
+    sync::OnceLock,
+};
+use url::Url;
+
+use crate::{dns::Resolver, HolidayAPIError};
+
+/// Boxed future returned by [`Transport`] and [`Runtime`](crate::runtime::Runtime)
+/// implementations.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Error returned by a [`Transport`] when no response could be received.
+pub type TransportError = Box<dyn Error + Send + Sync>;
+
+/// Whether a transport error, or one of its sources, is a timeout of the HTTP client or of
+/// the connection.
+pub(crate) fn is_timeout(err: &(dyn Error + 'static)) -> bool {
+    let mut source = Some(err);
+    while let Some(err) = source {
+        #[cfg(feature = "reqwest")]
+        if err
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(reqwest::Error::is_timeout)
+        {
+            return true;
+        }
+        #[cfg(feature = "hyper")]
+        if err
+            .downcast_ref::<hyper::Error>()
+            .is_some_and(hyper::Error::is_timeout)
+        {
+            return true;
+        }
+        if err
+            .downcast_ref::<io::Error>()
+            .is_some_and(|err| err.kind() == io::ErrorKind::TimedOut)
+        {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// Whether a transport error, or one of its sources, happened before the request reached the
+/// server: DNS resolution, refused connections and failed TLS handshakes.
+pub(crate) fn is_connect(err: &(dyn Error + 'static)) -> bool {
+    let mut source = Some(err);
+    while let Some(err) = source {
+        #[cfg(feature = "reqwest")]
+        if err
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(reqwest::Error::is_connect)
+        {
+            return true;
+        }
+        #[cfg(feature = "hyper")]
+        if err
+            .downcast_ref::<hyper::Error>()
+            .is_some_and(hyper::Error::is_connect)
+        {
+            return true;
+        }
+        if err.downcast_ref::<io::Error>().is_some_and(|err| {
+            matches!(
+                err.kind(),
+                io::ErrorKind::ConnectionRefused
+                    | io::ErrorKind::AddrNotAvailable
+                    | io::ErrorKind::NotFound
+            )
+        }) {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// `GET` request to send to HolidayAPI.
+#[derive(Debug, Clone)]
+pub struct TransportRequest {
+    pub url: Url,
+    pub headers: HeaderMap,
+    /// Longest body accepted, see
+    /// [`HolidayAPI::max_response_size`](crate::HolidayAPI::max_response_size). Transports
+    /// should stop reading longer bodies and fail with [`BodyTooLarge`], the client rejects
+    /// them anyway.
+    pub max_body_size: Option<usize>,
+}
+
+/// Error returned by a [`Transport`] when the body of a response is longer than
+/// [`TransportRequest::max_body_size`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyTooLarge {
+    pub limit: usize,
+}
+
+impl fmt::Display for BodyTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "body longer than {} bytes", self.limit)
+    }
+}
+
+impl Error for BodyTooLarge {}
+
+/// Fails when a body of `length` bytes, or the `Content-Length` of `headers`, exceeds `limit`.
+#[cfg(any(feature = "reqwest", feature = "hyper", feature = "ureq"))]
+fn check_body_size(
+    limit: Option<usize>,
+    headers: &HeaderMap,
+    length: usize,
+) -> Result<(), BodyTooLarge> {
+    let Some(limit) = limit else {
+        return Ok(());
+    };
+    let announced = headers
+        .get(http::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if length > limit || announced.is_some_and(|announced| announced > limit as u64) {
+        return Err(BodyTooLarge { limit });
+    }
+    Ok(())
+}
+
+/// Rate limits of the key sent in the `X-RateLimit-Limit`, `X-RateLimit-Remaining` and
+/// `X-RateLimit-Reset` headers, complementing the `requests` block of the JSON responses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotaInfo {
+    /// Requests allowed in the current window.
+    pub limit: Option<u32>,
+    /// Requests left in the current window.
+    pub remaining: Option<u32>,
+    /// Value of `X-RateLimit-Reset` as sent, in seconds: until the window resets, or the Unix
+    /// timestamp of the reset for servers sending one.
+    pub reset: Option<u64>,
+}
+
+impl QuotaInfo {
+    /// Parses the rate limit headers, or returns `None` when none of them is sent or valid.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        fn header<T: std::str::FromStr>(headers: &HeaderMap, name: &str) -> Option<T> {
+            headers.get(name)?.to_str().ok()?.trim().parse().ok()
+        }
+        let quota = Self {
+            limit: header(headers, "x-ratelimit-limit"),
+            remaining: header(headers, "x-ratelimit-remaining"),
+            reset: header(headers, "x-ratelimit-reset"),
+        };
+        (quota != Self::default()).then_some(quota)
+    }
+}
+
+/// Raw response received from HolidayAPI, independent of the HTTP client which received it.
+///
+/// Returned by [`Request::get_response`](crate::prelude::Request::get_response) and
+/// [`HolidayAPI::custom_request`](crate::HolidayAPI::custom_request) for raw access, so that
+/// upgrading an HTTP client does not change the API of this crate.
+#[derive(Debug, Clone)]
+pub struct Response {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
+    /// Body parsed by the first call to [`Response::field`], or the parse error.
+    json: OnceLock<Result<Value, String>>,
+}
+
+impl Response {
+    pub fn new(status: StatusCode, headers: HeaderMap, body: Vec<u8>) -> Self {
+        Self {
+            status,
+            headers,
+            body,
+            json: OnceLock::new(),
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Numeric status code, such as `429`.
+    pub fn status_code(&self) -> u16 {
+        self.status.as_u16()
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Rate limits sent in the headers of the response, see [`QuotaInfo`].
+    pub fn quota(&self) -> Option<QuotaInfo> {
+        QuotaInfo::from_headers(&self.headers)
+    }
+
+    /// Value of the header `name`, ignoring case, if it is present and valid UTF-8.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name)?.to_str().ok()
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Body of the response, invalid UTF-8 sequences are replaced.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// Body of the response as untyped JSON, to read a few fields without parsing a whole
+    /// response type: `response.json()?["holidays"][0]["name"]`.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`HolidayAPIError::InvalidResponse`] if the body is not JSON.
+    pub fn json(&self) -> Result<Value, HolidayAPIError> {
+        serde_json::from_slice(&self.body)
+            .map_err(|err| HolidayAPIError::InvalidResponse(err.to_string()))
+    }
+
+    /// Field of the JSON body at the [JSON pointer](https://www.rfc-editor.org/rfc/rfc6901)
+    /// `pointer`, such as `/holidays/0/name`, deserialized into `T`, or `None` if the field is
+    /// missing.
+    ///
+    /// The body is parsed on the first call and kept for the next ones.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`HolidayAPIError::InvalidResponse`] if the body is not JSON or the field is
+    /// not a `T`.
+    pub fn field<T: DeserializeOwned>(&self, pointer: &str) -> Result<Option<T>, HolidayAPIError> {
+        let json = self
+            .json
+            .get_or_init(|| self.json().map_err(|err| err.to_string()))
+            .as_ref()
+            .map_err(|err| HolidayAPIError::InvalidResponse(err.clone()))?;
+        match json.pointer(pointer) {
+            Some(field) => T::deserialize(field)
+                .map(Some)
+                .map_err(|err| HolidayAPIError::InvalidResponse(format!("{}: {}", pointer, err))),
+            None => Ok(None),
+        }
+    }
+}
+
+/// HTTP client used to reach HolidayAPI.
+///
+/// The `reqwest` feature, enabled by default, provides `ReqwestTransport`, the `hyper` feature
+/// provides `HyperTransport` and the `ureq` feature provides `UreqTransport`. Other HTTP stacks
+/// can be plugged in with [`HolidayAPI::transport`](crate::HolidayAPI::transport).
+pub trait Transport: Debug + Send + Sync {
+    /// Sends a `GET` request and returns the response, whatever its status.
+    fn get(&self, request: TransportRequest) -> BoxFuture<'_, Result<Response, TransportError>>;
+}
+
+/// Transport used when no HTTP client feature is enabled, failing every request.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoTransport;
+
+impl Transport for NoTransport {
+    fn get(&self, _request: TransportRequest) -> BoxFuture<'_, Result<Response, TransportError>> {
+        Box::pin(async {
+            Err(
+                "no transport configured, enable the `reqwest`, `hyper` or `ureq` feature or \
+                 set one with `HolidayAPI::transport`"
+                    .into(),
+            )
+        })
+    }
+}
+
+/// [`Transport`] backed by a [`reqwest::Client`].
+///
+/// reqwest types only appear in [`ReqwestTransport::new`], responses and errors are converted to
+/// [`Response`] and [`TransportError`].
+#[cfg(feature = "reqwest")]
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest")]
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+
+    /// Transport resolving host names with `resolver`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the client cannot be built, such as when the TLS backend fails
+    /// to initialize.
+    pub fn with_resolver(resolver: &Resolver) -> Result<Self, TransportError> {
+        if resolver.is_system() {
+            return Ok(Self::default());
+        }
+        let client = reqwest::Client::builder()
+            .dns_resolver(std::sync::Arc::new(ReqwestResolver(resolver.clone())))
+            .build()?;
+        Ok(Self::new(client))
+    }
+}
+
+#[cfg(feature = "reqwest")]
+struct ReqwestResolver(Resolver);
+
+#[cfg(feature = "reqwest")]
+impl reqwest::dns::Resolve for ReqwestResolver {
+    fn resolve(&self, name: hyper::client::connect::dns::Name) -> reqwest::dns::Resolving {
+        let resolver = self.0.clone();
+        Box::pin(async move {
+            let addresses = resolver.lookup_blocking(name.as_str(), 0).await?;
+            let addresses: reqwest::dns::Addrs = Box::new(addresses.into_iter());
+            Ok(addresses)
+        })
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl Transport for ReqwestTransport {
+    fn get(&self, request: TransportRequest) -> BoxFuture<'_, Result<Response, TransportError>> {
+        Box::pin(async move {
+            let limit = request.max_body_size;
+            let mut response = self
+                .client
+                .get(request.url)
+                .headers(request.headers)
+                .send()
+                .await?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            check_body_size(limit, &headers, 0)?;
+            let mut body = Vec::new();
+            while let Some(chunk) = response.chunk().await? {
+                body.extend_from_slice(&chunk);
+                check_body_size(limit, &headers, body.len())?;
+            }
+            Ok(Response::new(status, headers, body))
+        })
+    }
+}
+
+/// [`Transport`] backed by a [`hyper::Client`] over HTTPS, for applications already running
+/// hyper.
+#[cfg(feature = "hyper")]
+#[derive(Debug, Clone)]
+pub struct HyperTransport {
+    client: HyperClient,
+}
+
+#[cfg(feature = "hyper")]
+type HyperConnector<R = hyper::client::connect::dns::GaiResolver> =
+    hyper_tls::HttpsConnector<hyper::client::HttpConnector<R>>;
+
+#[cfg(feature = "hyper")]
+#[derive(Debug, Clone)]
+enum HyperClient {
+    System(hyper::Client<HyperConnector>),
+    Resolving(hyper::Client<HyperConnector<HyperResolver>>),
+}
+
+/// [`Resolver`] as a hyper DNS service.
+#[cfg(feature = "hyper")]
+#[derive(Debug, Clone)]
+struct HyperResolver(Resolver);
+
+#[cfg(feature = "hyper")]
+impl hyper::service::Service<hyper::client::connect::dns::Name> for HyperResolver {
+    type Response = std::vec::IntoIter<std::net::SocketAddr>;
+    type Error = std::io::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, name: hyper::client::connect::dns::Name) -> Self::Future {
+        let resolver = self.0.clone();
+        Box::pin(async move {
+            let addresses = resolver.lookup_blocking(name.as_str(), 0).await?;
+            Ok(addresses.into_iter())
+        })
+    }
+}
+
+#[cfg(feature = "hyper")]
+impl Default for HyperTransport {
+    fn default() -> Self {
+        Self::new(hyper::Client::builder().build(hyper_tls::HttpsConnector::new()))
+    }
+}
+
+#[cfg(feature = "hyper")]
+impl HyperTransport {
+    pub fn new(client: hyper::Client<HyperConnector>) -> Self {
+        Self {
+            client: HyperClient::System(client),
+        }
+    }
+
+    /// Transport resolving host names with `resolver`.
+    pub fn with_resolver(resolver: &Resolver) -> Self {
+        if resolver.is_system() {
+            return Self::default();
+        }
+        let mut http =
+            hyper::client::HttpConnector::new_with_resolver(HyperResolver(resolver.clone()));
+        http.enforce_http(false);
+        let connector = hyper_tls::HttpsConnector::new_with_connector(http);
+        Self {
+            client: HyperClient::Resolving(hyper::Client::builder().build(connector)),
+        }
+    }
+}
+
+#[cfg(feature = "hyper")]
+impl Transport for HyperTransport {
+    fn get(&self, request: TransportRequest) -> BoxFuture<'_, Result<Response, TransportError>> {
+        Box::pin(async move {
+            use hyper::body::HttpBody;
+
+            let limit = request.max_body_size;
+            let mut builder = hyper::Request::get(request.url.as_str());
+            if let Some(headers) = builder.headers_mut() {
+                *headers = request.headers;
+            }
+            let request = builder.body(hyper::Body::empty())?;
+            let response = match &self.client {
+                HyperClient::System(client) => client.request(request).await?,
+                HyperClient::Resolving(client) => client.request(request).await?,
+            };
+            let (parts, mut stream) = response.into_parts();
+            check_body_size(limit, &parts.headers, 0)?;
+            let mut body = Vec::new();
+            while let Some(chunk) = stream.data().await {
+                body.extend_from_slice(&chunk?);
+                check_body_size(limit, &parts.headers, body.len())?;
+            }
+            Ok(Response::new(parts.status, parts.headers, body))
+        })
+    }
+}
+
+/// Blocking [`Transport`] backed by a [`ureq::Agent`], for synchronous builds without tokio or
+/// hyper.
+///
+/// Requests are performed as soon as [`Transport::get`] is called, so the returned future is
+/// always ready. Pair it with [`block_on`](crate::block_on) to use the client from synchronous
+/// code.
+///
+/// # Examples
+/// ```no_run
+/// use holidayapi_rust::prelude::*;
+///
+/// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+///     .unwrap()
+///     .transport(UreqTransport::default());
+/// let holidays = block_on(api.holidays("us", 2021).get());
+/// ```
+#[cfg(feature = "ureq")]
+#[derive(Debug, Clone)]
+pub struct UreqTransport {
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "ureq")]
+impl Default for UreqTransport {
+    fn default() -> Self {
+        Self::new(ureq::Agent::new())
+    }
+}
+
+#[cfg(feature = "ureq")]
+impl UreqTransport {
+    pub fn new(agent: ureq::Agent) -> Self {
+        Self { agent }
+    }
+
+    /// Transport resolving host names with `resolver`.
+    pub fn with_resolver(resolver: &Resolver) -> Self {
+        if resolver.is_system() {
+            return Self::default();
+        }
+        let resolver = resolver.clone();
+        let agent = ureq::AgentBuilder::new()
+            .resolver(move |netloc: &str| {
+                let (host, port) = netloc.rsplit_once(':').ok_or_else(|| {
+                    std::io::Error::new(std::io::ErrorKind::InvalidInput, netloc.to_string())
+                })?;
+                let port = port
+                    .parse()
+                    .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, netloc))?;
+                resolver.lookup(host.trim_start_matches('[').trim_end_matches(']'), port)
+            })
+            .build();
+        Self::new(agent)
+    }
+
+    fn send(&self, request: TransportRequest) -> Result<Response, TransportError> {
+        use http::header::{HeaderName, HeaderValue};
+        use std::io::Read;
+
+        let mut call = self.agent.get(request.url.as_str());
+        for (name, value) in request.headers.iter() {
+            call = call.set(name.as_str(), value.to_str()?);
+        }
+        let response = match call.call() {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(err) => return Err(Box::new(err)),
+        };
+
+        let status = StatusCode::from_u16(response.status())?;
+        let mut headers = HeaderMap::new();
+        for name in response.headers_names() {
+            if let Some(value) = response.header(&name) {
+                headers.append(
+                    HeaderName::from_bytes(name.as_bytes())?,
+                    HeaderValue::from_str(value)?,
+                );
+            }
+        }
+        check_body_size(request.max_body_size, &headers, 0)?;
+        let mut body = Vec::new();
+        // One byte over the limit is enough to tell that the body is too long.
+        let readable = request
+            .max_body_size
+            .map_or(u64::MAX, |limit| limit as u64 + 1);
+        response
+            .into_reader()
+            .take(readable)
+            .read_to_end(&mut body)?;
+        check_body_size(request.max_body_size, &headers, body.len())?;
+        Ok(Response::new(status, headers, body))
+    }
+}
+
+#[cfg(feature = "ureq")]
+impl Transport for UreqTransport {
+    fn get(&self, request: TransportRequest) -> BoxFuture<'_, Result<Response, TransportError>> {
+        let response = self.send(request);
+        Box::pin(async move { response })
+    }
+}
+
+/// Transport used by clients unless configured otherwise.
+pub(crate) fn default_transport() -> std::sync::Arc<dyn Transport> {
+    #[cfg(feature = "reqwest")]
+    return std::sync::Arc::new(ReqwestTransport::default());
+    #[cfg(all(feature = "hyper", not(feature = "reqwest")))]
+    return std::sync::Arc::new(HyperTransport::default());
+    #[cfg(all(feature = "ureq", not(any(feature = "reqwest", feature = "hyper"))))]
+    return std::sync::Arc::new(UreqTransport::default());
+    #[cfg(not(any(feature = "reqwest", feature = "hyper", feature = "ureq")))]
+    std::sync::Arc::new(NoTransport)
+}
+
+/// [`default_transport`] resolving host names with `resolver`.
+pub(crate) fn resolving_transport(
+    resolver: &Resolver,
+) -> Result<std::sync::Arc<dyn Transport>, TransportError> {
+    #[cfg(feature = "reqwest")]
+    return Ok(std::sync::Arc::new(ReqwestTransport::with_resolver(
+        resolver,
+    )?));
+    #[cfg(all(feature = "hyper", not(feature = "reqwest")))]
+    return Ok(std::sync::Arc::new(HyperTransport::with_resolver(resolver)));
+    #[cfg(all(feature = "ureq", not(any(feature = "reqwest", feature = "hyper"))))]
+    return Ok(std::sync::Arc::new(UreqTransport::with_resolver(resolver)));
+    #[cfg(not(any(feature = "reqwest", feature = "hyper", feature = "ureq")))]
+    {
+        let _ = resolver;
+        Ok(default_transport())
+    }
+}
@@ -0,0 +1,289 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    budget::Spent, responses::APIRequests, transport::QuotaInfo, HolidayAPI, HolidayAPIError,
+};
+
+/// Writes `bytes` to a temporary file next to `path`, unique to the process and the call, and
+/// renames it over `path`, so that readers never see a partially written file and concurrent
+/// writers never mix their contents. The temporary file is removed if the rename fails.
+pub(crate) fn write_atomically(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    static TEMPORARY: AtomicU64 = AtomicU64::new(0);
+    let mut temporary = PathBuf::from(path).into_os_string();
+    temporary.push(format!(
+        ".{}-{}.tmp",
+        std::process::id(),
+        TEMPORARY.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&temporary, bytes)?;
+    std::fs::rename(&temporary, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&temporary);
+    })
+}
+
+/// Quota usage of a client, saved with [`HolidayAPI::persist_usage`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageState {
+    /// Quota reported by the `requests` block of the last successful response.
+    pub requests: Option<APIRequests>,
+    /// Rate limits sent in the headers of the last response, see [`QuotaInfo`].
+    pub rate_limits: Option<QuotaInfo>,
+    /// Requests spent from the [`Budget`](crate::budget::Budget) of the client.
+    #[serde(default)]
+    budget: Spent,
+}
+
+/// State file written after every response, see [`HolidayAPI::persist_usage`].
+#[derive(Debug, Clone)]
+pub(crate) struct UsageFile {
+    path: Arc<Path>,
+    state: Arc<Mutex<UsageState>>,
+    /// Number of the last recorded state, and of the last one written, so that writes
+    /// finishing out of order never replace a newer state.
+    recorded: Arc<AtomicU64>,
+    written: Arc<Mutex<u64>>,
+}
+
+impl UsageFile {
+    fn load(path: &Path) -> Result<Self, HolidayAPIError> {
+        let state = match std::fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(|err| {
+                HolidayAPIError::UsageFileError(format!("{}: {}", path.display(), err))
+            })?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => UsageState::default(),
+            Err(err) => {
+                return Err(HolidayAPIError::UsageFileError(format!(
+                    "{}: {}",
+                    path.display(),
+                    err
+                )))
+            }
+        };
+        Ok(Self {
+            path: path.into(),
+            state: Arc::new(Mutex::new(state)),
+            recorded: Arc::new(AtomicU64::new(0)),
+            written: Arc::new(Mutex::new(0)),
+        })
+    }
+
+    /// Spending of the budget read from the file or recorded since.
+    pub(crate) fn budget(&self) -> Spent {
+        self.state
+            .lock()
+            .expect("Lock is not poisoned")
+            .budget
+            .clone()
+    }
+
+    /// Records the quota reported by a response and saves the state, logging failures to
+    /// write the file so they do not fail requests.
+    ///
+    /// The file is written on the blocking thread pool when called from a tokio runtime, and
+    /// in place otherwise.
+    pub(crate) fn record(
+        &self,
+        api: &HolidayAPI,
+        requests: Option<&APIRequests>,
+        rate_limits: Option<QuotaInfo>,
+    ) {
+        let (number, bytes) = {
+            let mut state = self.state.lock().expect("Lock is not poisoned");
+            if let Some(requests) = requests {
+                state.requests = Some(requests.clone());
+            }
+            if rate_limits.is_some() {
+                state.rate_limits = rate_limits;
+            }
+            if let Some(budget) = &api.budget {
+                state.budget = budget.spending();
+            }
+            let number = self.recorded.fetch_add(1, Ordering::Relaxed) + 1;
+            (number, serde_json::to_vec(&*state))
+        };
+        let bytes = match bytes {
+            Ok(bytes) => bytes,
+            Err(err) => return self.warn(&err),
+        };
+
+        #[cfg(any(feature = "tokio", feature = "reqwest", feature = "hyper"))]
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let file = self.clone();
+            runtime.spawn_blocking(move || file.save(number, &bytes));
+            return;
+        }
+        self.save(number, &bytes);
+    }
+
+    /// Writes state `number` next to the file and renames it over the file, so that readers
+    /// never see a partially written state. States older than the last written one are
+    /// dropped.
+    fn save(&self, number: u64, bytes: &[u8]) {
+        let mut written = self.written.lock().expect("Lock is not poisoned");
+        if number <= *written {
+            return;
+        }
+        match self.write(bytes) {
+            Ok(()) => *written = number,
+            Err(err) => self.warn(&err),
+        }
+    }
+
+    fn write(&self, bytes: &[u8]) -> std::io::Result<()> {
+        write_atomically(&self.path, bytes)
+    }
+
+    /// Whether every recorded state was written.
+    #[cfg(test)]
+    fn is_saved(&self) -> bool {
+        *self.written.lock().expect("Lock is not poisoned") == self.recorded.load(Ordering::Relaxed)
+    }
+
+    fn warn(&self, err: &dyn std::fmt::Display) {
+        log::warn!(
+            "Failed to save the usage to {}: {}",
+            self.path.display(),
+            err
+        );
+    }
+}
+
+impl HolidayAPI {
+    /// Keeps the quota usage of the client in the JSON file at `path`, so that short-lived
+    /// processes and restarted services know how many requests were used.
+    ///
+    /// The quota reported by the API and the spending of the [`Budget`](crate::budget::Budget)
+    /// are read from the file if it exists, and written to it after every response. The
+    /// budget set with [`HolidayAPI::budget`], before or after, resumes from the saved
+    /// spending.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`HolidayAPIError::UsageFileError`] if the file exists but cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///     .unwrap()
+    ///     .budget(&Budget::new().per_month(9000))
+    ///     .persist_usage("/var/lib/holidays/usage.json")
+    ///     .unwrap();
+    /// if let Some(requests) = api.usage().and_then(|usage| usage.requests) {
+    ///     println!("{} requests left this month", requests.available);
+    /// }
+    /// ```
+    pub fn persist_usage(&mut self, path: impl AsRef<Path>) -> Result<Self, HolidayAPIError> {
+        let file = UsageFile::load(path.as_ref())?;
+        if let Some(budget) = &self.budget {
+            budget.resume(&file.budget());
+        }
+        self.usage = Some(file);
+        Ok(self.to_owned())
+    }
+
+    /// Quota usage kept with [`HolidayAPI::persist_usage`], if enabled.
+    pub fn usage(&self) -> Option<UsageState> {
+        let usage = self.usage.as_ref()?;
+        let state = usage.state.lock().expect("Lock is not poisoned");
+        Some(state.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::{
+        budget::Budget, clock::FixedClock, fixtures::Fixture, mock::MockTransport,
+        test_support::EXPIRED_KEY,
+    };
+
+    /// Waits for the writes of the usage on the blocking thread pool.
+    async fn saved(api: &HolidayAPI) {
+        while !api.usage.as_ref().unwrap().is_saved() {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_persist_usage() {
+        let path = std::env::temp_dir().join(format!("usage-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let now = Utc.with_ymd_and_hms(2021, 6, 1, 12, 0, 0).unwrap();
+        let client = |budget: &Budget| {
+            HolidayAPI::new(EXPIRED_KEY)
+                .unwrap()
+                .transport(MockTransport::new(
+                    Fixture::named("holidays").unwrap().mock_response(),
+                ))
+                .clock(FixedClock(now))
+                .budget(budget)
+                .persist_usage(&path)
+                .unwrap()
+        };
+
+        let api = client(&Budget::new().per_day(2));
+        assert!(api.usage().unwrap().requests.is_none());
+        api.holidays("us", 2021).get().await.unwrap();
+        saved(&api).await;
+        let usage = api.usage().unwrap();
+        assert!(usage.requests.is_some());
+
+        let budget = Budget::new().per_day(2);
+        let api = HolidayAPI::new(EXPIRED_KEY)
+            .unwrap()
+            .transport(MockTransport::new(
+                Fixture::named("holidays").unwrap().mock_response(),
+            ))
+            .clock(FixedClock(now))
+            .persist_usage(&path)
+            .unwrap()
+            .budget(&budget);
+        assert_eq!(budget.spent(now), (1, 1));
+        assert_eq!(
+            api.usage().unwrap().requests.unwrap().used,
+            usage.requests.unwrap().used
+        );
+        api.holidays("us", 2021).get().await.unwrap();
+        saved(&api).await;
+        assert!(matches!(
+            client(&Budget::new().per_day(2))
+                .holidays("us", 2021)
+                .get()
+                .await,
+            Err(HolidayAPIError::BudgetExceeded(_))
+        ));
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(matches!(
+            HolidayAPI::new(EXPIRED_KEY).unwrap().persist_usage(&path),
+            Err(HolidayAPIError::UsageFileError(_))
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_write_atomically_cleans_up() {
+        let directory = std::env::temp_dir().join(format!("atomic-{}", std::process::id()));
+        let target = directory.join("target");
+        std::fs::create_dir_all(&target).unwrap();
+        // A file cannot be renamed over a directory.
+        assert!(write_atomically(&target, b"{}").is_err());
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 1);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
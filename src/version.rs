@@ -0,0 +1,198 @@
+use std::{collections::HashMap, fmt};
+
+use serde::de::DeserializeOwned;
+use url::Url;
+
+use crate::{requests::Endpoint, HolidayAPIError};
+
+/// Version of the HolidayAPI, chosen with
+/// [`HolidayAPI::with_version`](crate::HolidayAPI::with_version).
+///
+/// Everything which differs between versions, the default base url, the shape of the requests
+/// and the decoding of the responses, is decided here, so that new versions are supported
+/// behind the same builder API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum Version {
+    #[default]
+    V1,
+}
+
+impl Version {
+    /// Versions supported by this library.
+    pub const ALL: [Version; 1] = [Version::V1];
+
+    /// Number of the version, as in the `/v1/` path of the API.
+    pub fn number(self) -> i32 {
+        match self {
+            Version::V1 => 1,
+        }
+    }
+
+    /// Base url of the version on `holidayapi.com`.
+    pub fn base_url(self) -> Url {
+        Url::parse(&format!("https://holidayapi.com/{}/", self)).expect("Base url is valid")
+    }
+
+    /// `base_url` moved from the path of the `from` version to the path of this one, `/v1/`
+    /// becoming `/v2/`. Urls without the version at the end of their path are kept.
+    pub(crate) fn rebase(self, base_url: &Url, from: Version) -> Url {
+        let from_path = format!("/{}/", from);
+        match base_url.path().strip_suffix(&from_path[1..]) {
+            Some(prefix) if prefix.ends_with('/') && self != from => {
+                let mut url = base_url.clone();
+                url.set_path(&format!("{}{}/", prefix, self));
+                url
+            }
+            _ => base_url.clone(),
+        }
+    }
+
+    /// Url of a request to `endpoint` on `base_url`, with the key and the parameters sorted by
+    /// name in its query.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`HolidayAPIError::InvalidEndpoint`] if the path of the endpoint is not
+    /// relative or has `..` segments, or [`HolidayAPIError::InvalidBaseUrl`] if it cannot be
+    /// joined to the base url.
+    pub(crate) fn request_url(
+        self,
+        base_url: &Url,
+        endpoint: &Endpoint,
+        key: &str,
+        parameters: &HashMap<String, String>,
+    ) -> Result<Url, HolidayAPIError> {
+        if let Endpoint::Other(path) = endpoint {
+            if path.starts_with(['/', '\\']) || Url::parse(path).is_ok() || has_parent(path) {
+                return Err(HolidayAPIError::InvalidEndpoint(path.clone()));
+            }
+        }
+        match self {
+            Version::V1 => {
+                let mut url = base_url.join(endpoint.path()).map_err(|err| {
+                    HolidayAPIError::InvalidBaseUrl(format!(
+                        "{} joined with {}: {}",
+                        base_url,
+                        endpoint.path(),
+                        err
+                    ))
+                })?;
+                let mut parameters: Vec<(&String, &String)> = parameters.iter().collect();
+                parameters.sort();
+                url.query_pairs_mut()
+                    .append_pair("key", key)
+                    .extend_pairs(parameters);
+                Ok(url)
+            }
+        }
+    }
+
+    /// Decodes a response body of this version into the response types of the library.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`HolidayAPIError::InvalidResponse`] if the body does not match `T`.
+    pub(crate) fn parse<T: DeserializeOwned>(self, body: &str) -> Result<T, HolidayAPIError> {
+        match self {
+            Version::V1 => serde_json::from_str(body)
+                .map_err(|err| HolidayAPIError::InvalidResponse(err.to_string())),
+        }
+    }
+}
+
+/// Whether a relative path has a `..` segment, percent-encoded dots included, which joining it
+/// to a base url would resolve above the path of the version.
+fn has_parent(path: &str) -> bool {
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    path.split(['/', '\\'])
+        .any(|segment| segment.to_ascii_lowercase().replace("%2e", ".") == "..")
+}
+
+impl TryFrom<i32> for Version {
+    type Error = HolidayAPIError;
+
+    fn try_from(number: i32) -> Result<Self, Self::Error> {
+        Self::ALL
+            .into_iter()
+            .find(|version| version.number() == number)
+            .ok_or_else(|| {
+                HolidayAPIError::InvalidVersion(format!(
+                    "Invalid version: {}, please choose: {:?}",
+                    number,
+                    Self::ALL.map(Version::number)
+                ))
+            })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.number())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions() {
+        assert_eq!(Version::try_from(1).unwrap(), Version::V1);
+        assert!(matches!(
+            Version::try_from(2),
+            Err(HolidayAPIError::InvalidVersion(message)) if message.ends_with("[1]")
+        ));
+        assert_eq!(
+            Version::V1.base_url().as_str(),
+            "https://holidayapi.com/v1/"
+        );
+
+        let parameters = HashMap::from([("country".to_string(), "US".to_string())]);
+        let url = Version::V1
+            .request_url(
+                &Version::V1.base_url(),
+                &Endpoint::Holidays,
+                "key",
+                &parameters,
+            )
+            .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://holidayapi.com/v1/holidays?key=key&country=US"
+        );
+
+        let parameters = HashMap::from([
+            ("search".to_string(), "Côte d'Ivoire & 1+1=2?#".to_string()),
+            ("country".to_string(), "CI".to_string()),
+        ]);
+        let url = Version::V1
+            .request_url(
+                &Version::V1.base_url(),
+                &Endpoint::Countries,
+                "key",
+                &parameters,
+            )
+            .unwrap();
+        assert_eq!(
+            url.query(),
+            Some("key=key&country=CI&search=C%C3%B4te+d%27Ivoire+%26+1%2B1%3D2%3F%23")
+        );
+        let pairs: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        assert_eq!(pairs["search"], "Côte d'Ivoire & 1+1=2?#");
+
+        let body: serde_json::Value = Version::V1.parse(r#"{ "status": 200 }"#).unwrap();
+        assert_eq!(body["status"], 200);
+        assert!(matches!(
+            Version::V1.parse::<serde_json::Value>("{"),
+            Err(HolidayAPIError::InvalidResponse(_))
+        ));
+
+        let mirror = Url::parse("https://mirror.internal/holidays/").unwrap();
+        assert_eq!(Version::V1.rebase(&mirror, Version::V1), mirror);
+        assert_eq!(
+            Version::V1.rebase(&Version::V1.base_url(), Version::V1),
+            Version::V1.base_url()
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use futures_util::stream::{self, BoxStream, StreamExt};
+
+#[cfg(feature = "tokio")]
+use crate::runtime::TokioRuntime;
+use crate::{
+    country::CountryCode, diff::HolidayId, responses::Holiday, runtime::Runtime,
+    scheduler::Scheduler, HolidayAPI, HolidayAPIError,
+};
+
+/// Polls the upcoming holidays of a country, created with [`HolidayAPI::watch_upcoming`].
+///
+/// Requests go through the client's cache, and through a [`Scheduler`] when one is set.
+///
+/// The watch spawns no task: polling stops when the stream is dropped.
+#[derive(Debug, Clone)]
+pub struct UpcomingWatch {
+    api: HolidayAPI,
+    country: CountryCode,
+    interval: Duration,
+    window: u32,
+    scheduler: Option<Scheduler>,
+    runtime: Arc<dyn Runtime>,
+}
+
+impl UpcomingWatch {
+    /// Number of days watched by default, today included.
+    pub const DEFAULT_WINDOW: u32 = 30;
+
+    pub(crate) fn new(
+        api: &HolidayAPI,
+        country: CountryCode,
+        interval: Duration,
+        runtime: Arc<dyn Runtime>,
+    ) -> Self {
+        Self {
+            api: api.clone(),
+            country,
+            interval,
+            window: Self::DEFAULT_WINDOW,
+            scheduler: None,
+            runtime,
+        }
+    }
+
+    /// Sets how many days ahead to watch.
+    pub fn window(&mut self, days: u32) -> Self {
+        self.window = days;
+        self.to_owned()
+    }
+
+    /// Dispatches the polling requests through a scheduler, to share its rate limit.
+    pub fn scheduler(&mut self, scheduler: &Scheduler) -> Self {
+        self.scheduler = Some(scheduler.clone());
+        self.to_owned()
+    }
+
+    /// Returns a stream polling the API every `interval`, yielding the holidays which entered
+    /// the window since the previous poll.
+    ///
+    /// The first item holds every holiday already in the window. Polls without new holidays
+    /// yield nothing, failed polls yield their error and the stream keeps polling.
+    pub fn into_stream(self) -> BoxStream<'static, Result<Vec<Holiday>, HolidayAPIError>> {
+        let state = (self, HashSet::new(), true);
+        stream::unfold(state, |(watch, mut seen, mut first)| async move {
+            loop {
+                if !first {
+                    watch.runtime.sleep(watch.interval).await;
+                }
+                first = false;
+                match watch.poll().await {
+                    Ok(holidays) => {
+                        let entered: Vec<Holiday> = holidays
+                            .into_iter()
+                            .filter(|holiday| seen.insert(HolidayId::of(holiday)))
+                            .collect();
+                        if !entered.is_empty() {
+                            return Some((Ok(entered), (watch, seen, first)));
+                        }
+                    }
+                    Err(err) => return Some((Err(err), (watch, seen, first))),
+                }
+            }
+        })
+        .boxed()
+    }
+
+    async fn poll(&self) -> Result<Vec<Holiday>, HolidayAPIError> {
+        let start = self.api.clock.today();
+        let mut holidays = Vec::new();
+        for year in HolidayAPI::window_years(start, self.window) {
+            let request = self.api.holidays(&self.country, year);
+            let response = match &self.scheduler {
+                Some(scheduler) => scheduler.enqueue(request).await?,
+                None => request.get_full().await?,
+            };
+            holidays.extend(response.holidays);
+        }
+        Ok(HolidayAPI::within_window(holidays, start, self.window))
+    }
+}
+
+impl HolidayAPI {
+    /// Watches the upcoming holidays of a country, polling every `interval` on the current
+    /// tokio runtime, see [`UpcomingWatch`].
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    /// ```no_run
+    /// use futures_util::StreamExt;
+    /// use holidayapi_rust::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000")
+    ///         .unwrap()
+    ///         .with_cache(Duration::from_secs(3600));
+    ///     let mut upcoming = api
+    ///         .watch_upcoming("us", Duration::from_secs(3600))
+    ///         .window(7)
+    ///         .into_stream();
+    ///     while let Some(Ok(holidays)) = upcoming.next().await {
+    ///         for holiday in holidays {
+    ///             println!("{} is coming on {}", holiday.name, holiday.date);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn watch_upcoming(
+        &self,
+        country: impl Into<CountryCode>,
+        interval: Duration,
+    ) -> UpcomingWatch {
+        self.watch_upcoming_with_runtime(country, interval, Arc::new(TokioRuntime))
+    }
+
+    /// Watches the upcoming holidays of a country, waiting between polls with the given runtime.
+    pub fn watch_upcoming_with_runtime(
+        &self,
+        country: impl Into<CountryCode>,
+        interval: Duration,
+        runtime: Arc<dyn Runtime>,
+    ) -> UpcomingWatch {
+        UpcomingWatch::new(self, country.into(), interval, runtime)
+    }
+}
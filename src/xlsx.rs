@@ -0,0 +1,415 @@
+use std::fmt::Write;
+
+use chrono::NaiveDate;
+
+use crate::responses::{Holiday, HolidaysResponse};
+
+/// Columns of a sheet of holidays.
+const HEADER: [&str; 8] = [
+    "Name",
+    "Date",
+    "Observed",
+    "Public",
+    "Country",
+    "Subdivisions",
+    "Weekday",
+    "UUID",
+];
+
+/// Cell formats of `xl/styles.xml`: the default one, dates and the bold header.
+const DATE_STYLE: u8 = 1;
+const HEADER_STYLE: u8 = 2;
+
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><fonts count="2"><font><sz val="11"/><name val="Calibri"/></font><font><b/><sz val="11"/><name val="Calibri"/></font></fonts><fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills><borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs><cellXfs count="3"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="14" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/><xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1"/></cellXfs></styleSheet>"#;
+
+impl HolidaysResponse {
+    /// Renders the holidays as an Excel workbook with a single `Holidays` sheet, one row per
+    /// holiday.
+    ///
+    /// Columns: `Name`, `Date` and `Observed` (as Excel dates, the raw string when they cannot
+    /// be parsed), `Public`, `Country`, `Subdivisions` (comma separated), `Weekday` and `UUID`.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let holidays = api.holidays("us", 2021).get_full().await.unwrap();
+    ///     std::fs::write("holidays.xlsx", holidays.to_xlsx()).unwrap();
+    /// }
+    /// ```
+    pub fn to_xlsx(&self) -> Vec<u8> {
+        HolidaysResponse::to_xlsx_sheets([("Holidays", self)])
+    }
+
+    /// Renders several responses, such as the results of [`HolidayAPI::get_many`] for several
+    /// countries, as an Excel workbook with one sheet per response, in order, see
+    /// [`HolidaysResponse::to_xlsx`].
+    ///
+    /// Sheet names are cut to 31 characters, Excel's limit, and the characters Excel rejects in
+    /// them are replaced with spaces. Names used by an earlier sheet, compared
+    /// case-insensitively like Excel does, get a ` (2)`, ` (3)`, ... suffix.
+    ///
+    /// [`HolidayAPI::get_many`]: crate::HolidayAPI::get_many
+    ///
+    /// # Examples
+    ///
+    /// Basic usage
+    ///
+    /// ```no_run
+    /// use holidayapi_rust::prelude::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = HolidayAPI::new("00000000-0000-0000-0000-000000000000").unwrap();
+    ///     let us = api.holidays("us", 2021).get_full().await.unwrap();
+    ///     let gb = api.holidays("gb", 2021).get_full().await.unwrap();
+    ///     let workbook = HolidaysResponse::to_xlsx_sheets([("US", &us), ("GB", &gb)]);
+    ///     std::fs::write("holidays.xlsx", workbook).unwrap();
+    /// }
+    /// ```
+    pub fn to_xlsx_sheets<'a>(
+        sheets: impl IntoIterator<Item = (&'a str, &'a HolidaysResponse)>,
+    ) -> Vec<u8> {
+        let mut names: Vec<String> = Vec::new();
+        let sheets: Vec<_> = sheets
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, response))| {
+                let name = unique_sheet_name(sheet_name(name, i + 1), &names);
+                names.push(name.clone());
+                (name, &response.holidays)
+            })
+            .collect();
+
+        let mut workbook = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>"#,
+        );
+        let mut relationships = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        );
+        let mut content_types = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>"#,
+        );
+        for (i, (name, _)) in sheets.iter().enumerate() {
+            let id = i + 1;
+            let _ = write!(
+                workbook,
+                r#"<sheet name="{}" sheetId="{id}" r:id="rId{id}"/>"#,
+                escape(name)
+            );
+            let _ = write!(
+                relationships,
+                r#"<Relationship Id="rId{id}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{id}.xml"/>"#
+            );
+            let _ = write!(
+                content_types,
+                r#"<Override PartName="/xl/worksheets/sheet{id}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#
+            );
+        }
+        workbook.push_str("</sheets></workbook>");
+        let _ = write!(
+            relationships,
+            r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#,
+            sheets.len() + 1
+        );
+        content_types.push_str("</Types>");
+
+        let mut archive = Zip::default();
+        archive.add("[Content_Types].xml", content_types.as_bytes());
+        archive.add(
+            "_rels/.rels",
+            br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#,
+        );
+        archive.add("xl/workbook.xml", workbook.as_bytes());
+        archive.add("xl/_rels/workbook.xml.rels", relationships.as_bytes());
+        archive.add("xl/styles.xml", STYLES.as_bytes());
+        for (i, (_, holidays)) in sheets.iter().enumerate() {
+            archive.add(
+                &format!("xl/worksheets/sheet{}.xml", i + 1),
+                worksheet(holidays).as_bytes(),
+            );
+        }
+        archive.finish()
+    }
+}
+
+/// Name of the sheet at `position` (from 1), without the characters Excel rejects.
+fn sheet_name(name: &str, position: usize) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '[' | ']' | ':' | '*' | '?' | '/' | '\\' => ' ',
+            c => c,
+        })
+        .take(31)
+        .collect();
+    match name.trim() {
+        "" => format!("Sheet{}", position),
+        name => name.to_string(),
+    }
+}
+
+/// `name`, or `name` with the first ` (n)` suffix making it differ from the `taken` names,
+/// keeping it within 31 characters.
+fn unique_sheet_name(name: String, taken: &[String]) -> String {
+    let is_taken = |name: &str| {
+        let name = name.to_lowercase();
+        taken.iter().any(|taken| taken.to_lowercase() == name)
+    };
+    if !is_taken(&name) {
+        return name;
+    }
+    (2..)
+        .map(|n| {
+            let suffix = format!(" ({})", n);
+            let base: String = name.chars().take(31 - suffix.len()).collect();
+            format!("{}{}", base.trim_end(), suffix)
+        })
+        .find(|name| !is_taken(name))
+        .expect("Suffixes are endless")
+}
+
+fn worksheet(holidays: &[Holiday]) -> String {
+    let mut sheet = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetViews><sheetView workbookViewId="0"><pane ySplit="1" topLeftCell="A2" state="frozen"/></sheetView></sheetViews><sheetData><row r="1">"#,
+    );
+    for (column, title) in HEADER.iter().enumerate() {
+        text_cell(&mut sheet, column, 1, title, HEADER_STYLE);
+    }
+    sheet.push_str("</row>");
+    for (i, holiday) in holidays.iter().enumerate() {
+        let row = i + 2;
+        let _ = write!(sheet, r#"<row r="{}">"#, row);
+        text_cell(&mut sheet, 0, row, &holiday.name, 0);
+        date_cell(&mut sheet, 1, row, &holiday.date, holiday.parse_date());
+        date_cell(
+            &mut sheet,
+            2,
+            row,
+            &holiday.observed,
+            holiday.parse_observed(),
+        );
+        let _ = write!(
+            sheet,
+            r#"<c r="{}" t="b"><v>{}</v></c>"#,
+            reference(3, row),
+            u8::from(holiday.public)
+        );
+        text_cell(&mut sheet, 4, row, &holiday.country, 0);
+        text_cell(&mut sheet, 5, row, &holiday.subdivisions.join(","), 0);
+        text_cell(&mut sheet, 6, row, &holiday.weekday.date.name, 0);
+        text_cell(&mut sheet, 7, row, &holiday.uuid, 0);
+        sheet.push_str("</row>");
+    }
+    sheet.push_str("</sheetData></worksheet>");
+    sheet
+}
+
+/// `A1` reference of a cell, for the few columns of [`HEADER`].
+fn reference(column: usize, row: usize) -> String {
+    format!("{}{}", char::from(b'A' + column as u8), row)
+}
+
+fn text_cell(sheet: &mut String, column: usize, row: usize, text: &str, style: u8) {
+    let _ = write!(
+        sheet,
+        r#"<c r="{}" s="{}" t="inlineStr"><is><t>{}</t></is></c>"#,
+        reference(column, row),
+        style,
+        escape(text)
+    );
+}
+
+/// Writes a date as the serial number Excel stores dates as, or `raw` if it was not parsed.
+fn date_cell(sheet: &mut String, column: usize, row: usize, raw: &str, date: Option<NaiveDate>) {
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap();
+    match date {
+        Some(date) => {
+            let _ = write!(
+                sheet,
+                r#"<c r="{}" s="{}"><v>{}</v></c>"#,
+                reference(column, row),
+                DATE_STYLE,
+                (date - epoch).num_days()
+            );
+        }
+        None => text_cell(sheet, column, row, raw, 0),
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Zip archive of uncompressed entries, the container of an `.xlsx` workbook.
+#[derive(Default)]
+struct Zip {
+    data: Vec<u8>,
+    directory: Vec<u8>,
+    entries: u16,
+}
+
+impl Zip {
+    /// Date of the entries, 1980-01-01 in the MS-DOS format of zip files.
+    const DATE: u16 = 0x21;
+
+    fn add(&mut self, name: &str, content: &[u8]) {
+        let offset = self.data.len() as u32;
+        let crc = crc32fast::hash(content);
+        let size = content.len() as u32;
+        // Local header: signature, version needed, flags, method (stored), time and date.
+        let mut header = Vec::with_capacity(30);
+        header.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        header.extend_from_slice(&20u16.to_le_bytes());
+        header.extend_from_slice(&[0; 6]);
+        header.extend_from_slice(&Self::DATE.to_le_bytes());
+        for value in [crc, size, size] {
+            header.extend_from_slice(&value.to_le_bytes());
+        }
+        header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes());
+
+        // Central directory entry: signature and version made by, then the local header
+        // fields, then the comment length, disk, attributes and offset of the local header.
+        self.directory
+            .extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        self.directory.extend_from_slice(&20u16.to_le_bytes());
+        self.directory.extend_from_slice(&header[4..]);
+        self.directory.extend_from_slice(&[0; 10]);
+        self.directory.extend_from_slice(&offset.to_le_bytes());
+        self.directory.extend_from_slice(name.as_bytes());
+
+        self.data.extend_from_slice(&header);
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(content);
+        self.entries += 1;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let offset = self.data.len() as u32;
+        let size = self.directory.len() as u32;
+        self.data.append(&mut self.directory);
+        self.data.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        self.data.extend_from_slice(&[0; 4]);
+        self.data.extend_from_slice(&self.entries.to_le_bytes());
+        self.data.extend_from_slice(&self.entries.to_le_bytes());
+        self.data.extend_from_slice(&size.to_le_bytes());
+        self.data.extend_from_slice(&offset.to_le_bytes());
+        self.data.extend_from_slice(&0u16.to_le_bytes());
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::Fixture;
+
+    /// Reads the entries of a zip archive from its central directory, checking that they
+    /// match their local headers and their CRC.
+    fn unzip(archive: &[u8]) -> Vec<(String, String)> {
+        let u16_at = |at: usize| u16::from_le_bytes([archive[at], archive[at + 1]]) as usize;
+        let u32_at =
+            |at: usize| u32::from_le_bytes(archive[at..at + 4].try_into().unwrap()) as usize;
+        let end = archive.len() - 22;
+        assert_eq!(u32_at(end), 0x0605_4b50);
+        let count = u16_at(end + 10);
+        let mut at = u32_at(end + 16);
+        assert_eq!(at + u32_at(end + 12), end);
+
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            assert_eq!(u32_at(at), 0x0201_4b50);
+            assert_eq!(u16_at(at + 10), 0, "entries are stored");
+            let (crc, size) = (u32_at(at + 16), u32_at(at + 20));
+            let name_length = u16_at(at + 28);
+            let extra_length = u16_at(at + 30) + u16_at(at + 32);
+            let local = u32_at(at + 42);
+            let name = &archive[at + 46..at + 46 + name_length];
+
+            assert_eq!(u32_at(local), 0x0403_4b50);
+            assert_eq!((u32_at(local + 14), u32_at(local + 22)), (crc, size));
+            let start = local + 30 + u16_at(local + 26) + u16_at(local + 28);
+            assert_eq!(&archive[local + 30..local + 30 + name_length], name);
+            let content = &archive[start..start + size];
+            assert_eq!(crc32fast::hash(content) as usize, crc);
+
+            entries.push((
+                String::from_utf8(name.to_vec()).unwrap(),
+                String::from_utf8(content.to_vec()).unwrap(),
+            ));
+            at += 46 + name_length + extra_length;
+        }
+        entries
+    }
+
+    fn entry<'a>(entries: &'a [(String, String)], name: &str) -> &'a str {
+        &entries.iter().find(|(entry, _)| entry == name).unwrap().1
+    }
+
+    #[test]
+    fn test_to_xlsx() {
+        let holidays: HolidaysResponse = Fixture::named("holidays").unwrap().parse().unwrap();
+        let entries = unzip(&holidays.to_xlsx());
+        assert_eq!(entries.len(), 6);
+        assert_eq!(entries[0].0, "[Content_Types].xml");
+
+        let holiday = &holidays.holidays[0];
+        assert!(entry(&entries, "xl/workbook.xml").contains(r#"<sheet name="Holidays""#));
+        let sheet = entry(&entries, "xl/worksheets/sheet1.xml");
+        assert!(sheet.contains(&escape(&holiday.name)));
+        let serial = (holiday.parse_date().unwrap()
+            - NaiveDate::from_ymd_opt(1899, 12, 30).unwrap())
+        .num_days();
+        assert!(sheet.contains(&format!(r#"s="1"><v>{}</v>"#, serial)));
+
+        let long = "x".repeat(40);
+        let entries = unzip(&HolidaysResponse::to_xlsx_sheets([
+            ("US", &holidays),
+            ("a/b: [2021]", &holidays),
+            ("us", &holidays),
+            (&long, &holidays),
+            (&long, &holidays),
+        ]));
+        assert!(entry(&entries, "xl/worksheets/sheet5.xml").contains(&escape(&holiday.name)));
+        let workbook = entry(&entries, "xl/workbook.xml");
+        for name in [
+            "US",
+            "a b   2021",
+            "us (2)",
+            &"x".repeat(31),
+            &format!("{} (2)", "x".repeat(27)),
+        ] {
+            assert!(
+                workbook.contains(&format!(r#"<sheet name="{}""#, name)),
+                "{}",
+                name
+            );
+        }
+        assert_eq!(sheet_name("", 3), "Sheet3");
+        assert_eq!(sheet_name(&long, 1).len(), 31);
+    }
+}